@@ -146,6 +146,9 @@ async fn handle_socket(mut socket: WebSocket) {
     let mut rx = tx.subscribe();
     info!("📡 WebSocket client subscribed to broadcast channel");
 
+    // Juez identificado en esta conexión (si lo hay)
+    let mut judge: Option<String> = None;
+
     loop {
         tokio::select! {
             msg = rx.recv() => {
@@ -163,10 +166,22 @@ async fn handle_socket(mut socket: WebSocket) {
             incoming = socket.recv() => {
                 match incoming {
                     Some(Ok(Message::Close(_))) | None => break,
-                    Some(Ok(_)) => { /* ignore client messages */ }
+                    Some(Ok(Message::Text(text))) => {
+                        // Solo los mensajes de jueces tienen respuesta; el resto se ignora
+                        if let Some(reply) = crate::judging::handle_client_message(&mut judge, &text) {
+                            if socket.send(Message::Text(reply)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Some(Ok(_)) => { /* ignore other client messages */ }
                     Some(Err(_)) => break,
                 }
             }
         }
     }
+
+    if let Some(judge_id) = judge {
+        crate::judging::disconnect(&judge_id);
+    }
 }
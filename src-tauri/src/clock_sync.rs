@@ -0,0 +1,158 @@
+// Sincronización del reloj del sensor con el reloj del host
+// Estima la hora del host para cada muestra a partir del timestamp de la banda
+
+use std::collections::VecDeque;
+
+// Duración de cada ventana de observación (µs del dispositivo)
+const WINDOW_US: u64 = 2_000_000;
+// Ventanas conservadas para estimar deriva (~60 s)
+const MAX_ANCHORS: usize = 30;
+// Salto hacia atrás que se interpreta como reinicio de la banda (µs)
+const RESET_THRESHOLD_US: u64 = 10_000_000;
+
+/// Convierte el contador u32 de la banda (µs) en un tiempo monotónico u64
+#[derive(Debug, Default)]
+struct DeviceClockUnwrapper {
+    last_raw: Option<u32>,
+    wraps: u64,
+}
+
+impl DeviceClockUnwrapper {
+    fn unwrap(&mut self, raw: u32) -> u64 {
+        if let Some(last) = self.last_raw {
+            // Un salto grande hacia atrás es un desbordamiento del contador
+            if raw < last && last - raw > u32::MAX / 2 {
+                self.wraps += 1;
+            }
+        }
+        self.last_raw = Some(raw);
+        (self.wraps << 32) | raw as u64
+    }
+}
+
+/// Estimador por conexión: hora_host = hora_dispositivo + offset(hora_dispositivo)
+///
+/// La latencia BLE solo puede retrasar la llegada, por lo que el offset mínimo de cada
+/// ventana es la mejor cota del offset real. Con varias ventanas se ajusta una recta
+/// (offset + deriva) por mínimos cuadrados.
+#[derive(Debug, Default)]
+pub struct ClockSyncEstimator {
+    unwrapper: DeviceClockUnwrapper,
+    last_device_us: Option<u64>,
+    window_start_us: Option<u64>,
+    window_min: Option<(u64, i64)>,     // (hora dispositivo, offset mínimo) de la ventana actual
+    anchors: VecDeque<(u64, i64)>,      // Mínimos de ventanas cerradas
+    model: Option<(u64, f64, f64)>,     // (referencia, offset en referencia, pendiente)
+}
+
+impl ClockSyncEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra una muestra y devuelve (hora dispositivo en µs, hora host estimada en ms)
+    pub fn synchronize(&mut self, device_raw_us: u32, received_at_us: u64) -> (u64, u64) {
+        let device_us = self.unwrapper.unwrap(device_raw_us);
+
+        // Reinicio de la banda: descartar el modelo anterior
+        if let Some(last) = self.last_device_us {
+            if device_us + RESET_THRESHOLD_US < last {
+                *self = Self::default();
+                return self.synchronize(device_raw_us, received_at_us);
+            }
+        }
+        self.last_device_us = Some(device_us);
+
+        let offset = received_at_us as i64 - device_us as i64;
+        self.observe(device_us, offset);
+
+        // La muestra no puede haberse tomado después de su llegada
+        let predicted_offset = self.predict_offset(device_us).unwrap_or(offset);
+        let host_us = (device_us as i64 + predicted_offset.min(offset)).max(0) as u64;
+
+        (device_us, host_us / 1000)
+    }
+
+    /// Offset estimado actual en milisegundos (latencia mínima incluida)
+    pub fn offset_ms(&self) -> Option<f64> {
+        let device_us = self.last_device_us?;
+        self.predict_offset(device_us).map(|offset| offset as f64 / 1000.0)
+    }
+
+    /// Deriva estimada del reloj de la banda en partes por millón
+    pub fn drift_ppm(&self) -> Option<f64> {
+        self.model.map(|(_, _, slope)| slope * 1_000_000.0)
+    }
+
+    fn observe(&mut self, device_us: u64, offset: i64) {
+        let window_start = *self.window_start_us.get_or_insert(device_us);
+
+        match self.window_min {
+            Some((_, min_offset)) if min_offset <= offset => {}
+            _ => self.window_min = Some((device_us, offset)),
+        }
+
+        if device_us.saturating_sub(window_start) >= WINDOW_US {
+            if let Some(anchor) = self.window_min.take() {
+                self.anchors.push_back(anchor);
+                if self.anchors.len() > MAX_ANCHORS {
+                    self.anchors.pop_front();
+                }
+                self.refit();
+            }
+            self.window_start_us = Some(device_us);
+        }
+    }
+
+    fn predict_offset(&self, device_us: u64) -> Option<i64> {
+        match self.model {
+            Some((reference, intercept, slope)) => {
+                let dx = device_us as f64 - reference as f64;
+                Some((intercept + slope * dx).round() as i64)
+            }
+            // Sin ventanas cerradas: usar el mínimo observado hasta ahora
+            None => self.window_min.map(|(_, offset)| offset),
+        }
+    }
+
+    /// Ajuste lineal offset = intercept + slope * (t - referencia)
+    fn refit(&mut self) {
+        let Some(&(reference, _)) = self.anchors.front() else {
+            return;
+        };
+
+        if self.anchors.len() < 2 {
+            self.model = self.anchors.front().map(|&(_, offset)| (reference, offset as f64, 0.0));
+            return;
+        }
+
+        let n = self.anchors.len() as f64;
+        let (mut sum_x, mut sum_y, mut sum_xx, mut sum_xy) = (0.0, 0.0, 0.0, 0.0);
+        for &(device_us, offset) in &self.anchors {
+            let x = (device_us - reference) as f64;
+            let y = offset as f64;
+            sum_x += x;
+            sum_y += y;
+            sum_xx += x * x;
+            sum_xy += x * y;
+        }
+
+        let denominator = n * sum_xx - sum_x * sum_x;
+        let slope = if denominator.abs() > f64::EPSILON {
+            (n * sum_xy - sum_x * sum_y) / denominator
+        } else {
+            0.0
+        };
+        let intercept = (sum_y - slope * sum_x) / n;
+
+        self.model = Some((reference, intercept, slope));
+    }
+}
+
+/// Hora actual del host en microsegundos
+pub fn host_time_us() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64
+}
@@ -0,0 +1,247 @@
+// Escaneo BLE continuo en segundo plano con eventos de descubrimiento en vivo
+// Permite que las bandas que se encienden tarde aparezcan sin volver a escanear
+
+use bluest::Device;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::broadcast_ws::ws_broadcast;
+use crate::gatt_profile;
+use crate::simple_ble::{self, BleDevice};
+
+// Configuración del escaneo continuo
+#[derive(Debug, Clone)]
+pub struct ContinuousScanConfig {
+    pub stale_after_ms: u64,        // Tiempo sin anuncios antes de considerar perdido el dispositivo
+    pub aging_interval_ms: u64,     // Frecuencia de revisión de entradas obsoletas
+    pub rssi_delta_threshold: i16,  // Cambio mínimo de RSSI (dBm) para emitir actualización
+    pub restart_delay_ms: u64,      // Espera antes de reiniciar un escaneo interrumpido
+}
+
+impl Default for ContinuousScanConfig {
+    fn default() -> Self {
+        Self {
+            stale_after_ms: 5000,
+            aging_interval_ms: 1000,
+            rssi_delta_threshold: 3,
+            restart_delay_ms: 1000,
+        }
+    }
+}
+
+// Entrada del caché de descubrimiento
+struct DiscoveryEntry {
+    info: BleDevice,
+    device: Device,
+    last_seen: Instant,
+}
+
+// Evento de descubrimiento enviado al frontend y por WebSocket
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeviceDiscoveryEvent {
+    pub kind: String,              // "appeared", "updated", "lost"
+    pub device: BleDevice,
+    pub timestamp: u64,
+}
+
+// Caché global de dispositivos descubiertos (usando device_id como clave)
+static DISCOVERY_CACHE: Lazy<Arc<Mutex<HashMap<String, DiscoveryEntry>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+// Tarea del escaneo continuo en curso
+static SCAN_TASK: Lazy<Mutex<Option<JoinHandle<()>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Indica si el escaneo continuo está activo
+pub fn is_continuous_scan_running() -> bool {
+    SCAN_TASK.lock().unwrap()
+        .as_ref()
+        .map(|task| !task.is_finished())
+        .unwrap_or(false)
+}
+
+/// Inicia el escaneo continuo en segundo plano
+pub fn start_continuous_scan<R: tauri::Runtime>(
+    app_handle: Arc<AppHandle<R>>,
+    config: ContinuousScanConfig,
+) -> Result<(), String> {
+    let mut task_guard = SCAN_TASK.lock().unwrap();
+    if task_guard.as_ref().map(|task| !task.is_finished()).unwrap_or(false) {
+        return Err("El escaneo continuo ya está activo".to_string());
+    }
+
+    info!(stale_after_ms = config.stale_after_ms, "📡 Iniciando escaneo BLE continuo");
+    *task_guard = Some(tokio::spawn(run_continuous_scan(app_handle, config)));
+    Ok(())
+}
+
+/// Detiene el escaneo continuo y limpia el caché
+pub fn stop_continuous_scan() -> Result<(), String> {
+    let task = SCAN_TASK.lock().unwrap().take();
+
+    match task {
+        Some(task_handle) => {
+            task_handle.abort();
+            DISCOVERY_CACHE.lock().unwrap().clear();
+            info!("🛑 Escaneo BLE continuo detenido");
+            Ok(())
+        }
+        None => Err("El escaneo continuo no está activo".to_string()),
+    }
+}
+
+/// Lista de dispositivos actualmente visibles en el escaneo continuo
+pub fn get_discovered_devices() -> Vec<BleDevice> {
+    let cache = DISCOVERY_CACHE.lock().unwrap();
+    cache.values().map(|entry| entry.info.clone()).collect()
+}
+
+/// Busca un dispositivo en el caché de descubrimiento
+/// Evita abrir un segundo escaneo cuando el continuo ya lo encontró
+pub(crate) fn find_cached_device(device_id: &str) -> Option<(Device, String)> {
+    let cache = DISCOVERY_CACHE.lock().unwrap();
+    cache.get(device_id).map(|entry| (entry.device.clone(), entry.info.name.clone()))
+}
+
+/// Loop principal: reinicia el escaneo si el stream termina
+async fn run_continuous_scan<R: tauri::Runtime>(
+    app_handle: Arc<AppHandle<R>>,
+    config: ContinuousScanConfig,
+) {
+    loop {
+        if let Err(e) = scan_until_interrupted(&app_handle, &config).await {
+            error!(error = %e, "❌ Error en escaneo BLE continuo");
+        }
+
+        warn!(restart_delay_ms = config.restart_delay_ms, "⚠️ Escaneo continuo interrumpido, reiniciando");
+        tokio::time::sleep(Duration::from_millis(config.restart_delay_ms)).await;
+    }
+}
+
+/// Ejecuta un escaneo hasta que el stream del adaptador termine
+async fn scan_until_interrupted<R: tauri::Runtime>(
+    app_handle: &Arc<AppHandle<R>>,
+    config: &ContinuousScanConfig,
+) -> Result<(), String> {
+    // Reutilizar el adaptador singleton compartido con las conexiones activas
+    let adapter = simple_ble::get_ble_adapter().await?;
+
+    adapter.wait_available().await
+        .map_err(|e| format!("Error esperando adaptador: {}", e))?;
+
+    let service_filter = gatt_profile::scan_service_filter();
+    let mut scan = adapter.scan(&service_filter).await
+        .map_err(|e| format!("Error iniciando escaneo: {}", e))?;
+
+    let mut aging_interval = tokio::time::interval(Duration::from_millis(config.aging_interval_ms));
+
+    loop {
+        tokio::select! {
+            _ = aging_interval.tick() => {
+                evict_stale_devices(app_handle, config);
+            }
+            discovered = scan.next() => {
+                match discovered {
+                    Some(discovered_device) => {
+                        let Some(ble_device) = simple_ble::build_ble_device(&discovered_device) else {
+                            continue;
+                        };
+                        register_advertisement(app_handle, config, ble_device, discovered_device.device);
+                    }
+                    None => return Err("Stream de escaneo finalizado".to_string()),
+                }
+            }
+        }
+    }
+}
+
+/// Registra un anuncio en el caché y emite appeared/updated según corresponda
+fn register_advertisement<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    config: &ContinuousScanConfig,
+    ble_device: BleDevice,
+    device: Device,
+) {
+    let mut cache = DISCOVERY_CACHE.lock().unwrap();
+
+    let kind = match cache.get_mut(&ble_device.id) {
+        Some(entry) => {
+            entry.last_seen = Instant::now();
+
+            let rssi_changed = match (entry.info.rssi, ble_device.rssi) {
+                (Some(previous), Some(current)) => (current - previous).abs() >= config.rssi_delta_threshold,
+                (previous, current) => previous.is_some() != current.is_some(),
+            };
+            let connectable_changed = entry.info.is_connectable != ble_device.is_connectable;
+
+            if !rssi_changed && !connectable_changed {
+                return; // Sin cambios relevantes
+            }
+
+            entry.info = ble_device.clone();
+            "updated"
+        }
+        None => {
+            info!(device_name = %ble_device.name, device_id = %ble_device.id, "📱 Dispositivo BLE apareció");
+            cache.insert(ble_device.id.clone(), DiscoveryEntry {
+                info: ble_device.clone(),
+                device,
+                last_seen: Instant::now(),
+            });
+            "appeared"
+        }
+    };
+    drop(cache);
+
+    emit_discovery_event(app_handle, kind, ble_device);
+}
+
+/// Elimina del caché los dispositivos sin anuncios recientes
+/// Los dispositivos conectados dejan de anunciarse, por lo que no envejecen
+fn evict_stale_devices<R: tauri::Runtime>(app_handle: &AppHandle<R>, config: &ContinuousScanConfig) {
+    let stale_after = Duration::from_millis(config.stale_after_ms);
+
+    let lost_devices: Vec<BleDevice> = {
+        let mut cache = DISCOVERY_CACHE.lock().unwrap();
+        let stale_ids: Vec<String> = cache.iter()
+            .filter(|(id, entry)| entry.last_seen.elapsed() > stale_after && !simple_ble::is_device_connected(id))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        stale_ids.iter()
+            .filter_map(|id| cache.remove(id))
+            .map(|entry| entry.info)
+            .collect()
+    };
+
+    for ble_device in lost_devices {
+        debug!(device_id = %ble_device.id, "📴 Dispositivo BLE perdido");
+        emit_discovery_event(app_handle, "lost", ble_device);
+    }
+}
+
+/// Emite el evento de descubrimiento al frontend y por WebSocket
+fn emit_discovery_event<R: tauri::Runtime>(app_handle: &AppHandle<R>, kind: &str, device: BleDevice) {
+    let event = DeviceDiscoveryEvent {
+        kind: kind.to_string(),
+        device,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    };
+
+    if let Err(e) = app_handle.emit(&format!("ble-device-{}", kind), &event) {
+        error!(error = %e, "Error emitiendo evento de descubrimiento");
+    }
+
+    ws_broadcast(&serde_json::json!({
+        "type": "device_discovery",
+        "data": event
+    }));
+}
@@ -0,0 +1,110 @@
+// Lectura de Battery Service y Device Information Service estándar
+// Se ejecuta una vez tras establecer la conexión BLE con cada banda
+
+use bluest::{Device, Uuid};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info};
+
+use crate::gatt_profile::bluetooth_uuid;
+
+// Servicios y características estándar del Bluetooth SIG
+const BATTERY_SERVICE_UUID: Uuid = bluetooth_uuid(0x180F);
+const BATTERY_LEVEL_UUID: Uuid = bluetooth_uuid(0x2A19);
+const DEVICE_INFORMATION_SERVICE_UUID: Uuid = bluetooth_uuid(0x180A);
+const MANUFACTURER_NAME_UUID: Uuid = bluetooth_uuid(0x2A29);
+const MODEL_NUMBER_UUID: Uuid = bluetooth_uuid(0x2A24);
+const SERIAL_NUMBER_UUID: Uuid = bluetooth_uuid(0x2A25);
+const HARDWARE_REVISION_UUID: Uuid = bluetooth_uuid(0x2A27);
+const FIRMWARE_REVISION_UUID: Uuid = bluetooth_uuid(0x2A26);
+
+// Información leída de la banda (campos ausentes si la banda no los expone)
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct DeviceInformation {
+    pub battery_level: Option<u8>,          // Porcentaje 0-100
+    pub manufacturer: Option<String>,
+    pub model_number: Option<String>,
+    pub serial_number: Option<String>,
+    pub hardware_revision: Option<String>,
+    pub firmware_revision: Option<String>,
+    pub read_at: u64,                       // Timestamp de la lectura
+}
+
+// Registro global de información por dispositivo (usando device_id como clave)
+static DEVICE_INFO_REGISTRY: Lazy<Arc<Mutex<HashMap<String, DeviceInformation>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Lee Battery Level y Device Information de una banda conectada
+pub async fn read_device_information(device: &Device) -> DeviceInformation {
+    let battery_level = read_characteristic(device, BATTERY_SERVICE_UUID, BATTERY_LEVEL_UUID).await
+        .and_then(|bytes| bytes.first().copied());
+
+    DeviceInformation {
+        battery_level,
+        manufacturer: read_string(device, MANUFACTURER_NAME_UUID).await,
+        model_number: read_string(device, MODEL_NUMBER_UUID).await,
+        serial_number: read_string(device, SERIAL_NUMBER_UUID).await,
+        hardware_revision: read_string(device, HARDWARE_REVISION_UUID).await,
+        firmware_revision: read_string(device, FIRMWARE_REVISION_UUID).await,
+        read_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    }
+}
+
+/// Lee una característica de texto UTF-8 del Device Information Service
+async fn read_string(device: &Device, characteristic_uuid: Uuid) -> Option<String> {
+    let bytes = read_characteristic(device, DEVICE_INFORMATION_SERVICE_UUID, characteristic_uuid).await?;
+    let value = String::from_utf8_lossy(&bytes)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string();
+
+    if value.is_empty() { None } else { Some(value) }
+}
+
+/// Lee el valor crudo de una característica; None si no existe o falla la lectura
+async fn read_characteristic(device: &Device, service_uuid: Uuid, characteristic_uuid: Uuid) -> Option<Vec<u8>> {
+    let services = device.discover_services_with_uuid(service_uuid).await.ok()?;
+
+    for service in &services {
+        let Ok(characteristics) = service.discover_characteristics_with_uuid(characteristic_uuid).await else {
+            continue;
+        };
+
+        for characteristic in characteristics {
+            match characteristic.read().await {
+                Ok(bytes) => return Some(bytes),
+                Err(e) => debug!(uuid = %characteristic_uuid, error = %e, "Error leyendo característica estándar"),
+            }
+        }
+    }
+
+    None
+}
+
+/// Guarda la información leída en el registro
+pub fn store_device_information(device_id: &str, information: DeviceInformation) {
+    info!(device_id = %device_id, battery_level = ?information.battery_level,
+          firmware_revision = ?information.firmware_revision, "🔋 Información de dispositivo registrada");
+
+    let mut registry = DEVICE_INFO_REGISTRY.lock().unwrap();
+    registry.insert(device_id.to_string(), information);
+}
+
+/// Obtiene la información registrada de un dispositivo
+pub fn get_device_information(device_id: &str) -> Option<DeviceInformation> {
+    DEVICE_INFO_REGISTRY.lock().unwrap().get(device_id).cloned()
+}
+
+/// Elimina la información de un dispositivo desconectado
+pub fn remove_device_information(device_id: &str) {
+    DEVICE_INFO_REGISTRY.lock().unwrap().remove(device_id);
+}
+
+/// Limpia todo el registro
+pub fn clear_device_information() {
+    DEVICE_INFO_REGISTRY.lock().unwrap().clear();
+}
@@ -0,0 +1,773 @@
+// Actualización de firmware OTA para las bandas BH
+// Valida la imagen, la transmite por la característica DFU y verifica la versión final
+
+use bluest::{Characteristic, Device};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::device_info;
+use crate::gatt_profile::{BH_DFU_CONTROL_UUID, BH_DFU_DATA_UUID, BH_DFU_SERVICE_UUID};
+use crate::simple_ble;
+
+// Formato de imagen de firmware:
+// [0..4]   magic "BHFW"
+// [4]      versión del encabezado (1)
+// [5..8]   versión de firmware (major, minor, patch)
+// [8..12]  longitud del payload (u32 LE)
+// [12..16] CRC-32 del payload (u32 LE)
+// [16..]   payload
+const IMAGE_MAGIC: &[u8; 4] = b"BHFW";
+const IMAGE_HEADER_VERSION: u8 = 1;
+const IMAGE_HEADER_SIZE: usize = 16;
+
+// Opcodes del punto de control DFU
+const DFU_OP_BEGIN: u8 = 0x01;
+const DFU_OP_COMMIT: u8 = 0x03;
+const DFU_OP_ABORT: u8 = 0x04;
+
+// Bytes del encabezado de cada bloque de datos (offset u32 LE)
+const DFU_CHUNK_HEADER_SIZE: usize = 4;
+
+// Versión de firmware (semántica major.minor.patch)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+pub struct FirmwareVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub patch: u8,
+}
+
+impl FirmwareVersion {
+    /// Interpreta la cadena de Firmware Revision ("1.2.3", "v1.2.3", "1.2.3-beta")
+    pub fn parse(revision: &str) -> Option<Self> {
+        let revision = revision.trim().trim_start_matches(['v', 'V']);
+        let core = revision.split(['-', ' ']).next()?;
+        let mut parts = core.split('.').map(|part| part.parse::<u8>());
+
+        Some(Self {
+            major: parts.next()?.ok()?,
+            minor: parts.next().unwrap_or(Ok(0)).ok()?,
+            patch: parts.next().unwrap_or(Ok(0)).ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+// Imagen de firmware validada
+#[derive(Debug, Clone)]
+pub struct FirmwareImage {
+    pub version: FirmwareVersion,
+    pub payload: Vec<u8>,
+    pub crc32: u32,
+}
+
+impl FirmwareImage {
+    /// Valida encabezado, longitud y CRC de una imagen de firmware
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < IMAGE_HEADER_SIZE {
+            return Err("Imagen de firmware demasiado corta".to_string());
+        }
+        if &bytes[0..4] != IMAGE_MAGIC {
+            return Err("Imagen de firmware inválida: magic incorrecto".to_string());
+        }
+        if bytes[4] != IMAGE_HEADER_VERSION {
+            return Err(format!("Versión de encabezado no soportada: {}", bytes[4]));
+        }
+
+        let version = FirmwareVersion { major: bytes[5], minor: bytes[6], patch: bytes[7] };
+        let payload_len = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize;
+        let expected_crc = u32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]);
+        let payload = &bytes[IMAGE_HEADER_SIZE..];
+
+        if payload.is_empty() {
+            return Err("Imagen de firmware sin payload".to_string());
+        }
+        if payload.len() != payload_len {
+            return Err(format!("Longitud de payload incorrecta: {} bytes, esperados {}", payload.len(), payload_len));
+        }
+
+        let crc32 = crc32(payload);
+        if crc32 != expected_crc {
+            return Err(format!("CRC de firmware incorrecto: {:08x}, esperado {:08x}", crc32, expected_crc));
+        }
+
+        Ok(Self { version, payload: payload.to_vec(), crc32 })
+    }
+
+    pub fn size(&self) -> u32 {
+        self.payload.len() as u32
+    }
+}
+
+/// CRC-32 (IEEE 802.3, polinomio reflejado 0xEDB88320)
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// Estado reportado por el punto de control DFU de la banda
+#[derive(Debug, Clone, Copy)]
+pub struct DfuStatus {
+    pub image_crc32: u32, // CRC de la imagen en curso (0 si no hay transferencia)
+    pub offset: u32,      // Bytes recibidos y persistidos por la banda
+}
+
+// Transporte DFU: BLE real o banda simulada
+pub trait DfuTransport: Send {
+    fn max_chunk_len(&self) -> usize;
+    fn query_status(&mut self) -> impl Future<Output = Result<DfuStatus, String>> + Send;
+    fn begin(&mut self, image: &FirmwareImage) -> impl Future<Output = Result<(), String>> + Send;
+    fn write_chunk(&mut self, offset: u32, data: &[u8]) -> impl Future<Output = Result<(), String>> + Send;
+    fn commit(&mut self) -> impl Future<Output = Result<(), String>> + Send;
+    fn abort(&mut self) -> impl Future<Output = Result<(), String>> + Send;
+    fn reconnect(&mut self) -> impl Future<Output = Result<(), String>> + Send;
+    fn read_firmware_version(&mut self) -> impl Future<Output = Result<Option<String>, String>> + Send;
+}
+
+// Transporte DFU sobre BLE
+pub struct BleDfuTransport {
+    device: Device,
+    control: Characteristic,
+    data: Characteristic,
+}
+
+impl BleDfuTransport {
+    /// Conecta a la banda y descubre las características DFU
+    pub async fn connect(device: Device) -> Result<Self, String> {
+        let adapter = simple_ble::get_ble_adapter().await?;
+        adapter.connect_device(&device).await
+            .map_err(|e| format!("Error conectando: {}", e))?;
+
+        let (control, data) = discover_dfu_characteristics(&device).await?;
+        Ok(Self { device, control, data })
+    }
+}
+
+/// Busca las características de control y datos del servicio DFU
+async fn discover_dfu_characteristics(device: &Device) -> Result<(Characteristic, Characteristic), String> {
+    let services = device.discover_services_with_uuid(BH_DFU_SERVICE_UUID).await
+        .map_err(|e| format!("Error obteniendo servicios: {}", e))?;
+    let service = services.first()
+        .ok_or_else(|| "La banda no expone el servicio DFU".to_string())?;
+
+    let control = service.discover_characteristics_with_uuid(BH_DFU_CONTROL_UUID).await
+        .map_err(|e| format!("Error obteniendo características: {}", e))?
+        .into_iter().next()
+        .ok_or_else(|| "Característica de control DFU no encontrada".to_string())?;
+    let data = service.discover_characteristics_with_uuid(BH_DFU_DATA_UUID).await
+        .map_err(|e| format!("Error obteniendo características: {}", e))?
+        .into_iter().next()
+        .ok_or_else(|| "Característica de datos DFU no encontrada".to_string())?;
+
+    Ok((control, data))
+}
+
+impl DfuTransport for BleDfuTransport {
+    fn max_chunk_len(&self) -> usize {
+        self.data.max_write_len()
+            .map(|len| len.saturating_sub(DFU_CHUNK_HEADER_SIZE))
+            .unwrap_or(16)
+            .max(16)
+    }
+
+    async fn query_status(&mut self) -> Result<DfuStatus, String> {
+        let bytes = self.control.read().await
+            .map_err(|e| format!("Error leyendo estado DFU: {}", e))?;
+        if bytes.len() < 8 {
+            return Err(format!("Estado DFU inválido ({} bytes)", bytes.len()));
+        }
+
+        Ok(DfuStatus {
+            image_crc32: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            offset: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        })
+    }
+
+    async fn begin(&mut self, image: &FirmwareImage) -> Result<(), String> {
+        let mut command = Vec::with_capacity(12);
+        command.push(DFU_OP_BEGIN);
+        command.extend_from_slice(&image.size().to_le_bytes());
+        command.extend_from_slice(&image.crc32.to_le_bytes());
+        command.extend_from_slice(&[image.version.major, image.version.minor, image.version.patch]);
+
+        self.control.write(&command).await
+            .map_err(|e| format!("Error iniciando DFU: {}", e))
+    }
+
+    async fn write_chunk(&mut self, offset: u32, data: &[u8]) -> Result<(), String> {
+        let mut packet = Vec::with_capacity(DFU_CHUNK_HEADER_SIZE + data.len());
+        packet.extend_from_slice(&offset.to_le_bytes());
+        packet.extend_from_slice(data);
+
+        self.data.write(&packet).await
+            .map_err(|e| format!("Error enviando bloque en offset {}: {}", offset, e))
+    }
+
+    async fn commit(&mut self) -> Result<(), String> {
+        self.control.write(&[DFU_OP_COMMIT]).await
+            .map_err(|e| format!("Error confirmando DFU: {}", e))
+    }
+
+    async fn abort(&mut self) -> Result<(), String> {
+        self.control.write(&[DFU_OP_ABORT]).await
+            .map_err(|e| format!("Error abortando DFU: {}", e))
+    }
+
+    async fn reconnect(&mut self) -> Result<(), String> {
+        let adapter = simple_ble::get_ble_adapter().await?;
+        adapter.connect_device(&self.device).await
+            .map_err(|e| format!("Error reconectando: {}", e))?;
+
+        let (control, data) = discover_dfu_characteristics(&self.device).await?;
+        self.control = control;
+        self.data = data;
+        Ok(())
+    }
+
+    async fn read_firmware_version(&mut self) -> Result<Option<String>, String> {
+        Ok(device_info::read_device_information(&self.device).await.firmware_revision)
+    }
+}
+
+// Banda simulada para probar el flujo OTA sin hardware
+// Persiste el offset entre desconexiones como lo hace el bootloader real
+#[cfg(any(test, feature = "ota-simulator"))]
+pub struct SimulatedDfuTransport {
+    firmware_version: FirmwareVersion,
+    pending: Option<(FirmwareVersion, u32, u32)>, // (versión, tamaño, CRC) de la imagen en curso
+    received: Vec<u8>,
+    connected: bool,
+    disconnect_every_chunks: Option<u32>, // Fallos inyectados (solo en pruebas)
+    chunks_written: u32,
+    install_fails: bool,                  // El bootloader descarta la imagen al reiniciar
+}
+
+#[cfg(any(test, feature = "ota-simulator"))]
+impl SimulatedDfuTransport {
+    pub fn new(firmware_version: FirmwareVersion) -> Self {
+        Self {
+            firmware_version,
+            pending: None,
+            received: Vec::new(),
+            connected: true,
+            disconnect_every_chunks: None,
+            chunks_written: 0,
+            install_fails: false,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_disconnect_every(mut self, chunks: u32) -> Self {
+        self.disconnect_every_chunks = Some(chunks);
+        self
+    }
+
+    #[cfg(test)]
+    fn with_failed_install(mut self) -> Self {
+        self.install_fails = true;
+        self
+    }
+
+    fn ensure_connected(&self) -> Result<(), String> {
+        if self.connected { Ok(()) } else { Err("Banda simulada desconectada".to_string()) }
+    }
+}
+
+#[cfg(any(test, feature = "ota-simulator"))]
+impl DfuTransport for SimulatedDfuTransport {
+    fn max_chunk_len(&self) -> usize {
+        240
+    }
+
+    async fn query_status(&mut self) -> Result<DfuStatus, String> {
+        self.ensure_connected()?;
+        Ok(DfuStatus {
+            image_crc32: self.pending.map(|(_, _, crc)| crc).unwrap_or(0),
+            offset: self.received.len() as u32,
+        })
+    }
+
+    async fn begin(&mut self, image: &FirmwareImage) -> Result<(), String> {
+        self.ensure_connected()?;
+        self.pending = Some((image.version, image.size(), image.crc32));
+        self.received.clear();
+        Ok(())
+    }
+
+    async fn write_chunk(&mut self, offset: u32, data: &[u8]) -> Result<(), String> {
+        self.ensure_connected()?;
+        if offset as usize != self.received.len() {
+            return Err(format!("Offset fuera de secuencia: {} (esperado {})", offset, self.received.len()));
+        }
+
+        self.received.extend_from_slice(data);
+        self.chunks_written += 1;
+
+        if let Some(every) = self.disconnect_every_chunks {
+            if self.chunks_written.is_multiple_of(every) {
+                self.connected = false;
+                return Err("Desconexión simulada".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> Result<(), String> {
+        self.ensure_connected()?;
+        let (version, size, crc) = self.pending.take()
+            .ok_or_else(|| "No hay transferencia DFU en curso".to_string())?;
+
+        if self.received.len() as u32 != size || crc32(&self.received) != crc {
+            return Err("La imagen recibida no coincide con el CRC esperado".to_string());
+        }
+
+        // Reinicio tras instalar la nueva imagen
+        if !self.install_fails {
+            self.firmware_version = version;
+        }
+        self.received.clear();
+        self.connected = false;
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> Result<(), String> {
+        self.pending = None;
+        self.received.clear();
+        Ok(())
+    }
+
+    async fn reconnect(&mut self) -> Result<(), String> {
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn read_firmware_version(&mut self) -> Result<Option<String>, String> {
+        self.ensure_connected()?;
+        Ok(Some(self.firmware_version.to_string()))
+    }
+}
+
+// Parámetros del proceso de actualización
+#[derive(Debug, Clone)]
+pub struct OtaConfig {
+    pub max_reconnect_attempts: u32,
+    pub reconnect_delay_ms: u64,
+    pub reboot_wait_ms: u64,      // Espera tras COMMIT para que la banda reinicie
+}
+
+impl Default for OtaConfig {
+    fn default() -> Self {
+        Self {
+            max_reconnect_attempts: 5,
+            reconnect_delay_ms: 2000,
+            reboot_wait_ms: 5000,
+        }
+    }
+}
+
+// Progreso de la actualización enviado al frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FirmwareUpdateProgress {
+    pub device_id: String,
+    pub stage: String,            // "transferring", "resuming", "verifying", "completed", "failed", "cancelled"
+    pub target_version: String,
+    pub bytes_sent: u32,
+    pub total_bytes: u32,
+    pub percent: f32,
+    pub message: Option<String>,
+    pub timestamp: u64,
+}
+
+// Actualizaciones en curso (None = reservada mientras arranca) y último progreso por dispositivo
+type OtaTasks = HashMap<String, Option<JoinHandle<()>>>;
+static OTA_TASKS: Lazy<Arc<Mutex<OtaTasks>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+static OTA_STATUS: Lazy<Arc<Mutex<HashMap<String, FirmwareUpdateProgress>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Lee y valida una imagen de firmware desde disco
+pub fn load_firmware_image(image_path: &str) -> Result<FirmwareImage, String> {
+    let bytes = std::fs::read(image_path)
+        .map_err(|e| format!("Error leyendo imagen {}: {}", image_path, e))?;
+    FirmwareImage::parse(&bytes)
+}
+
+/// Inicia la actualización OTA de una banda
+/// Rechaza bandas asignadas a un combate activo
+pub async fn start_firmware_update<R: tauri::Runtime>(
+    app_handle: Arc<AppHandle<R>>,
+    device_id: String,
+    image_path: String,
+    simulate: bool,
+) -> Result<FirmwareVersion, String> {
+    // Se reserva antes de comprobar para que una conexión al combate simultánea vea la reserva
+    reserve_update(&device_id)?;
+    if simple_ble::is_device_assigned_to_match(&device_id) {
+        OTA_TASKS.lock().unwrap().remove(&device_id);
+        return Err(format!("El dispositivo {} está asignado a un combate activo", device_id));
+    }
+
+    let (target_version, task) = match prepare_update(app_handle, &device_id, &image_path, simulate).await {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            OTA_TASKS.lock().unwrap().remove(&device_id);
+            return Err(e);
+        }
+    };
+
+    // Si se canceló mientras conectaba, la reserva ya no existe
+    match OTA_TASKS.lock().unwrap().get_mut(&device_id) {
+        Some(slot) => *slot = Some(task),
+        None => task.abort(),
+    }
+    Ok(target_version)
+}
+
+// Valida la imagen, conecta con la banda y lanza la transferencia
+async fn prepare_update<R: tauri::Runtime>(
+    app_handle: Arc<AppHandle<R>>,
+    device_id: &str,
+    image_path: &str,
+    simulate: bool,
+) -> Result<(FirmwareVersion, JoinHandle<()>), String> {
+    let image = load_firmware_image(image_path)?;
+    let target_version = image.version;
+    info!(device_id = %device_id, version = %target_version, size = image.size(), "📦 Imagen de firmware validada");
+
+    let config = OtaConfig::default();
+    let task = if simulate {
+        spawn_simulated_update(image, config, device_id.to_string(), app_handle)?
+    } else {
+        let device = simple_ble::find_ble_device(device_id).await?;
+        let transport = BleDfuTransport::connect(device).await?;
+        spawn_update(transport, image, config, device_id.to_string(), app_handle)
+    };
+    Ok((target_version, task))
+}
+
+// Banda simulada: solo en compilaciones de desarrollo, nunca como opción de producción
+#[cfg(feature = "ota-simulator")]
+fn spawn_simulated_update<R: tauri::Runtime>(
+    image: FirmwareImage,
+    config: OtaConfig,
+    device_id: String,
+    app_handle: Arc<AppHandle<R>>,
+) -> Result<JoinHandle<()>, String> {
+    warn!(device_id = %device_id, "🧪 Actualización OTA contra una banda simulada");
+    let transport = SimulatedDfuTransport::new(FirmwareVersion { major: 0, minor: 0, patch: 0 });
+    Ok(spawn_update(transport, image, config, device_id, app_handle))
+}
+
+#[cfg(not(feature = "ota-simulator"))]
+fn spawn_simulated_update<R: tauri::Runtime>(
+    _image: FirmwareImage,
+    _config: OtaConfig,
+    _device_id: String,
+    _app_handle: Arc<AppHandle<R>>,
+) -> Result<JoinHandle<()>, String> {
+    Err("La banda simulada solo existe en compilaciones con la característica ota-simulator".to_string())
+}
+
+/// Cancela una actualización en curso
+pub fn cancel_firmware_update<R: tauri::Runtime>(app_handle: &AppHandle<R>, device_id: &str) -> Result<(), String> {
+    let task = OTA_TASKS.lock().unwrap().remove(device_id)
+        .ok_or_else(|| format!("No hay actualización en curso para {}", device_id))?;
+    if let Some(task) = task {
+        task.abort();
+    }
+
+    let progress = OTA_STATUS.lock().unwrap().get(device_id).cloned();
+    if let Some(mut progress) = progress {
+        progress.stage = "cancelled".to_string();
+        progress.message = Some("Actualización cancelada por el operador".to_string());
+        publish_progress(app_handle, progress);
+    }
+
+    warn!(device_id = %device_id, "🛑 Actualización de firmware cancelada");
+    Ok(())
+}
+
+/// Último progreso conocido de la actualización de un dispositivo
+pub fn get_firmware_update_status(device_id: &str) -> Option<FirmwareUpdateProgress> {
+    OTA_STATUS.lock().unwrap().get(device_id).cloned()
+}
+
+/// Indica si la banda tiene una actualización reservada o transmitiendo
+pub fn is_update_running(device_id: &str) -> bool {
+    OTA_TASKS.lock().unwrap().get(device_id)
+        .is_some_and(|task| task.as_ref().is_none_or(|task| !task.is_finished()))
+}
+
+// Reserva la banda antes del primer await para que dos arranques no transmitan a la vez
+fn reserve_update(device_id: &str) -> Result<(), String> {
+    let mut tasks = OTA_TASKS.lock().unwrap();
+    let running = tasks.get(device_id)
+        .is_some_and(|task| task.as_ref().is_none_or(|task| !task.is_finished()));
+    if running {
+        return Err(format!("Ya hay una actualización en curso para {}", device_id));
+    }
+    tasks.insert(device_id.to_string(), None);
+    Ok(())
+}
+
+/// Lanza la tarea de actualización con el transporte indicado
+fn spawn_update<T: DfuTransport + 'static, R: tauri::Runtime>(
+    mut transport: T,
+    image: FirmwareImage,
+    config: OtaConfig,
+    device_id: String,
+    app_handle: Arc<AppHandle<R>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut reporter = ProgressReporter::new(app_handle, device_id.clone(), &image);
+        let result = run_update(&mut transport, &image, &config, &mut |stage, bytes_sent, message| {
+            reporter.report(stage, bytes_sent, message)
+        }).await;
+
+        match result {
+            Ok(()) => {
+                info!(device_id = %device_id, version = %image.version, "✅ Firmware actualizado");
+                reporter.report("completed", image.size(), None);
+            }
+            Err(e) => {
+                error!(device_id = %device_id, error = %e, "❌ Error actualizando firmware");
+                let _ = transport.abort().await;
+                reporter.report("failed", 0, Some(e));
+            }
+        }
+    })
+}
+
+/// Transfiere la imagen reanudando tras desconexiones y verifica la versión final
+async fn run_update<T: DfuTransport>(
+    transport: &mut T,
+    image: &FirmwareImage,
+    config: &OtaConfig,
+    report: &mut impl FnMut(&str, u32, Option<String>),
+) -> Result<(), String> {
+    // 1. Reanudar si la banda ya tiene parte de esta misma imagen
+    let status = transport.query_status().await?;
+    let mut offset = if status.image_crc32 == image.crc32 && status.offset <= image.size() {
+        info!(offset = status.offset, "⏯️ Reanudando transferencia DFU");
+        status.offset
+    } else {
+        transport.begin(image).await?;
+        0
+    };
+
+    // 2. Transferir bloques
+    let chunk_len = transport.max_chunk_len();
+    let mut reconnect_attempts = 0;
+
+    while offset < image.size() {
+        let end = (offset as usize + chunk_len).min(image.payload.len());
+        let chunk = &image.payload[offset as usize..end];
+
+        match transport.write_chunk(offset, chunk).await {
+            Ok(()) => {
+                offset = end as u32;
+                reconnect_attempts = 0;
+                report("transferring", offset, None);
+            }
+            Err(e) => {
+                reconnect_attempts += 1;
+                if reconnect_attempts > config.max_reconnect_attempts {
+                    return Err(format!("Transferencia interrumpida: {}", e));
+                }
+
+                warn!(offset = offset, attempt = reconnect_attempts, error = %e, "⚠️ Transferencia DFU interrumpida");
+                report("resuming", offset, Some(e));
+                tokio::time::sleep(Duration::from_millis(config.reconnect_delay_ms)).await;
+
+                if transport.reconnect().await.is_ok() {
+                    // La banda indica cuántos bytes persistió
+                    if let Ok(status) = transport.query_status().await {
+                        if status.image_crc32 == image.crc32 && status.offset <= image.size() {
+                            offset = status.offset;
+                        } else {
+                            transport.begin(image).await?;
+                            offset = 0;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // 3. Confirmar e instalar
+    report("verifying", offset, None);
+    transport.commit().await?;
+    tokio::time::sleep(Duration::from_millis(config.reboot_wait_ms)).await;
+
+    // 4. Verificar versión tras el reinicio
+    transport.reconnect().await?;
+    let reported = transport.read_firmware_version().await?
+        .ok_or_else(|| "La banda no reporta versión de firmware".to_string())?;
+
+    match FirmwareVersion::parse(&reported) {
+        Some(version) if version == image.version => Ok(()),
+        _ => Err(format!("La banda reporta la versión {}, se esperaba {}", reported, image.version)),
+    }
+}
+
+// Emite el progreso al frontend y lo guarda como último estado
+struct ProgressReporter<R: tauri::Runtime> {
+    app_handle: Arc<AppHandle<R>>,
+    device_id: String,
+    target_version: String,
+    total_bytes: u32,
+    last_percent: f32, // Último porcentaje publicado en esta actualización
+}
+
+impl<R: tauri::Runtime> ProgressReporter<R> {
+    fn new(app_handle: Arc<AppHandle<R>>, device_id: String, image: &FirmwareImage) -> Self {
+        Self {
+            app_handle,
+            device_id,
+            target_version: image.version.to_string(),
+            total_bytes: image.size(),
+            last_percent: 0.0,
+        }
+    }
+
+    fn report(&mut self, stage: &str, bytes_sent: u32, message: Option<String>) {
+        // Limitar eventos de transferencia a cada 1% para no saturar el frontend
+        let percent = bytes_sent as f32 * 100.0 / self.total_bytes as f32;
+        if stage == "transferring" && percent - self.last_percent < 1.0 && bytes_sent < self.total_bytes {
+            return;
+        }
+        self.last_percent = percent;
+
+        publish_progress(&self.app_handle, FirmwareUpdateProgress {
+            device_id: self.device_id.clone(),
+            stage: stage.to_string(),
+            target_version: self.target_version.clone(),
+            bytes_sent,
+            total_bytes: self.total_bytes,
+            percent,
+            message,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        });
+    }
+}
+
+fn publish_progress<R: tauri::Runtime>(app_handle: &AppHandle<R>, progress: FirmwareUpdateProgress) {
+    if let Err(e) = app_handle.emit("firmware-update-progress", &progress) {
+        error!(error = %e, "Error emitiendo progreso de firmware");
+    }
+    OTA_STATUS.lock().unwrap().insert(progress.device_id.clone(), progress);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERSION: FirmwareVersion = FirmwareVersion { major: 1, minor: 2, patch: 3 };
+
+    fn image_bytes(payload: &[u8]) -> Vec<u8> {
+        let mut bytes = IMAGE_MAGIC.to_vec();
+        bytes.push(IMAGE_HEADER_VERSION);
+        bytes.extend_from_slice(&[VERSION.major, VERSION.minor, VERSION.patch]);
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&crc32(payload).to_le_bytes());
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn test_image() -> FirmwareImage {
+        let payload: Vec<u8> = (0..5000u32).map(|i| (i % 251) as u8).collect();
+        FirmwareImage::parse(&image_bytes(&payload)).unwrap()
+    }
+
+    fn fast_config() -> OtaConfig {
+        OtaConfig { max_reconnect_attempts: 3, reconnect_delay_ms: 0, reboot_wait_ms: 0 }
+    }
+
+    fn old_transport() -> SimulatedDfuTransport {
+        SimulatedDfuTransport::new(FirmwareVersion { major: 1, minor: 0, patch: 0 })
+    }
+
+    #[tokio::test]
+    async fn resumes_after_disconnect_without_resending() {
+        let image = test_image();
+        let mut transport = old_transport().with_disconnect_every(5);
+        let mut stages = Vec::new();
+
+        run_update(&mut transport, &image, &fast_config(), &mut |stage, _, _| stages.push(stage.to_string()))
+            .await
+            .unwrap();
+
+        // 5000 bytes en bloques de 240: cada bloque se envía una sola vez
+        assert_eq!(transport.chunks_written, 21);
+        assert_eq!(stages.iter().filter(|stage| *stage == "resuming").count(), 4);
+        assert_eq!(transport.read_firmware_version().await.unwrap(), Some("1.2.3".to_string()));
+    }
+
+    #[tokio::test]
+    async fn restarts_when_band_holds_another_image() {
+        let image = test_image();
+        let other = FirmwareImage::parse(&image_bytes(&[7; 600])).unwrap();
+        let mut transport = old_transport();
+        transport.begin(&other).await.unwrap();
+        transport.write_chunk(0, &other.payload[..240]).await.unwrap();
+
+        run_update(&mut transport, &image, &fast_config(), &mut |_, _, _| {}).await.unwrap();
+        assert_eq!(transport.chunks_written, 1 + 21);
+    }
+
+    #[test]
+    fn rejects_image_with_crc_mismatch() {
+        let mut bytes = image_bytes(&[1, 2, 3, 4]);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+
+        let error = FirmwareImage::parse(&bytes).unwrap_err();
+        assert!(error.contains("CRC"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn commit_fails_when_received_bytes_do_not_match_crc() {
+        let image = test_image();
+        let mut transport = old_transport();
+        transport.begin(&image).await.unwrap();
+        transport.write_chunk(0, &[0; 240]).await.unwrap();
+
+        assert!(transport.commit().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fails_when_band_reports_old_version_after_reboot() {
+        let image = test_image();
+        let mut transport = old_transport().with_failed_install();
+
+        let error = run_update(&mut transport, &image, &fast_config(), &mut |_, _, _| {}).await.unwrap_err();
+        assert!(error.contains("1.0.0") && error.contains("1.2.3"), "{}", error);
+    }
+
+    #[test]
+    fn parses_firmware_revision_strings() {
+        assert_eq!(FirmwareVersion::parse("v1.2.3-beta"), Some(VERSION));
+        assert_eq!(FirmwareVersion::parse("1.2"), Some(FirmwareVersion { major: 1, minor: 2, patch: 0 }));
+        assert_eq!(FirmwareVersion::parse("abc"), None);
+    }
+}
@@ -0,0 +1,370 @@
+// Modelos biomecánicos de fuerza intercambiables
+// Cada evento registra el modelo y los parámetros que produjeron su fuerza
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::roster;
+use crate::simple_ble::LimbType;
+use crate::storage;
+
+const FORCE_MODEL_FILE: &str = "force_model.json";
+
+// Mínimo de golpes de referencia para ajustar un modelo calibrado
+const MIN_CALIBRATION_SAMPLES: usize = 5;
+
+/// Datos de un golpe disponibles para estimar la fuerza
+#[derive(Debug, Clone)]
+pub struct ForceInput {
+    pub roster_id: String, // Competidor de la plantilla
+    pub body_weight: f32,  // kg
+    pub limb_type: LimbType,
+    pub acceleration: f32, // m/s² (aceleración lineal máxima)
+    pub velocity: f32,     // m/s
+}
+
+impl ForceInput {
+    fn is_hand(&self) -> bool {
+        matches!(self.limb_type, LimbType::LeftHand | LimbType::RightHand)
+    }
+}
+
+/// Modelo y parámetros con los que se calculó la fuerza de un evento
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ForceModelRecord {
+    pub name: String,
+    pub parameters: serde_json::Value,
+}
+
+/// Modelo de fuerza enchufable
+pub trait ForceModel {
+    fn name(&self) -> &'static str;
+    /// Parámetros usados para este golpe (solo los del competidor y la extremidad)
+    fn parameters(&self, input: &ForceInput) -> serde_json::Value;
+    /// Fuerza estimada en Newtons
+    fn estimate(&self, input: &ForceInput) -> f32;
+
+    fn record(&self, input: &ForceInput) -> ForceModelRecord {
+        ForceModelRecord {
+            name: self.name().to_string(),
+            parameters: self.parameters(input),
+        }
+    }
+}
+
+// Masa segmentaria (fracción del peso) x aceleración x rigidez articular (Dempster 1955)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DempsterModel {
+    pub hand_mass_percentage: f32,   // 2.7% del peso corporal
+    pub foot_mass_percentage: f32,   // 6.2% del peso corporal
+    pub joint_stiffness_factor: f32, // 1.8 factor de rigidez articular
+}
+
+impl Default for DempsterModel {
+    fn default() -> Self {
+        Self {
+            hand_mass_percentage: 0.027,
+            foot_mass_percentage: 0.062,
+            joint_stiffness_factor: 1.8,
+        }
+    }
+}
+
+impl DempsterModel {
+    fn limb_mass(&self, input: &ForceInput) -> f32 {
+        let percentage = if input.is_hand() { self.hand_mass_percentage } else { self.foot_mass_percentage };
+        input.body_weight * percentage
+    }
+}
+
+impl ForceModel for DempsterModel {
+    fn name(&self) -> &'static str {
+        "dempster"
+    }
+
+    fn parameters(&self, _input: &ForceInput) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn estimate(&self, input: &ForceInput) -> f32 {
+        self.limb_mass(input) * input.acceleration * self.joint_stiffness_factor
+    }
+}
+
+// Masa efectiva e impulso: F = m_efectiva * v / tiempo de contacto
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EffectiveMassModel {
+    pub hand_effective_mass_percentage: f32, // Brazo acoplado al tronco en el impacto
+    pub foot_effective_mass_percentage: f32,
+    pub hand_contact_time_ms: f32,
+    pub foot_contact_time_ms: f32,
+}
+
+impl Default for EffectiveMassModel {
+    fn default() -> Self {
+        Self {
+            hand_effective_mass_percentage: 0.04,
+            foot_effective_mass_percentage: 0.10,
+            hand_contact_time_ms: 15.0,
+            foot_contact_time_ms: 20.0,
+        }
+    }
+}
+
+impl ForceModel for EffectiveMassModel {
+    fn name(&self) -> &'static str {
+        "effective_mass"
+    }
+
+    fn parameters(&self, _input: &ForceInput) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn estimate(&self, input: &ForceInput) -> f32 {
+        let (percentage, contact_time_ms) = if input.is_hand() {
+            (self.hand_effective_mass_percentage, self.hand_contact_time_ms)
+        } else {
+            (self.foot_effective_mass_percentage, self.foot_contact_time_ms)
+        };
+
+        input.body_weight * percentage * input.velocity / (contact_time_ms.max(1.0) / 1000.0)
+    }
+}
+
+// Masas de segmentos medidas para un competidor (kg)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SegmentMasses {
+    pub hand: f32,
+    pub forearm: f32,
+    pub upper_arm: f32,
+    pub foot: f32,
+    pub shank: f32,
+    pub thigh: f32,
+}
+
+// Cadena de segmentos por competidor; sin datos propios usa Dempster
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SegmentMassModel {
+    pub segments: HashMap<String, SegmentMasses>, // roster_id -> masas
+    pub fallback: DempsterModel,
+}
+
+impl SegmentMasses {
+    // Masas (distal a proximal) de la cadena que golpea
+    fn chain(&self, is_hand: bool) -> [f32; 3] {
+        if is_hand {
+            [self.hand, self.forearm, self.upper_arm]
+        } else {
+            [self.foot, self.shank, self.thigh]
+        }
+    }
+}
+
+impl ForceModel for SegmentMassModel {
+    fn name(&self) -> &'static str {
+        "segment_mass"
+    }
+
+    fn parameters(&self, input: &ForceInput) -> serde_json::Value {
+        let Some(segments) = self.segments.get(&input.roster_id) else {
+            return serde_json::json!({
+                "roster_id": input.roster_id,
+                "fallback": self.fallback.parameters(input)
+            });
+        };
+
+        let chain = segments.chain(input.is_hand());
+        serde_json::json!({
+            "roster_id": input.roster_id,
+            "chain": if input.is_hand() { ["hand", "forearm", "upper_arm"] } else { ["foot", "shank", "thigh"] },
+            "chain_masses": chain,
+            "chain_mass": chain.iter().sum::<f32>(),
+            "joint_stiffness_factor": self.fallback.joint_stiffness_factor
+        })
+    }
+
+    fn estimate(&self, input: &ForceInput) -> f32 {
+        let Some(segments) = self.segments.get(&input.roster_id) else {
+            return self.fallback.estimate(input);
+        };
+
+        let chain_mass: f32 = segments.chain(input.is_hand()).iter().sum();
+        chain_mass * input.acceleration * self.fallback.joint_stiffness_factor
+    }
+}
+
+// Ajuste lineal contra una plataforma de fuerza: F = gain * (masa segmentaria * a) + offset
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CalibratedModel {
+    pub base: DempsterModel,
+    pub gain: f32,
+    pub offset: f32,
+    pub r_squared: f32,
+    pub sample_count: usize,
+    pub fitted_at: u64,
+}
+
+impl ForceModel for CalibratedModel {
+    fn name(&self) -> &'static str {
+        "calibrated"
+    }
+
+    fn parameters(&self, _input: &ForceInput) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn estimate(&self, input: &ForceInput) -> f32 {
+        (self.gain * self.base.limb_mass(input) * input.acceleration + self.offset).max(0.0)
+    }
+}
+
+/// Modelo activo (serializable para persistirlo y editarlo desde la UI)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum ForceModelConfig {
+    Dempster(DempsterModel),
+    EffectiveMass(EffectiveMassModel),
+    SegmentMass(SegmentMassModel),
+    Calibrated(CalibratedModel),
+}
+
+impl Default for ForceModelConfig {
+    fn default() -> Self {
+        ForceModelConfig::Dempster(DempsterModel::default())
+    }
+}
+
+impl ForceModelConfig {
+    pub fn model(&self) -> &dyn ForceModel {
+        match self {
+            ForceModelConfig::Dempster(model) => model,
+            ForceModelConfig::EffectiveMass(model) => model,
+            ForceModelConfig::SegmentMass(model) => model,
+            ForceModelConfig::Calibrated(model) => model,
+        }
+    }
+}
+
+static ACTIVE_FORCE_MODEL: Lazy<RwLock<ForceModelConfig>> =
+    Lazy::new(|| RwLock::new(ForceModelConfig::default()));
+
+/// Estima la fuerza con el modelo activo y devuelve el registro del modelo usado
+pub fn estimate_force(input: &ForceInput) -> (f32, ForceModelRecord) {
+    let config = ACTIVE_FORCE_MODEL.read().unwrap();
+    let model = config.model();
+    (model.estimate(input), model.record(input))
+}
+
+pub fn current_force_model() -> ForceModelConfig {
+    ACTIVE_FORCE_MODEL.read().unwrap().clone()
+}
+
+/// Carga el modelo guardado al iniciar la aplicación
+pub fn load_force_model<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    if let Some(config) = storage::load_json::<ForceModelConfig, R>(app_handle, FORCE_MODEL_FILE) {
+        info!(model = config.model().name(), "🧮 Modelo de fuerza cargado");
+        *ACTIVE_FORCE_MODEL.write().unwrap() = config;
+    }
+}
+
+/// Reemplaza el modelo activo y lo persiste
+pub fn set_force_model<R: tauri::Runtime>(app_handle: &AppHandle<R>, config: ForceModelConfig) -> Result<(), String> {
+    // Las masas medidas se asignan a competidores de la plantilla, no a la ranura de la banda
+    if let ForceModelConfig::SegmentMass(model) = &config {
+        if let Some(unknown) = model.segments.keys().find(|id| roster::get_competitor(id).is_none()) {
+            return Err(format!("El competidor {} no está en la plantilla", unknown));
+        }
+    }
+    storage::save_json(app_handle, FORCE_MODEL_FILE, &config)?;
+    info!(model = config.model().name(), "🧮 Modelo de fuerza actualizado");
+    *ACTIVE_FORCE_MODEL.write().unwrap() = config;
+    Ok(())
+}
+
+// Golpe de referencia medido con plataforma de fuerza
+struct ReferenceStrike {
+    is_hand: bool,
+    body_weight: f32,
+    acceleration: f32,
+    measured_force: f32,
+}
+
+/// Ajusta un modelo calibrado a partir de una grabación CSV de plataforma de fuerza
+/// Columnas: limb (hand|foot), body_weight_kg, acceleration_ms2, measured_force_n
+pub fn fit_calibrated_model(recording_path: &str, base: DempsterModel) -> Result<CalibratedModel, String> {
+    let contents = std::fs::read_to_string(recording_path)
+        .map_err(|e| format!("Error leyendo grabación de referencia: {}", e))?;
+
+    let strikes = contents.lines()
+        .skip(1) // Encabezado
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(index, line)| parse_reference_strike(line).map_err(|e| format!("Línea {}: {}", index + 2, e)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if strikes.len() < MIN_CALIBRATION_SAMPLES {
+        return Err(format!("Se necesitan al menos {} golpes de referencia ({} encontrados)",
+                           MIN_CALIBRATION_SAMPLES, strikes.len()));
+    }
+
+    // Mínimos cuadrados sobre x = masa segmentaria * aceleración
+    let points: Vec<(f32, f32)> = strikes.iter()
+        .map(|strike| {
+            let percentage = if strike.is_hand { base.hand_mass_percentage } else { base.foot_mass_percentage };
+            (strike.body_weight * percentage * strike.acceleration, strike.measured_force)
+        })
+        .collect();
+
+    let n = points.len() as f32;
+    let mean_x = points.iter().map(|p| p.0).sum::<f32>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f32>() / n;
+    let covariance: f32 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance_x: f32 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if variance_x <= f32::EPSILON {
+        return Err("Los golpes de referencia no tienen variación de aceleración".to_string());
+    }
+
+    let gain = covariance / variance_x;
+    let offset = mean_y - gain * mean_x;
+
+    let total: f32 = points.iter().map(|p| (p.1 - mean_y).powi(2)).sum();
+    let residual: f32 = points.iter().map(|p| (p.1 - (gain * p.0 + offset)).powi(2)).sum();
+    let r_squared = if total > 0.0 { 1.0 - residual / total } else { 0.0 };
+
+    Ok(CalibratedModel {
+        base,
+        gain,
+        offset,
+        r_squared,
+        sample_count: points.len(),
+        fitted_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    })
+}
+
+fn parse_reference_strike(line: &str) -> Result<ReferenceStrike, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() < 4 {
+        return Err("se esperaban 4 columnas".to_string());
+    }
+
+    let is_hand = match fields[0].to_lowercase().as_str() {
+        "hand" | "mano" => true,
+        "foot" | "pie" => false,
+        other => return Err(format!("extremidad desconocida '{}'", other)),
+    };
+    let number = |index: usize| fields[index].parse::<f32>()
+        .map_err(|_| format!("valor inválido '{}'", fields[index]));
+
+    Ok(ReferenceStrike {
+        is_hand,
+        body_weight: number(1)?,
+        acceleration: number(2)?,
+        measured_force: number(3)?,
+    })
+}
@@ -0,0 +1,140 @@
+// Perfil GATT de las bandas BH
+// UUIDs esperados del servicio y característica del sensor IMU
+
+use once_cell::sync::Lazy;
+use std::sync::RwLock;
+use uuid::Uuid;
+
+// Servicio y característica IMU definidos por el firmware de las bandas BH
+pub const BH_SENSOR_SERVICE_UUID: Uuid = Uuid::from_u128(0x4fafc201_1fb5_459e_8fcc_c5c9c331914b);
+pub const BH_IMU_CHARACTERISTIC_UUID: Uuid = Uuid::from_u128(0xbeb5483e_36e1_4688_b7f5_ea07361b26a8);
+
+// Servicio DFU para actualización de firmware OTA
+pub const BH_DFU_SERVICE_UUID: Uuid = Uuid::from_u128(0x8d53dc1d_1db7_4cd3_868b_8a527460aa84);
+pub const BH_DFU_CONTROL_UUID: Uuid = Uuid::from_u128(0xda2e7828_fbce_4e01_ae9e_261174997c48);
+pub const BH_DFU_DATA_UUID: Uuid = Uuid::from_u128(0x5c1e0b7a_3f6d_4e1b_9a3c_2f8b6d4e7a10);
+
+// Prefijo de nombre usado solo cuando el descubrimiento de respaldo está habilitado
+pub const BH_NAME_PREFIX: &str = "BH-";
+
+// Base UUID del Bluetooth SIG (0000xxxx-0000-1000-8000-00805f9b34fb)
+const BLUETOOTH_BASE_UUID: u128 = 0x0000_0000_0000_1000_8000_0080_5F9B_34FB;
+
+/// Convierte un UUID corto de 16 bits del Bluetooth SIG a UUID completo
+pub const fn bluetooth_uuid(short: u16) -> Uuid {
+    Uuid::from_u128(((short as u128) << 96) | BLUETOOTH_BASE_UUID)
+}
+
+/// Indica si el UUID pertenece al rango estándar del Bluetooth SIG
+/// (Battery Service, Device Information, etc.)
+pub fn is_standard_bluetooth_uuid(uuid: &Uuid) -> bool {
+    uuid.as_u128() & 0x0000_0000_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF == BLUETOOTH_BASE_UUID
+}
+
+// Configuración del descubrimiento GATT
+#[derive(Debug, Clone)]
+pub struct GattProfileConfig {
+    pub service_uuid: Uuid,
+    pub imu_characteristic_uuid: Uuid,
+    pub allow_fallback_discovery: bool, // Buscar cualquier característica notify si no se encuentran los UUIDs
+    pub filter_scan_by_service: bool,   // Filtrar el escaneo por UUID de servicio en el adaptador
+}
+
+impl Default for GattProfileConfig {
+    fn default() -> Self {
+        Self {
+            service_uuid: BH_SENSOR_SERVICE_UUID,
+            imu_characteristic_uuid: BH_IMU_CHARACTERISTIC_UUID,
+            // Hasta confirmar los UUIDs en el firmware de todas las bandas se mantiene
+            // el descubrimiento por nombre y sin filtrar el escaneo
+            allow_fallback_discovery: true,
+            filter_scan_by_service: false,
+        }
+    }
+}
+
+// Representación serializable para el frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GattProfileInfo {
+    pub service_uuid: String,
+    pub imu_characteristic_uuid: String,
+    pub allow_fallback_discovery: bool,
+    pub filter_scan_by_service: bool,
+}
+
+impl From<&GattProfileConfig> for GattProfileInfo {
+    fn from(config: &GattProfileConfig) -> Self {
+        Self {
+            service_uuid: config.service_uuid.to_string(),
+            imu_characteristic_uuid: config.imu_characteristic_uuid.to_string(),
+            allow_fallback_discovery: config.allow_fallback_discovery,
+            filter_scan_by_service: config.filter_scan_by_service,
+        }
+    }
+}
+
+// Configuración global del perfil GATT
+static GATT_PROFILE: Lazy<RwLock<GattProfileConfig>> =
+    Lazy::new(|| RwLock::new(GattProfileConfig::default()));
+
+/// Obtiene una copia de la configuración actual
+pub fn current_profile() -> GattProfileConfig {
+    GATT_PROFILE.read().unwrap().clone()
+}
+
+/// Actualiza la configuración del perfil GATT
+pub fn update_profile(
+    service_uuid: Option<String>,
+    imu_characteristic_uuid: Option<String>,
+    allow_fallback_discovery: Option<bool>,
+    filter_scan_by_service: Option<bool>,
+) -> Result<GattProfileInfo, String> {
+    // Validar antes de modificar el estado global
+    let service_uuid = service_uuid
+        .map(|uuid| Uuid::parse_str(&uuid).map_err(|e| format!("UUID de servicio inválido: {}", e)))
+        .transpose()?;
+    let imu_characteristic_uuid = imu_characteristic_uuid
+        .map(|uuid| Uuid::parse_str(&uuid).map_err(|e| format!("UUID de característica inválido: {}", e)))
+        .transpose()?;
+
+    let mut profile = GATT_PROFILE.write().unwrap();
+    if let Some(uuid) = service_uuid {
+        profile.service_uuid = uuid;
+    }
+    if let Some(uuid) = imu_characteristic_uuid {
+        profile.imu_characteristic_uuid = uuid;
+    }
+    if let Some(allow) = allow_fallback_discovery {
+        profile.allow_fallback_discovery = allow;
+    }
+    if let Some(filter) = filter_scan_by_service {
+        profile.filter_scan_by_service = filter;
+    }
+
+    Ok(GattProfileInfo::from(&*profile))
+}
+
+/// UUIDs de servicio a pasar a `adapter.scan`
+/// Vacío cuando el filtrado por servicio está deshabilitado
+pub fn scan_service_filter() -> Vec<Uuid> {
+    let profile = current_profile();
+    if profile.filter_scan_by_service {
+        vec![profile.service_uuid]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Determina si un anuncio BLE corresponde a una banda BH
+/// Se prioriza el UUID de servicio; el prefijo de nombre solo con respaldo habilitado
+/// (independiente de si el escaneo se filtra por servicio)
+pub fn is_bh_advertisement(services: &[Uuid], local_name: Option<&str>) -> bool {
+    let profile = current_profile();
+
+    if services.contains(&profile.service_uuid) {
+        return true;
+    }
+
+    profile.allow_fallback_discovery
+        && local_name.map(|name| name.contains(BH_NAME_PREFIX)).unwrap_or(false)
+}
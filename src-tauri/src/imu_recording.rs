@@ -0,0 +1,271 @@
+// Grabación de datos IMU con etiquetas del operador
+// Cada grabación es un archivo JSONL: encabezado, muestras y etiquetas de golpes
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tracing::{error, info};
+
+use crate::simple_ble::ImuData;
+use crate::storage;
+use crate::strike_classifier::MotionSample;
+
+const RECORDINGS_DIR: &str = "recordings";
+// Final del archivo que se lee para encontrar la última marca de tiempo
+const SPAN_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Línea de un archivo de grabación
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordingEntry {
+    Header {
+        device_id: String,
+        note: Option<String>,
+        started_at: u64,
+    },
+    Sample(RecordedSample),
+    Label {
+        timestamp: u64,     // Hora host (ms) en la que el operador marcó el golpe
+        strike_type: String,
+    },
+}
+
+/// Muestra grabada: valores crudos del sensor y magnitudes procesadas
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RecordedSample {
+    pub limb_id: u8,
+    pub raw_acc: [i16; 3],
+    pub raw_gyro: [i16; 3],
+    pub acc: [f32; 3],           // g calibrada, sin filtrar
+    pub gyro: [f32; 3],          // °/s calibrado, sin filtrar
+    pub filtered_acc: [f32; 3],  // Tras la cadena de filtros
+    pub filtered_gyro: [f32; 3],
+    pub motion: MotionSample,    // Aceleración lineal y giro usados por el detector
+}
+
+// Grabación en curso de un dispositivo
+struct ActiveRecording {
+    writer: BufWriter<File>,
+    file_name: String,
+    samples: u64,
+    labels: u64,
+}
+
+/// Resumen de una grabación
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RecordingSummary {
+    pub device_id: String,
+    pub file_name: String,
+    pub samples: u64,
+    pub labels: u64,
+}
+
+static ACTIVE_RECORDINGS: Lazy<Arc<Mutex<HashMap<String, ActiveRecording>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+// Evita bloquear el mapa a 200Hz cuando no se está grabando
+static RECORDING_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn write_entry(writer: &mut BufWriter<File>, entry: &RecordingEntry) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *writer, entry)?;
+    writer.write_all(b"\n")
+}
+
+/// Ruta de un archivo de grabación por nombre (sin permitir salir del directorio)
+pub fn recording_path<R: tauri::Runtime>(app_handle: &AppHandle<R>, file_name: &str) -> Result<PathBuf, String> {
+    if file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+        return Err(format!("Nombre de grabación inválido: {}", file_name));
+    }
+    Ok(storage::data_subdir(app_handle, RECORDINGS_DIR)?.join(file_name))
+}
+
+/// Empieza a grabar las muestras de un dispositivo conectado
+pub fn start_recording<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    device_id: &str,
+    note: Option<String>,
+) -> Result<String, String> {
+    let mut recordings = ACTIVE_RECORDINGS.lock().unwrap();
+    if recordings.contains_key(device_id) {
+        return Err(format!("Ya hay una grabación en curso para {}", device_id));
+    }
+
+    let started_at = now_ms();
+    let safe_id: String = device_id.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    let file_name = format!("{}_{}.jsonl", safe_id, started_at);
+    let path = recording_path(app_handle, &file_name)?;
+
+    let file = File::create(&path)
+        .map_err(|e| format!("Error creando grabación {}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    write_entry(&mut writer, &RecordingEntry::Header { device_id: device_id.to_string(), note, started_at })
+        .map_err(|e| format!("Error escribiendo grabación: {}", e))?;
+
+    recordings.insert(device_id.to_string(), ActiveRecording { writer, file_name: file_name.clone(), samples: 0, labels: 0 });
+    RECORDING_COUNT.fetch_add(1, Ordering::Relaxed);
+
+    info!(device_id = %device_id, file_name = %file_name, "⏺️ Grabación IMU iniciada");
+    Ok(file_name)
+}
+
+/// Detiene la grabación de un dispositivo y cierra el archivo
+pub fn stop_recording(device_id: &str) -> Result<RecordingSummary, String> {
+    let mut recording = ACTIVE_RECORDINGS.lock().unwrap().remove(device_id)
+        .ok_or_else(|| format!("No hay grabación en curso para {}", device_id))?;
+    RECORDING_COUNT.fetch_sub(1, Ordering::Relaxed);
+
+    recording.writer.flush()
+        .map_err(|e| format!("Error cerrando grabación: {}", e))?;
+
+    info!(device_id = %device_id, samples = recording.samples, labels = recording.labels, "⏹️ Grabación IMU detenida");
+    Ok(RecordingSummary {
+        device_id: device_id.to_string(),
+        file_name: recording.file_name,
+        samples: recording.samples,
+        labels: recording.labels,
+    })
+}
+
+/// Registra la etiqueta del operador para el golpe recién realizado
+pub fn tag_strike(device_id: &str, strike_type: &str, timestamp: Option<u64>) -> Result<(), String> {
+    let mut recordings = ACTIVE_RECORDINGS.lock().unwrap();
+    let recording = recordings.get_mut(device_id)
+        .ok_or_else(|| format!("No hay grabación en curso para {}", device_id))?;
+
+    let entry = RecordingEntry::Label {
+        timestamp: timestamp.unwrap_or_else(now_ms),
+        strike_type: strike_type.to_string(),
+    };
+    write_entry(&mut recording.writer, &entry)
+        .map_err(|e| format!("Error escribiendo etiqueta: {}", e))?;
+    recording.labels += 1;
+    Ok(())
+}
+
+/// Añade una muestra a la grabación del dispositivo, si hay una en curso
+/// CRÍTICO: se llama a 200Hz; sale de inmediato si no se está grabando
+pub fn record_sample(device_id: &str, data: &ImuData, motion: &MotionSample) {
+    if RECORDING_COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    let mut recordings = ACTIVE_RECORDINGS.lock().unwrap();
+    let Some(recording) = recordings.get_mut(device_id) else {
+        return;
+    };
+
+    let entry = RecordingEntry::Sample(RecordedSample {
+        limb_id: data.limb_id,
+        raw_acc: [data.acc_x, data.acc_y, data.acc_z],
+        raw_gyro: [data.gyro_x, data.gyro_y, data.gyro_z],
+        acc: data.unfiltered_acc,
+        gyro: data.unfiltered_gyro,
+        filtered_acc: data.acc,
+        filtered_gyro: data.gyro,
+        motion: *motion,
+    });
+
+    match write_entry(&mut recording.writer, &entry) {
+        Ok(()) => recording.samples += 1,
+        Err(e) => {
+            error!(device_id = %device_id, error = %e, "❌ Error escribiendo grabación, se detiene");
+            recordings.remove(device_id);
+            RECORDING_COUNT.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Lista los archivos de grabación guardados
+pub fn list_recordings<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<Vec<String>, String> {
+    let dir = storage::data_subdir(app_handle, RECORDINGS_DIR)?;
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Error leyendo grabaciones: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".jsonl"))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Grabación cargada en memoria
+#[derive(Debug, Default)]
+pub struct Recording {
+    pub device_id: String,
+    pub samples: Vec<RecordedSample>,
+    pub labels: Vec<(u64, String)>,
+}
+
+/// Lee una grabación completa
+pub fn load_recording(path: &Path) -> Result<Recording, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Error abriendo grabación {}: {}", path.display(), e))?;
+
+    let mut recording = Recording::default();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Error leyendo grabación: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let entry: RecordingEntry = serde_json::from_str(&line)
+            .map_err(|e| format!("{} línea {}: {}", path.display(), index + 1, e))?;
+        match entry {
+            RecordingEntry::Header { device_id, .. } => recording.device_id = device_id,
+            RecordingEntry::Sample(sample) => recording.samples.push(sample),
+            RecordingEntry::Label { timestamp, strike_type } => recording.labels.push((timestamp, strike_type)),
+        }
+    }
+
+    Ok(recording)
+}
+
+/// Intervalo (ms) que cubre una grabación: inicio del encabezado y última marca de tiempo
+/// Solo lee la primera línea y el final del archivo; sin marca legible al final el fin queda abierto
+pub fn recording_span(path: &Path) -> Result<(u64, u64), String> {
+    let mut file = File::open(path)
+        .map_err(|e| format!("Error abriendo grabación {}: {}", path.display(), e))?;
+
+    let mut header = String::new();
+    BufReader::new(&mut file).read_line(&mut header)
+        .map_err(|e| format!("Error leyendo grabación: {}", e))?;
+    let started_at = match serde_json::from_str(&header) {
+        Ok(RecordingEntry::Header { started_at, .. }) => started_at,
+        _ => return Err(format!("{}: falta el encabezado", path.display())),
+    };
+
+    let len = file.metadata().map_err(|e| format!("Error leyendo grabación: {}", e))?.len();
+    let offset = len.saturating_sub(SPAN_TAIL_BYTES);
+    let mut tail = Vec::new();
+    file.seek(SeekFrom::Start(offset))
+        .and_then(|_| file.read_to_end(&mut tail))
+        .map_err(|e| format!("Error leyendo grabación: {}", e))?;
+    let tail = String::from_utf8_lossy(&tail);
+    // La primera línea del tramo puede estar cortada
+    let tail = if offset > 0 { tail.split_once('\n').map_or("", |(_, rest)| rest) } else { &tail };
+    let ended_at = tail.lines().rev()
+        .find_map(|line| match serde_json::from_str(line).ok()? {
+            RecordingEntry::Header { started_at, .. } => Some(started_at),
+            RecordingEntry::Sample(sample) => Some(sample.motion.timestamp),
+            RecordingEntry::Label { timestamp, .. } => Some(timestamp),
+        })
+        .unwrap_or(u64::MAX);
+    Ok((started_at, ended_at.max(started_at)))
+}
+
+/// Tramo de muestras de una grabación para comparar señal cruda y filtrada
+pub fn read_recording_samples(path: &Path, offset: usize, limit: usize) -> Result<Vec<RecordedSample>, String> {
+    let recording = load_recording(path)?;
+    Ok(recording.samples.into_iter().skip(offset).take(limit).collect())
+}
@@ -0,0 +1,287 @@
+// Transmisión en vivo de muestras IMU (crudas y filtradas) para visualizar la señal
+// Diezmada por dispositivo y entregada por canal Tauri y/o WebSocket, con indicador de calidad
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::ipc::Channel;
+use tracing::{info, warn};
+
+use crate::broadcast_ws::ws_broadcast;
+use crate::sensor_health::{self, EXPECTED_SAMPLE_RATE_HZ};
+use crate::simple_ble::ImuData;
+
+const DEFAULT_STREAM_RATE_HZ: f32 = 50.0;
+const MAX_STREAM_RATE_HZ: f32 = 200.0;
+// Intervalo sobre el que se recalcula la calidad de señal
+const QUALITY_WINDOW_MS: u64 = 1_000;
+
+/// Muestra enviada a la interfaz
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImuStreamFrame {
+    pub device_id: String,
+    pub limb_id: u8,
+    pub timestamp: u64,
+    pub raw_acc: [i16; 3],
+    pub raw_gyro: [i16; 3],
+    pub acc: [f32; 3],           // g calibrada, sin filtrar
+    pub gyro: [f32; 3],          // °/s calibrado, sin filtrar
+    pub filtered_acc: [f32; 3],
+    pub filtered_gyro: [f32; 3],
+    pub linear_acc: [f32; 3],
+    pub quality: Option<SignalQuality>, // None hasta completar la primera ventana
+}
+
+/// Indicador de calidad de señal de la última ventana
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SignalQuality {
+    pub sample_rate_hz: f32,     // Muestras recibidas por segundo
+    pub rate_ratio: f32,         // Respecto a los 200Hz esperados
+    pub gravity_g: f32,          // Módulo medio de la aceleración (≈1 g en reposo)
+    pub acc_noise_g: f32,        // Desviación típica del módulo de aceleración
+    pub gyro_noise_dps: f32,     // Desviación típica del módulo de giro
+    pub saturated_ratio: f32,    // Fracción de muestras con algún eje saturado
+    pub status: SignalStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalStatus {
+    Good,
+    LowRate,    // Se pierden muestras (distancia, interferencias)
+    Saturated,  // Rango del sensor excedido
+    BadMount,   // El módulo de la gravedad no es ≈1 g: banda suelta o mal calibrada
+}
+
+// Acumuladores de la ventana de calidad
+#[derive(Debug, Default)]
+struct QualityAccumulator {
+    window_start: Option<u64>,
+    count: u32,
+    saturated: u32,
+    acc_sum: f64,
+    acc_sum_sq: f64,
+    gyro_sum: f64,
+    gyro_sum_sq: f64,
+}
+
+impl QualityAccumulator {
+    fn add(&mut self, data: &ImuData) -> Option<SignalQuality> {
+        let window_start = *self.window_start.get_or_insert(data.timestamp);
+        let acc = norm(data.unfiltered_acc) as f64;
+        let gyro = norm(data.unfiltered_gyro) as f64;
+        let raw = [data.acc_x, data.acc_y, data.acc_z, data.gyro_x, data.gyro_y, data.gyro_z];
+
+        self.count += 1;
+        self.acc_sum += acc;
+        self.acc_sum_sq += acc * acc;
+        self.gyro_sum += gyro;
+        self.gyro_sum_sq += gyro * gyro;
+        if raw.iter().any(|&value| sensor_health::is_saturated(value)) {
+            self.saturated += 1;
+        }
+
+        let elapsed_ms = data.timestamp.saturating_sub(window_start);
+        if elapsed_ms < QUALITY_WINDOW_MS {
+            return None;
+        }
+
+        let quality = self.summarize(elapsed_ms);
+        *self = Self { window_start: Some(data.timestamp), ..Self::default() };
+        Some(quality)
+    }
+
+    fn summarize(&self, elapsed_ms: u64) -> SignalQuality {
+        let count = self.count as f64;
+        let std_dev = |sum: f64, sum_sq: f64| ((sum_sq / count - (sum / count).powi(2)).max(0.0)).sqrt() as f32;
+
+        let sample_rate_hz = self.count as f32 * 1000.0 / elapsed_ms as f32;
+        let rate_ratio = sample_rate_hz / EXPECTED_SAMPLE_RATE_HZ;
+        let gravity_g = (self.acc_sum / count) as f32;
+        let saturated_ratio = self.saturated as f32 / self.count as f32;
+
+        let status = if saturated_ratio > 0.01 {
+            SignalStatus::Saturated
+        } else if rate_ratio < 0.8 {
+            SignalStatus::LowRate
+        } else if !(0.7..=1.3).contains(&gravity_g) {
+            SignalStatus::BadMount
+        } else {
+            SignalStatus::Good
+        };
+
+        SignalQuality {
+            sample_rate_hz,
+            rate_ratio,
+            gravity_g,
+            acc_noise_g: std_dev(self.acc_sum, self.acc_sum_sq),
+            gyro_noise_dps: std_dev(self.gyro_sum, self.gyro_sum_sq),
+            saturated_ratio,
+            status,
+        }
+    }
+}
+
+fn norm(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+// Suscripción activa de un dispositivo
+struct ImuStream {
+    channel: Option<Channel<ImuStreamFrame>>,
+    websocket: bool,
+    interval_ms: u64,
+    last_sent: Option<u64>,
+    quality: QualityAccumulator,
+    last_quality: Option<SignalQuality>,
+}
+
+/// Estado de una transmisión
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImuStreamStatus {
+    pub device_id: String,
+    pub rate_hz: f32,
+    pub channel: bool,
+    pub websocket: bool,
+}
+
+static ACTIVE_STREAMS: Lazy<Arc<Mutex<HashMap<String, ImuStream>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+// Evita bloquear el mapa a 200Hz cuando nadie está mirando
+static STREAM_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Empieza (o reconfigura) la transmisión de un dispositivo
+pub fn start_stream(
+    device_id: &str,
+    rate_hz: Option<f32>,
+    channel: Option<Channel<ImuStreamFrame>>,
+    websocket: bool,
+) -> Result<ImuStreamStatus, String> {
+    let rate_hz = rate_hz.unwrap_or(DEFAULT_STREAM_RATE_HZ);
+    if !(rate_hz > 0.0 && rate_hz <= MAX_STREAM_RATE_HZ) {
+        return Err(format!("Frecuencia de transmisión fuera de rango (0, {} Hz]", MAX_STREAM_RATE_HZ));
+    }
+    if channel.is_none() && !websocket {
+        return Err("La transmisión necesita un canal o el WebSocket".to_string());
+    }
+
+    let status = ImuStreamStatus {
+        device_id: device_id.to_string(),
+        rate_hz,
+        channel: channel.is_some(),
+        websocket,
+    };
+    let stream = ImuStream {
+        channel,
+        websocket,
+        interval_ms: (1000.0 / rate_hz).round() as u64,
+        last_sent: None,
+        quality: QualityAccumulator::default(),
+        last_quality: None,
+    };
+
+    if ACTIVE_STREAMS.lock().unwrap().insert(device_id.to_string(), stream).is_none() {
+        STREAM_COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+
+    info!(device_id = %device_id, rate_hz = rate_hz, websocket = websocket, "📈 Transmisión IMU iniciada");
+    Ok(status)
+}
+
+/// Detiene la transmisión de un dispositivo
+pub fn stop_stream(device_id: &str) -> Result<(), String> {
+    ACTIVE_STREAMS.lock().unwrap().remove(device_id)
+        .ok_or_else(|| format!("No hay transmisión IMU para {}", device_id))?;
+    STREAM_COUNT.fetch_sub(1, Ordering::Relaxed);
+
+    info!(device_id = %device_id, "📉 Transmisión IMU detenida");
+    Ok(())
+}
+
+/// Detiene la transmisión de un dispositivo desconectado (no es error si no tenía)
+pub fn forget_device(device_id: &str) {
+    if ACTIVE_STREAMS.lock().unwrap().remove(device_id).is_some() {
+        STREAM_COUNT.fetch_sub(1, Ordering::Relaxed);
+        info!(device_id = %device_id, "📉 Transmisión IMU detenida al desconectar");
+    }
+}
+
+/// Detiene todas las transmisiones
+pub fn stop_all_streams() {
+    let mut streams = ACTIVE_STREAMS.lock().unwrap();
+    STREAM_COUNT.fetch_sub(streams.len(), Ordering::Relaxed);
+    streams.clear();
+}
+
+/// Publica una muestra si el dispositivo tiene transmisión activa
+/// CRÍTICO: se llama a 200Hz; sale de inmediato si no hay transmisiones
+pub fn publish_sample(device_id: &str, data: &ImuData) {
+    if STREAM_COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    let mut streams = ACTIVE_STREAMS.lock().unwrap();
+    let Some(stream) = streams.get_mut(device_id) else {
+        return;
+    };
+
+    // La calidad usa todas las muestras, no solo las enviadas
+    if let Some(quality) = stream.quality.add(data) {
+        stream.last_quality = Some(quality);
+    }
+
+    // Diezmado; un reloj que retrocede reenvía en lugar de quedarse bloqueado
+    let due = stream.last_sent.is_none_or(|last| {
+        data.timestamp < last || data.timestamp - last >= stream.interval_ms
+    });
+    if !due {
+        return;
+    }
+    stream.last_sent = Some(data.timestamp);
+
+    let frame = ImuStreamFrame {
+        device_id: device_id.to_string(),
+        limb_id: data.limb_id,
+        timestamp: data.timestamp,
+        raw_acc: [data.acc_x, data.acc_y, data.acc_z],
+        raw_gyro: [data.gyro_x, data.gyro_y, data.gyro_z],
+        acc: data.unfiltered_acc,
+        gyro: data.unfiltered_gyro,
+        filtered_acc: data.acc,
+        filtered_gyro: data.gyro,
+        linear_acc: data.linear_acc,
+        quality: stream.last_quality.clone(),
+    };
+
+    if stream.websocket {
+        ws_broadcast(&serde_json::json!({
+            "type": "imu_stream",
+            "data": &frame,
+        }));
+    }
+
+    // Si la ventana que abrió el canal se cerró, dejar de transmitir por él
+    if let Some(channel) = &stream.channel {
+        if channel.send(frame).is_err() {
+            warn!(device_id = %device_id, "⚠️ Canal de transmisión IMU cerrado");
+            stream.channel = None;
+            if !stream.websocket {
+                streams.remove(device_id);
+                STREAM_COUNT.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Transmisiones activas
+pub fn active_streams() -> Vec<ImuStreamStatus> {
+    ACTIVE_STREAMS.lock().unwrap().iter()
+        .map(|(device_id, stream)| ImuStreamStatus {
+            device_id: device_id.clone(),
+            rate_hz: 1000.0 / stream.interval_ms as f32,
+            channel: stream.channel.is_some(),
+            websocket: stream.websocket,
+        })
+        .collect()
+}
@@ -0,0 +1,551 @@
+// Puntuación de jueces desde el móvil a través del servidor de difusión (/ws)
+// Un juez se identifica con su código, recibe el estado del asalto y envía tarjetas (10-point must) o marcas de falta
+// Las tarjetas se validan, se fechan y se guardan en la sesión de combate junto a las estadísticas de los sensores
+
+use once_cell::sync::{Lazy, OnceCell};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, RwLock};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::scoring::{self, CombatSession, SessionStatus};
+use crate::storage;
+
+// Sistema 10-point must: el ganador del asalto recibe 10 y el resto entre 7 y 10
+pub const MUST_SCORE: u8 = 10;
+pub const MIN_ROUND_SCORE: u8 = 7;
+const MIN_CODE_LEN: usize = 4;
+const JUDGE_PANEL_FILE: &str = "judge_panel.json";
+
+/// Juez del panel (el código lo introduce en su móvil para identificarse)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct JudgeSeat {
+    pub id: String,
+    pub name: String,
+    pub code: String,
+}
+
+/// Juez del panel tal como se muestra (sin el código)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct JudgeStatus {
+    pub id: String,
+    pub name: String,
+    pub connected: bool,
+}
+
+/// Tarjeta de un juez para un asalto
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct JudgeCard {
+    pub judge_id: String,
+    pub round: u32,
+    pub scores: BTreeMap<String, u8>, // fighter_id -> puntos
+    pub submitted_at: u64,
+    pub revision: u32,                // 0 la primera vez; sube si el juez la corrige
+}
+
+/// Falta señalada por un juez (informativa; la penalización la aplica el árbitro)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FoulMarker {
+    pub judge_id: String,
+    pub round: u32,
+    pub fighter_id: String,
+    pub foul: String,
+    pub note: Option<String>,
+    pub at: u64,
+}
+
+/// Tipo de decisión de los jueces
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecisionKind {
+    Unanimous, // Todos los jueces dan el mismo ganador
+    Majority,  // El resto de jueces dan empate
+    Split,     // Algún juez da ganador al rival
+    Draw,      // Sin mayoría
+}
+
+/// Tarjetas de un asalto y su resultado por mayoría
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RoundCards {
+    pub round: u32,
+    pub cards: BTreeMap<String, BTreeMap<String, u8>>, // judge_id -> tarjeta
+    pub complete: bool,
+    pub winner: Option<String>,
+}
+
+/// Decisión final de los jueces
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct JudgeDecision {
+    pub winner: Option<String>,
+    pub kind: DecisionKind,
+    pub totals: BTreeMap<String, BTreeMap<String, u32>>, // judge_id -> puntos totales por peleador
+    pub cards_won: BTreeMap<String, i32>,                // fighter_id -> jueces que le dan la victoria
+}
+
+/// Resumen de la puntuación de los jueces incluido en el marcador
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct JudgingSummary {
+    pub judges: BTreeMap<String, String>,
+    pub rounds: Vec<RoundCards>,
+    pub fouls: BTreeMap<String, u32>,       // fighter_id -> faltas señaladas
+    pub decision: Option<JudgeDecision>,    // Cuando todos los jueces han puntuado todos los asaltos
+}
+
+/// Estado enviado a un juez al conectarse y tras cada envío
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct JudgeRoundState {
+    pub judge_id: String,
+    pub judge_name: String,
+    pub session_id: Option<String>,
+    pub status: Option<SessionStatus>,
+    pub fighters: BTreeMap<String, String>,
+    pub current_round: u32,
+    pub total_rounds: u32,
+    pub scorable_rounds: Vec<u32>,
+    pub submitted_rounds: Vec<u32>,
+    pub must_score: u8,
+    pub min_score: u8,
+}
+
+// Mensajes que envía el móvil del juez
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type")]
+enum JudgeMessage {
+    #[serde(rename = "judge_join")]
+    Join { judge_id: String, code: String },
+    #[serde(rename = "judge_state")]
+    State,
+    #[serde(rename = "judge_round_score")]
+    RoundScore { round: u32, scores: BTreeMap<String, u8> },
+    #[serde(rename = "judge_foul")]
+    Foul { fighter_id: String, foul: String, note: Option<String> },
+}
+
+static JUDGE_PANEL: Lazy<RwLock<BTreeMap<String, JudgeSeat>>> = Lazy::new(|| RwLock::new(BTreeMap::new()));
+// Conexiones abiertas por juez (un juez puede tener más de un dispositivo)
+static CONNECTED_JUDGES: Lazy<Mutex<HashMap<String, usize>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+// El servidor WebSocket no tiene AppHandle; se guarda al iniciar la aplicación
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Registra el AppHandle para guardar y publicar lo que llega por WebSocket
+pub fn attach(app_handle: AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+/// Jueces del panel (id -> nombre)
+pub fn panel() -> BTreeMap<String, String> {
+    JUDGE_PANEL.read().unwrap().values()
+        .map(|seat| (seat.id.clone(), seat.name.clone()))
+        .collect()
+}
+
+/// Jueces del panel con su estado de conexión
+pub fn panel_status() -> Vec<JudgeStatus> {
+    let connected = CONNECTED_JUDGES.lock().unwrap();
+    JUDGE_PANEL.read().unwrap().values()
+        .map(|seat| JudgeStatus {
+            id: seat.id.clone(),
+            name: seat.name.clone(),
+            connected: connected.get(&seat.id).is_some_and(|&count| count > 0),
+        })
+        .collect()
+}
+
+/// Reemplaza el panel de jueces; si hay un combate en curso pasa a usar el nuevo panel
+pub fn configure_panel<R: tauri::Runtime>(app_handle: &AppHandle<R>, seats: Vec<JudgeSeat>) -> Result<Vec<JudgeStatus>, String> {
+    let mut panel = BTreeMap::new();
+    for seat in seats {
+        if seat.id.trim().is_empty() || seat.name.trim().is_empty() {
+            return Err("Cada juez necesita un identificador y un nombre".to_string());
+        }
+        if seat.code.len() < MIN_CODE_LEN {
+            return Err(format!("El código de {} debe tener al menos {} caracteres", seat.id, MIN_CODE_LEN));
+        }
+        if panel.values().any(|other: &JudgeSeat| other.code == seat.code) {
+            return Err(format!("El código de {} ya lo usa otro juez", seat.id));
+        }
+        if let Some(duplicate) = panel.insert(seat.id.clone(), seat) {
+            return Err(format!("El juez {} está repetido", duplicate.id));
+        }
+    }
+    storage::save_json(app_handle, JUDGE_PANEL_FILE, &panel)?;
+    *JUDGE_PANEL.write().unwrap() = panel;
+    let judges = self::panel();
+    info!(judges = ?judges.keys(), "🧑‍⚖️ Panel de jueces configurado");
+
+    if scoring::current_session().is_some_and(|session| session.status == SessionStatus::InProgress) {
+        scoring::update_session(app_handle, "judge_update", |session| {
+            session.judges = judges;
+            session.refresh_results();
+            Ok(())
+        })?;
+    }
+    Ok(panel_status())
+}
+
+/// Carga el panel de jueces guardado al iniciar la aplicación
+pub fn load_panel<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(saved) = storage::load_json::<BTreeMap<String, JudgeSeat>, R>(app_handle, JUDGE_PANEL_FILE) else {
+        return;
+    };
+    info!(judges = ?saved.keys(), "🧑‍⚖️ Panel de jueces cargado");
+    *JUDGE_PANEL.write().unwrap() = saved;
+}
+
+// Asaltos que ya se pueden puntuar: los cerrados y, si el combate terminó antes, el último
+fn scorable_rounds(session: &CombatSession) -> Vec<u32> {
+    let mut rounds: Vec<u32> = session.rounds.iter().map(|round| round.round).collect();
+    if session.status == SessionStatus::Finished && !rounds.contains(&session.current_round) {
+        rounds.push(session.current_round);
+    }
+    rounds
+}
+
+// Comprueba una tarjeta 10-point must
+fn validate_card(fighters: &BTreeMap<String, String>, scores: &BTreeMap<String, u8>) -> Result<(), String> {
+    if let Some(missing) = fighters.keys().find(|id| !scores.contains_key(*id)) {
+        return Err(format!("Falta la puntuación de {}", missing));
+    }
+    if let Some(unknown) = scores.keys().find(|id| !fighters.contains_key(*id)) {
+        return Err(format!("{} no participa en el combate", unknown));
+    }
+    if let Some((fighter_id, points)) = scores.iter().find(|(_, points)| !(MIN_ROUND_SCORE..=MUST_SCORE).contains(points)) {
+        return Err(format!("Puntuación de {} fuera de rango: {} ({}-{})", fighter_id, points, MIN_ROUND_SCORE, MUST_SCORE));
+    }
+    if !scores.values().any(|&points| points == MUST_SCORE) {
+        return Err(format!("Al menos un peleador debe recibir {} puntos", MUST_SCORE));
+    }
+    Ok(())
+}
+
+// Ganador de una tarjeta (o de la suma de tarjetas); None si hay empate
+fn card_winner<T: Copy + Into<i32>>(scores: &BTreeMap<String, T>) -> Option<String> {
+    scoring::leader(&scores.iter().map(|(id, &points)| (id.clone(), points.into())).collect())
+}
+
+// Jueces que dan la victoria a cada peleador
+fn tally_winners<'a>(fighters: &BTreeMap<String, String>, winners: impl Iterator<Item = Option<&'a String>>) -> BTreeMap<String, i32> {
+    let mut tally: BTreeMap<String, i32> = fighters.keys().map(|id| (id.clone(), 0)).collect();
+    for winner in winners.flatten() {
+        *tally.entry(winner.clone()).or_default() += 1;
+    }
+    tally
+}
+
+/// Ganador por mayoría: necesita a más de la mitad de los jueces del panel
+pub fn majority_winner(tally: &BTreeMap<String, i32>, judges: usize) -> Option<String> {
+    tally.iter()
+        .find(|(_, &cards)| cards > (judges / 2) as i32)
+        .map(|(fighter_id, _)| fighter_id.clone())
+}
+
+/// Jueces que dan el asalto a cada peleador
+pub fn round_tally(session: &CombatSession, round: u32) -> BTreeMap<String, i32> {
+    let winners: Vec<Option<String>> = session.judge_cards.iter()
+        .filter(|card| card.round == round && session.judges.contains_key(&card.judge_id))
+        .map(|card| card_winner(&card.scores))
+        .collect();
+    tally_winners(&session.fighters, winners.iter().map(Option::as_ref))
+}
+
+/// Decisión de los jueces si todos han puntuado todos los asaltos puntuables
+pub fn decision(session: &CombatSession) -> Option<JudgeDecision> {
+    let rounds = scorable_rounds(session);
+    if session.judges.is_empty() || rounds.is_empty() {
+        return None;
+    }
+
+    let mut totals = BTreeMap::new();
+    for judge_id in session.judges.keys() {
+        let mut total: BTreeMap<String, u32> = session.fighters.keys().map(|id| (id.clone(), 0)).collect();
+        for round in &rounds {
+            let card = session.judge_cards.iter().find(|card| &card.judge_id == judge_id && card.round == *round)?;
+            for (fighter_id, &points) in &card.scores {
+                *total.entry(fighter_id.clone()).or_default() += points as u32;
+            }
+        }
+        totals.insert(judge_id.clone(), total);
+    }
+
+    let judge_winners: Vec<Option<String>> = totals.values()
+        .map(|total| scoring::leader(&total.iter().map(|(id, &points)| (id.clone(), points as i32)).collect()))
+        .collect();
+    let cards_won = tally_winners(&session.fighters, judge_winners.iter().map(Option::as_ref));
+    let winner = majority_winner(&cards_won, session.judges.len());
+    let kind = match &winner {
+        None => DecisionKind::Draw,
+        Some(winner) if judge_winners.iter().all(|judge_winner| judge_winner.as_ref() == Some(winner)) => DecisionKind::Unanimous,
+        Some(winner) if judge_winners.iter().flatten().any(|judge_winner| judge_winner != winner) => DecisionKind::Split,
+        Some(_) => DecisionKind::Majority,
+    };
+
+    Some(JudgeDecision { winner, kind, totals, cards_won })
+}
+
+/// Resumen para el marcador; None si el combate no tiene jueces
+pub fn summary(session: &CombatSession) -> Option<JudgingSummary> {
+    if session.judges.is_empty() && session.judge_cards.is_empty() {
+        return None;
+    }
+
+    let rounds = scorable_rounds(session).into_iter()
+        .map(|round| {
+            let cards: BTreeMap<String, BTreeMap<String, u8>> = session.judge_cards.iter()
+                .filter(|card| card.round == round)
+                .map(|card| (card.judge_id.clone(), card.scores.clone()))
+                .collect();
+            RoundCards {
+                round,
+                complete: session.judges.keys().all(|judge_id| cards.contains_key(judge_id)),
+                winner: majority_winner(&round_tally(session, round), session.judges.len()),
+                cards,
+            }
+        })
+        .collect();
+
+    let mut fouls: BTreeMap<String, u32> = session.fighters.keys().map(|id| (id.clone(), 0)).collect();
+    for marker in &session.fouls {
+        *fouls.entry(marker.fighter_id.clone()).or_default() += 1;
+    }
+
+    Some(JudgingSummary {
+        judges: session.judges.clone(),
+        rounds,
+        fouls,
+        decision: decision(session),
+    })
+}
+
+fn round_state(judge_id: &str) -> JudgeRoundState {
+    let session = scoring::current_session();
+    let judge_name = JUDGE_PANEL.read().unwrap().get(judge_id)
+        .map(|seat| seat.name.clone())
+        .unwrap_or_default();
+
+    JudgeRoundState {
+        judge_id: judge_id.to_string(),
+        judge_name,
+        session_id: session.as_ref().map(|session| session.id.clone()),
+        status: session.as_ref().map(|session| session.status),
+        fighters: session.as_ref().map(|session| session.fighters.clone()).unwrap_or_default(),
+        current_round: session.as_ref().map_or(0, |session| session.current_round),
+        total_rounds: session.as_ref().map_or(0, |session| session.rule_set.rounds),
+        scorable_rounds: session.as_ref().map(scorable_rounds).unwrap_or_default(),
+        submitted_rounds: session.as_ref()
+            .map(|session| session.judge_cards.iter()
+                .filter(|card| card.judge_id == judge_id)
+                .map(|card| card.round)
+                .collect())
+            .unwrap_or_default(),
+        must_score: MUST_SCORE,
+        min_score: MIN_ROUND_SCORE,
+    }
+}
+
+// Guarda una tarjeta (o la corrige si el juez ya había puntuado ese asalto)
+fn submit_card<R: tauri::Runtime>(app_handle: &AppHandle<R>, judge_id: &str, round: u32, scores: BTreeMap<String, u8>) -> Result<(), String> {
+    scoring::update_session(app_handle, "judge_update", |session| {
+        if !session.judges.contains_key(judge_id) {
+            return Err("El juez no pertenece al panel de este combate".to_string());
+        }
+        if !scorable_rounds(session).contains(&round) {
+            return Err(format!("El asalto {} todavía no se puede puntuar", round));
+        }
+        validate_card(&session.fighters, &scores)?;
+
+        let submitted_at = now_ms();
+        match session.judge_cards.iter_mut().find(|card| card.judge_id == judge_id && card.round == round) {
+            Some(card) => {
+                card.scores = scores;
+                card.submitted_at = submitted_at;
+                card.revision += 1;
+                info!(judge_id = %judge_id, round = round, scores = ?card.scores, revision = card.revision, "📝 Tarjeta corregida");
+            }
+            None => {
+                info!(judge_id = %judge_id, round = round, scores = ?scores, "📝 Tarjeta recibida");
+                session.judge_cards.push(JudgeCard {
+                    judge_id: judge_id.to_string(),
+                    round,
+                    scores,
+                    submitted_at,
+                    revision: 0,
+                });
+            }
+        }
+        session.refresh_results();
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn mark_foul<R: tauri::Runtime>(app_handle: &AppHandle<R>, judge_id: &str, fighter_id: String, foul: String, note: Option<String>) -> Result<(), String> {
+    scoring::update_session(app_handle, "judge_update", |session| {
+        if !session.judges.contains_key(judge_id) {
+            return Err("El juez no pertenece al panel de este combate".to_string());
+        }
+        if session.status != SessionStatus::InProgress {
+            return Err("El combate ya ha terminado".to_string());
+        }
+        if !session.fighters.contains_key(&fighter_id) {
+            return Err(format!("{} no participa en el combate", fighter_id));
+        }
+        if foul.trim().is_empty() {
+            return Err("La falta necesita un tipo".to_string());
+        }
+
+        info!(judge_id = %judge_id, fighter_id = %fighter_id, foul = %foul, round = session.current_round, "🚩 Falta señalada");
+        session.fouls.push(FoulMarker {
+            judge_id: judge_id.to_string(),
+            round: session.current_round,
+            fighter_id,
+            foul,
+            note,
+            at: now_ms(),
+        });
+        Ok(())
+    })?;
+    Ok(())
+}
+
+// Procesa un mensaje de juez; Err con el texto para el móvil
+fn handle_judge_message(judge: &mut Option<String>, message: JudgeMessage) -> Result<serde_json::Value, String> {
+    if let JudgeMessage::Join { judge_id, code } = message {
+        let valid = JUDGE_PANEL.read().unwrap().get(&judge_id).is_some_and(|seat| seat.code == code);
+        if !valid {
+            warn!(judge_id = %judge_id, "⚠️ Código de juez incorrecto");
+            return Err("Juez o código incorrecto".to_string());
+        }
+        if let Some(previous) = judge.replace(judge_id.clone()) {
+            disconnect(&previous);
+        }
+        *CONNECTED_JUDGES.lock().unwrap().entry(judge_id.clone()).or_default() += 1;
+        info!(judge_id = %judge_id, "🧑‍⚖️ Juez conectado");
+        return Ok(serde_json::json!({ "type": "judge_welcome", "data": round_state(&judge_id) }));
+    }
+
+    let judge_id = judge.as_deref().ok_or("Identifícate como juez primero")?;
+    let app_handle = APP_HANDLE.get().ok_or("La aplicación no está lista")?;
+    match message {
+        JudgeMessage::Join { .. } | JudgeMessage::State => {}
+        JudgeMessage::RoundScore { round, scores } => submit_card(app_handle, judge_id, round, scores)?,
+        JudgeMessage::Foul { fighter_id, foul, note } => mark_foul(app_handle, judge_id, fighter_id, foul, note)?,
+    }
+    Ok(serde_json::json!({ "type": "judge_ack", "data": round_state(judge_id) }))
+}
+
+/// Procesa un mensaje recibido por /ws; solo responde a los mensajes "judge_*"
+/// `judge` es el juez identificado en esa conexión
+pub fn handle_client_message(judge: &mut Option<String>, text: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    if !value.get("type")?.as_str()?.starts_with("judge_") {
+        return None;
+    }
+
+    let reply = serde_json::from_value::<JudgeMessage>(value)
+        .map_err(|e| format!("Mensaje de juez inválido: {}", e))
+        .and_then(|message| handle_judge_message(judge, message))
+        .unwrap_or_else(|error| serde_json::json!({ "type": "judge_error", "error": error }));
+    Some(reply.to_string())
+}
+
+/// Marca la desconexión de un juez
+pub fn disconnect(judge_id: &str) {
+    let mut connected = CONNECTED_JUDGES.lock().unwrap();
+    if let Some(count) = connected.get_mut(judge_id) {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            connected.remove(judge_id);
+            info!(judge_id = %judge_id, "🔌 Juez desconectado");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scoring::ScoringRuleSet;
+
+    // Combate terminado en el primer asalto con un panel de tantos jueces como tarjetas
+    fn session(cards: &[(u8, u8)]) -> CombatSession {
+        let judges: BTreeMap<String, String> = (0..cards.len())
+            .map(|index| (format!("judge_{}", index), format!("Juez {}", index)))
+            .collect();
+        let judge_cards = cards.iter().enumerate()
+            .map(|(index, &(red, blue))| JudgeCard {
+                judge_id: format!("judge_{}", index),
+                round: 1,
+                scores: BTreeMap::from([("red".to_string(), red), ("blue".to_string(), blue)]),
+                submitted_at: 0,
+                revision: 0,
+            })
+            .collect();
+        CombatSession {
+            id: "session_test".to_string(),
+            started_at: 0,
+            ended_at: Some(1),
+            rule_set: ScoringRuleSet::default(),
+            fighters: BTreeMap::from([("red".to_string(), "Rojo".to_string()), ("blue".to_string(), "Azul".to_string())]),
+            roster_ids: BTreeMap::new(),
+            status: SessionStatus::Finished,
+            current_round: 1,
+            round_started_at: 0,
+            rounds: Vec::new(),
+            event_log: Vec::new(),
+            winner: None,
+            turn_order: Vec::new(),
+            turn: None,
+            turns: Vec::new(),
+            audit_log: Vec::new(),
+            judges,
+            judge_cards,
+            fouls: Vec::new(),
+        }
+    }
+
+    fn assert_decision(cards: &[(u8, u8)], winner: Option<&str>, kind: DecisionKind) {
+        let session = session(cards);
+        let decision = decision(&session).expect("todas las tarjetas están entregadas");
+        assert_eq!(decision.winner.as_deref(), winner);
+        assert_eq!(decision.kind, kind);
+        assert_eq!(majority_winner(&round_tally(&session, 1), session.judges.len()).as_deref(), winner);
+    }
+
+    #[test]
+    fn unanimous_panel() {
+        assert_decision(&[(10, 9), (10, 8), (10, 9)], Some("red"), DecisionKind::Unanimous);
+    }
+
+    #[test]
+    fn split_panel() {
+        assert_decision(&[(10, 9), (9, 10), (10, 9)], Some("red"), DecisionKind::Split);
+    }
+
+    #[test]
+    fn majority_panel() {
+        assert_decision(&[(10, 9), (10, 10), (10, 9)], Some("red"), DecisionKind::Majority);
+    }
+
+    #[test]
+    fn one_card_of_three_is_not_a_majority() {
+        assert_decision(&[(10, 9), (10, 10), (10, 10)], None, DecisionKind::Draw);
+    }
+
+    #[test]
+    fn even_panel_split_is_a_draw() {
+        assert_decision(&[(10, 9), (9, 10), (10, 9), (9, 10)], None, DecisionKind::Draw);
+    }
+
+    #[test]
+    fn missing_card_has_no_decision() {
+        let mut session = session(&[(10, 9), (10, 9)]);
+        session.judge_cards.pop();
+        assert!(decision(&session).is_none());
+    }
+}
@@ -3,7 +3,32 @@
 mod simple_ble;
 mod combat_types;
 mod broadcast_ws;
+mod device_discovery;
+mod gatt_profile;
+mod device_info;
+mod firmware_ota;
+mod clock_sync;
+mod packet_decoder;
+mod storage;
+mod sensor_calibration;
+mod orientation;
+mod velocity;
+mod force_model;
+mod strike_classifier;
+mod imu_recording;
+mod strike_model;
+mod signal_filters;
+mod strike_tracker;
+mod imu_stream;
+mod sensor_health;
+mod scoring;
+mod judging;
+mod roster;
+mod limb_assignment;
+mod tournament;
+mod session_export;
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 use tracing::{info, error, debug};
@@ -13,7 +38,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 pub use combat_types::{SimpleCombatEvent, LimbType, ImuData, SimpleStats};
 
 // Usar la estructura BleDevice de simple_ble
-use simple_ble::BleDevice;
+use simple_ble::{BleDevice, ConnectedDeviceDetails};
 
 // Comando para escanear dispositivos BLE disponibles
 #[tauri::command]
@@ -32,6 +57,76 @@ async fn scan_ble_devices() -> Result<Vec<BleDevice>, String> {
     }
 }
 
+// Comando para iniciar el escaneo BLE continuo en segundo plano
+#[tauri::command]
+async fn start_continuous_scan(app_handle: AppHandle, stale_after_ms: Option<u64>) -> Result<String, String> {
+    info!(stale_after_ms = ?stale_after_ms, "📡 Iniciando escaneo BLE continuo");
+    
+    let mut config = device_discovery::ContinuousScanConfig::default();
+    if let Some(stale_after_ms) = stale_after_ms {
+        config.stale_after_ms = stale_after_ms;
+    }
+    
+    match device_discovery::start_continuous_scan(Arc::new(app_handle), config) {
+        Ok(_) => Ok("Escaneo continuo iniciado".to_string()),
+        Err(e) => {
+            error!(error = %e, "❌ Error iniciando escaneo continuo");
+            Err(format!("Error iniciando escaneo continuo: {}", e))
+        }
+    }
+}
+
+// Comando para detener el escaneo BLE continuo
+#[tauri::command]
+async fn stop_continuous_scan() -> Result<String, String> {
+    info!("🛑 Deteniendo escaneo BLE continuo");
+    
+    match device_discovery::stop_continuous_scan() {
+        Ok(_) => Ok("Escaneo continuo detenido".to_string()),
+        Err(e) => {
+            error!(error = %e, "❌ Error deteniendo escaneo continuo");
+            Err(format!("Error deteniendo escaneo continuo: {}", e))
+        }
+    }
+}
+
+// Comando para obtener los dispositivos visibles en el escaneo continuo
+#[tauri::command]
+async fn get_discovered_devices() -> Result<Vec<BleDevice>, String> {
+    if !device_discovery::is_continuous_scan_running() {
+        return Err("El escaneo continuo no está activo".to_string());
+    }
+    
+    Ok(device_discovery::get_discovered_devices())
+}
+
+// Comando para obtener el perfil GATT usado en escaneo y descubrimiento
+#[tauri::command]
+fn get_gatt_profile() -> Result<gatt_profile::GattProfileInfo, String> {
+    Ok(gatt_profile::GattProfileInfo::from(&gatt_profile::current_profile()))
+}
+
+// Comando para configurar UUIDs y descubrimiento de respaldo
+#[tauri::command]
+fn set_gatt_profile(
+    service_uuid: Option<String>,
+    imu_characteristic_uuid: Option<String>,
+    allow_fallback_discovery: Option<bool>,
+    filter_scan_by_service: Option<bool>,
+) -> Result<gatt_profile::GattProfileInfo, String> {
+    match gatt_profile::update_profile(service_uuid, imu_characteristic_uuid, allow_fallback_discovery, filter_scan_by_service) {
+        Ok(profile) => {
+            info!(service_uuid = %profile.service_uuid, characteristic_uuid = %profile.imu_characteristic_uuid,
+                  allow_fallback = profile.allow_fallback_discovery, "⚙️ Perfil GATT actualizado");
+            Ok(profile)
+        },
+        Err(e) => {
+            error!(error = %e, "❌ Error actualizando perfil GATT");
+            Err(e)
+        }
+    }
+}
+
 // Comando para conectar a un dispositivo específico
 #[tauri::command]
 async fn connect_to_device(app_handle: AppHandle, device_id: String) -> Result<String, String> {
@@ -51,18 +146,20 @@ async fn connect_to_device(app_handle: AppHandle, device_id: String) -> Result<S
     }
 }
 
-// Comando para conectar a un dispositivo con información del competidor
+// Comando para conectar a un dispositivo con un competidor de la plantilla
 #[tauri::command]
 async fn connect_to_device_with_competitor(
     app_handle: AppHandle, 
     device_id: String,
     competitor_id: u8,
-    competitor_name: String,
-    competitor_weight: f32
+    roster_id: String,
+    limb: Option<simple_ble::LimbType>
 ) -> Result<String, String> {
-    info!(device_id = %device_id, competitor_id = competitor_id, 
-          competitor_name = %competitor_name, competitor_weight = competitor_weight,
-          "🔗 Conectando dispositivo para competidor");
+    let competitor_name = roster::get_competitor(&roster_id)
+        .map(|entry| entry.competitor.name)
+        .ok_or_else(|| format!("No existe el competidor {} en la plantilla", roster_id))?;
+    info!(device_id = %device_id, competitor_id = competitor_id, roster_id = %roster_id,
+          competitor_name = %competitor_name, "🔗 Conectando dispositivo para competidor");
     
     let app_handle_arc = Arc::new(app_handle);
     
@@ -70,8 +167,8 @@ async fn connect_to_device_with_competitor(
         app_handle_arc, 
         device_id.clone(),
         competitor_id,
-        competitor_name.clone(),
-        competitor_weight
+        roster_id,
+        limb
     ).await {
         Ok(_) => {
             info!(device_id = %device_id, competitor_name = %competitor_name, 
@@ -148,6 +245,633 @@ async fn get_connected_devices() -> Result<Vec<String>, String> {
     }
 }
 
+// Comando para obtener dispositivos conectados con batería y firmware
+#[tauri::command]
+async fn get_connected_devices_details() -> Result<Vec<ConnectedDeviceDetails>, String> {
+    debug!("📋 Obteniendo detalles de dispositivos conectados");
+    
+    match simple_ble::get_connected_devices_details().await {
+        Ok(devices) => {
+            debug!(devices_count = devices.len(), "✅ Detalles de dispositivos conectados obtenidos");
+            Ok(devices)
+        },
+        Err(e) => {
+            error!(error = %e, "❌ Error obteniendo detalles de dispositivos conectados");
+            Err(format!("Error obteniendo detalles de dispositivos: {}", e))
+        }
+    }
+}
+
+// Comando para actualizar el firmware de una banda por OTA
+#[tauri::command]
+async fn start_firmware_update(
+    app_handle: AppHandle,
+    device_id: String,
+    image_path: String,
+    simulate: Option<bool>
+) -> Result<String, String> {
+    info!(device_id = %device_id, image_path = %image_path, "📦 Iniciando actualización de firmware");
+    
+    match firmware_ota::start_firmware_update(Arc::new(app_handle), device_id.clone(), image_path, simulate.unwrap_or(false)).await {
+        Ok(version) => Ok(format!("Actualizando {} a firmware {}", device_id, version)),
+        Err(e) => {
+            error!(device_id = %device_id, error = %e, "❌ Error iniciando actualización de firmware");
+            Err(format!("Error actualizando firmware de {}: {}", device_id, e))
+        }
+    }
+}
+
+// Comando para cancelar una actualización de firmware en curso
+#[tauri::command]
+fn cancel_firmware_update(app_handle: AppHandle, device_id: String) -> Result<String, String> {
+    firmware_ota::cancel_firmware_update(&app_handle, &device_id)
+        .map(|_| format!("Actualización de {} cancelada", device_id))
+}
+
+// Comando para obtener el progreso de la actualización de firmware
+#[tauri::command]
+fn get_firmware_update_status(device_id: String) -> Result<firmware_ota::FirmwareUpdateProgress, String> {
+    firmware_ota::get_firmware_update_status(&device_id)
+        .ok_or_else(|| format!("No hay actualizaciones registradas para {}", device_id))
+}
+
+// Comando para obtener estadísticas de recepción de paquetes por dispositivo
+#[tauri::command]
+fn get_packet_stats(device_id: Option<String>) -> Result<Vec<packet_decoder::DevicePacketStats>, String> {
+    let stats = packet_decoder::get_packet_stats();
+    
+    match device_id {
+        Some(id) => {
+            let device_stats: Vec<_> = stats.into_iter().filter(|s| s.device_id == id).collect();
+            if device_stats.is_empty() {
+                Err(format!("No hay estadísticas de paquetes para {}", id))
+            } else {
+                Ok(device_stats)
+            }
+        },
+        None => Ok(stats),
+    }
+}
+
+// Comando para reiniciar las estadísticas de paquetes
+#[tauri::command]
+fn reset_packet_stats() -> Result<String, String> {
+    packet_decoder::reset_packet_stats();
+    info!("🔄 Estadísticas de paquetes reseteadas");
+    Ok("Estadísticas de paquetes reseteadas".to_string())
+}
+
+// Comando para iniciar la calibración de una banda conectada
+#[tauri::command]
+fn start_device_calibration(
+    app_handle: AppHandle,
+    device_id: String,
+    six_orientation: Option<bool>
+) -> Result<sensor_calibration::CalibrationStatus, String> {
+    if !simple_ble::is_device_connected(&device_id) {
+        return Err(format!("El dispositivo {} no está conectado", device_id));
+    }
+    
+    Ok(sensor_calibration::start_calibration(&app_handle, &device_id, six_orientation.unwrap_or(false)))
+}
+
+// Comando para capturar la siguiente orientación de la calibración de seis posiciones
+#[tauri::command]
+fn capture_calibration_orientation(app_handle: AppHandle, device_id: String) -> Result<sensor_calibration::CalibrationStatus, String> {
+    sensor_calibration::capture_orientation(&app_handle, &device_id)
+}
+
+// Comando para cancelar una calibración en curso
+#[tauri::command]
+fn cancel_device_calibration(app_handle: AppHandle, device_id: String) -> Result<String, String> {
+    sensor_calibration::cancel_calibration(&app_handle, &device_id)
+        .map(|_| format!("Calibración de {} cancelada", device_id))
+}
+
+// Comando para obtener las calibraciones guardadas
+#[tauri::command]
+fn get_device_calibrations() -> Result<Vec<sensor_calibration::DeviceCalibration>, String> {
+    Ok(sensor_calibration::get_calibrations())
+}
+
+// Comando para eliminar la calibración de una banda
+#[tauri::command]
+fn clear_device_calibration(app_handle: AppHandle, device_id: String) -> Result<String, String> {
+    sensor_calibration::clear_calibration(&app_handle, &device_id)?;
+    info!(device_id = %device_id, "🔄 Calibración eliminada");
+    Ok(format!("Calibración de {} eliminada", device_id))
+}
+
+// Comando para obtener el modelo de fuerza activo
+#[tauri::command]
+fn get_force_model() -> Result<force_model::ForceModelConfig, String> {
+    Ok(force_model::current_force_model())
+}
+
+// Comando para cambiar el modelo de fuerza (y sus parámetros)
+#[tauri::command]
+fn set_force_model(app_handle: AppHandle, config: force_model::ForceModelConfig) -> Result<String, String> {
+    let name = config.model().name();
+    force_model::set_force_model(&app_handle, config)
+        .map(|_| format!("Modelo de fuerza '{}' activado", name))
+}
+
+// Comando para ajustar un modelo calibrado con una grabación de plataforma de fuerza
+#[tauri::command]
+fn fit_force_model(
+    app_handle: AppHandle,
+    recording_path: String,
+    activate: Option<bool>
+) -> Result<force_model::CalibratedModel, String> {
+    let model = force_model::fit_calibrated_model(&recording_path, force_model::DempsterModel::default())?;
+    info!(gain = model.gain, offset = model.offset, r_squared = model.r_squared, samples = model.sample_count,
+          "🧮 Modelo de fuerza calibrado");
+    
+    if activate.unwrap_or(true) {
+        force_model::set_force_model(&app_handle, force_model::ForceModelConfig::Calibrated(model.clone()))?;
+    }
+    Ok(model)
+}
+
+// Comando para obtener las clases de golpes y sus umbrales
+#[tauri::command]
+fn get_strike_classifier_config() -> Result<strike_classifier::StrikeClassifierConfig, String> {
+    Ok(strike_classifier::current_config())
+}
+
+// Comando para reemplazar las clases de golpes y sus umbrales
+#[tauri::command]
+fn set_strike_classifier_config(
+    app_handle: AppHandle,
+    config: strike_classifier::StrikeClassifierConfig
+) -> Result<String, String> {
+    let classes = config.classes.len();
+    strike_classifier::set_config(&app_handle, config)?;
+    info!(classes = classes, "🥋 Clases de golpes actualizadas");
+    Ok(format!("{} clases de golpes configuradas", classes))
+}
+
+// Comando para grabar datos IMU de un dispositivo (para entrenamiento)
+#[tauri::command]
+fn start_imu_recording(app_handle: AppHandle, device_id: String, note: Option<String>) -> Result<String, String> {
+    if !simple_ble::is_device_connected(&device_id) {
+        return Err(format!("El dispositivo {} no está conectado", device_id));
+    }
+    imu_recording::start_recording(&app_handle, &device_id, note)
+}
+
+// Comando para detener una grabación IMU
+#[tauri::command]
+fn stop_imu_recording(device_id: String) -> Result<imu_recording::RecordingSummary, String> {
+    imu_recording::stop_recording(&device_id)
+}
+
+// Comando para que el operador etiquete el golpe recién realizado
+#[tauri::command]
+fn tag_recorded_strike(device_id: String, strike_type: String, timestamp: Option<u64>) -> Result<String, String> {
+    imu_recording::tag_strike(&device_id, &strike_type, timestamp)?;
+    Ok(format!("Golpe '{}' etiquetado", strike_type))
+}
+
+// Comando para listar las grabaciones guardadas
+#[tauri::command]
+fn list_imu_recordings(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    imu_recording::list_recordings(&app_handle)
+}
+
+// Comando para leer un tramo de una grabación (señal cruda frente a filtrada)
+#[tauri::command]
+fn get_recording_samples(
+    app_handle: AppHandle,
+    file_name: String,
+    offset: Option<usize>,
+    limit: Option<usize>
+) -> Result<Vec<imu_recording::RecordedSample>, String> {
+    let path = imu_recording::recording_path(&app_handle, &file_name)?;
+    imu_recording::read_recording_samples(&path, offset.unwrap_or(0), limit.unwrap_or(2000))
+}
+
+// Comando para entrenar el clasificador de golpes con grabaciones etiquetadas
+// El entrenamiento corre en un hilo bloqueante para no congelar la interfaz
+#[tauri::command]
+async fn train_strike_model(
+    app_handle: AppHandle,
+    recordings: Option<Vec<String>>,
+    export_path: Option<String>,
+    activate: Option<bool>
+) -> Result<strike_model::TrainingReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let names = match recordings {
+            Some(names) if !names.is_empty() => names,
+            _ => imu_recording::list_recordings(&app_handle)?,
+        };
+        let paths = names.iter()
+            .map(|name| imu_recording::recording_path(&app_handle, name))
+            .collect::<Result<Vec<_>, String>>()?;
+        let path_refs: Vec<&std::path::Path> = paths.iter().map(|path| path.as_path()).collect();
+
+        let pre_onset_ms = strike_classifier::current_config().pre_onset_ms;
+        let (model, report) = strike_model::train_model(&path_refs, pre_onset_ms)?;
+        info!(strikes = report.strikes, accuracy = ?report.accuracy, "🧠 Modelo de golpes entrenado");
+
+        if let Some(path) = export_path {
+            strike_model::export_model(&model, std::path::Path::new(&path))?;
+        }
+        if activate.unwrap_or(true) {
+            strike_model::activate_model(&app_handle, model)?;
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de entrenamiento: {}", e))?
+}
+
+// Comando para cargar un modelo de golpes exportado
+#[tauri::command]
+fn load_strike_model(app_handle: AppHandle, path: String) -> Result<String, String> {
+    let model = strike_model::read_model(std::path::Path::new(&path))?;
+    strike_model::activate_model(&app_handle, model)?;
+    Ok(format!("Modelo de golpes cargado desde {}", path))
+}
+
+// Comando para volver a las reglas de clasificación configuradas
+#[tauri::command]
+fn clear_strike_model(app_handle: AppHandle) -> Result<String, String> {
+    strike_model::clear_model(&app_handle)?;
+    info!("🔄 Modelo de golpes desactivado");
+    Ok("Clasificación por reglas activada".to_string())
+}
+
+// Comando para obtener la cadena de filtros efectiva de un dispositivo
+#[tauri::command]
+fn get_filter_config(device_id: String) -> Result<signal_filters::FilterChainConfig, String> {
+    Ok(signal_filters::device_filter_config(&device_id)
+        .unwrap_or_else(|| simple_ble::SimpleDetectionConfig::default().filters))
+}
+
+// Comando para configurar la cadena de filtros de un dispositivo (None = valores por defecto)
+#[tauri::command]
+fn set_filter_config(
+    app_handle: AppHandle,
+    device_id: String,
+    config: Option<signal_filters::FilterChainConfig>
+) -> Result<String, String> {
+    signal_filters::set_device_filter_config(&app_handle, &device_id, config)?;
+    info!(device_id = %device_id, "🎛️ Filtros actualizados");
+    Ok(format!("Filtros de {} actualizados", device_id))
+}
+
+// Comando para transmitir muestras IMU en vivo (canal Tauri y/o WebSocket)
+#[tauri::command]
+fn start_imu_stream(
+    device_id: String,
+    rate_hz: Option<f32>,
+    websocket: Option<bool>,
+    on_frame: Option<tauri::ipc::Channel<imu_stream::ImuStreamFrame>>
+) -> Result<imu_stream::ImuStreamStatus, String> {
+    if !simple_ble::is_device_connected(&device_id) {
+        return Err(format!("El dispositivo {} no está conectado", device_id));
+    }
+    imu_stream::start_stream(&device_id, rate_hz, on_frame, websocket.unwrap_or(false))
+}
+
+// Comando para detener la transmisión IMU de un dispositivo
+#[tauri::command]
+fn stop_imu_stream(device_id: String) -> Result<String, String> {
+    imu_stream::stop_stream(&device_id)
+        .map(|_| format!("Transmisión IMU de {} detenida", device_id))
+}
+
+// Comando para obtener el diagnóstico de salud de las bandas
+#[tauri::command]
+fn get_sensor_health(device_id: Option<String>) -> Result<Vec<sensor_health::DeviceHealthReport>, String> {
+    Ok(sensor_health::get_health(device_id.as_deref()))
+}
+
+// Comando para listar las transmisiones IMU activas
+#[tauri::command]
+fn get_imu_streams() -> Result<Vec<imu_stream::ImuStreamStatus>, String> {
+    Ok(imu_stream::active_streams())
+}
+
+// Comando para listar los conjuntos de reglas de puntuación
+#[tauri::command]
+fn get_scoring_rule_sets() -> Result<Vec<scoring::ScoringRuleSet>, String> {
+    Ok(scoring::rule_sets())
+}
+
+// Comando para guardar un conjunto de reglas de puntuación
+#[tauri::command]
+fn save_scoring_rule_set(app_handle: AppHandle, rule_set: scoring::ScoringRuleSet) -> Result<String, String> {
+    let name = rule_set.name.clone();
+    scoring::save_rule_set(&app_handle, rule_set)?;
+    info!(name = %name, "📏 Reglas de puntuación guardadas");
+    Ok(format!("Reglas {} guardadas", name))
+}
+
+// Comando para eliminar un conjunto de reglas de puntuación
+#[tauri::command]
+fn delete_scoring_rule_set(app_handle: AppHandle, name: String) -> Result<String, String> {
+    scoring::delete_rule_set(&app_handle, &name)?;
+    Ok(format!("Reglas {} eliminadas", name))
+}
+
+// Comando para empezar una sesión de combate (fighter_id -> nombre y, opcional, fighter_id -> roster_id)
+#[tauri::command]
+fn start_combat_session(
+    app_handle: AppHandle,
+    rule_set: Option<String>,
+    fighters: BTreeMap<String, String>,
+    roster_ids: Option<BTreeMap<String, String>>,
+    turn_order: Option<Vec<String>>
+) -> Result<scoring::ScoreBoard, String> {
+    scoring::start_session(&app_handle, rule_set.as_deref(), fighters, roster_ids.unwrap_or_default(), turn_order)
+}
+
+// Comando para abrir la ventana de golpe del turno en curso (modo por turnos)
+#[tauri::command]
+fn open_turn_window(app_handle: AppHandle) -> Result<scoring::ScoreBoard, String> {
+    scoring::open_turn_window(&app_handle)
+}
+
+// Comando para cerrar el asalto en curso
+#[tauri::command]
+fn end_combat_round(app_handle: AppHandle) -> Result<scoring::ScoreBoard, String> {
+    scoring::end_round(&app_handle)
+}
+
+// Comando para terminar el combate antes de tiempo
+#[tauri::command]
+fn finish_combat_session(app_handle: AppHandle) -> Result<scoring::ScoreBoard, String> {
+    scoring::finish_session(&app_handle)
+}
+
+// Comando para que el árbitro aplique una penalización
+#[tauri::command]
+fn apply_penalty(
+    app_handle: AppHandle,
+    fighter_id: String,
+    penalty: String,
+    note: Option<String>
+) -> Result<scoring::ScoreBoard, String> {
+    scoring::apply_penalty(&app_handle, &fighter_id, &penalty, note)
+}
+
+// Comando para que el árbitro anule un evento del registro
+#[tauri::command]
+fn void_combat_event(
+    app_handle: AppHandle,
+    entry_id: u64,
+    referee: String,
+    reason: String
+) -> Result<scoring::ScoreBoard, String> {
+    scoring::void_entry(&app_handle, entry_id, &referee, &reason)
+}
+
+// Comando para que el árbitro añada un golpe no detectado
+#[tauri::command]
+fn add_manual_strike(
+    app_handle: AppHandle,
+    fighter_id: String,
+    event_type: String,
+    force: Option<f32>,
+    round: Option<u32>,
+    referee: String,
+    reason: String
+) -> Result<scoring::ScoreBoard, String> {
+    scoring::add_manual_strike(&app_handle, &fighter_id, &event_type, force, round, &referee, &reason)
+}
+
+// Comando para que el árbitro corrija un golpe registrado
+#[tauri::command]
+fn amend_combat_event(
+    app_handle: AppHandle,
+    entry_id: u64,
+    amendment: scoring::StrikeAmendment,
+    referee: String,
+    reason: String
+) -> Result<scoring::ScoreBoard, String> {
+    scoring::amend_strike(&app_handle, entry_id, amendment, &referee, &reason)
+}
+
+// Comando para obtener el marcador actual
+#[tauri::command]
+fn get_scoreboard() -> Result<Option<scoring::ScoreBoard>, String> {
+    Ok(scoring::current_scoreboard())
+}
+
+// Comando para obtener la sesión actual con su registro de eventos
+#[tauri::command]
+fn get_combat_session() -> Result<Option<scoring::CombatSession>, String> {
+    Ok(scoring::current_session())
+}
+
+// Comando para listar las sesiones guardadas
+#[tauri::command]
+fn list_combat_sessions(app_handle: AppHandle) -> Result<Vec<String>, String> {
+    scoring::list_sessions(&app_handle)
+}
+
+// Comando para leer una sesión guardada
+#[tauri::command]
+fn load_combat_session(app_handle: AppHandle, session_id: String) -> Result<scoring::CombatSession, String> {
+    scoring::load_session(&app_handle, &session_id)
+}
+
+// Comando para exportar una sesión (la actual sin session_id) a CSV, JSON Lines o Parquet
+#[tauri::command]
+async fn export_combat_session(
+    app_handle: AppHandle,
+    session_id: Option<String>,
+    directory: String,
+    formats: Option<Vec<session_export::ExportFormat>>
+) -> Result<session_export::ExportSummary, String> {
+    // Leer grabaciones y escribir los archivos no debe bloquear el hilo de comandos
+    tokio::task::spawn_blocking(move || {
+        session_export::export_session(&app_handle, session_id.as_deref(), &directory, &formats.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Error en la tarea de exportación: {}", e))?
+}
+
+// Comando para listar la plantilla de competidores
+#[tauri::command]
+fn list_competitors() -> Result<Vec<roster::RosterEntry>, String> {
+    Ok(roster::list_competitors())
+}
+
+// Comando para obtener un competidor de la plantilla
+#[tauri::command]
+fn get_competitor(roster_id: String) -> Result<Option<roster::RosterEntry>, String> {
+    Ok(roster::get_competitor(&roster_id))
+}
+
+// Comando para crear o actualizar un competidor
+#[tauri::command]
+fn save_competitor(app_handle: AppHandle, profile: roster::CompetitorProfile) -> Result<roster::RosterEntry, String> {
+    roster::save_competitor(&app_handle, profile)
+}
+
+// Comando para eliminar un competidor de la plantilla
+#[tauri::command]
+fn delete_competitor(app_handle: AppHandle, roster_id: String) -> Result<(), String> {
+    roster::delete_competitor(&app_handle, &roster_id)?;
+    limb_assignment::delete_profile(&app_handle, &roster_id)?;
+    Ok(())
+}
+
+// Comando para registrar un pesaje
+#[tauri::command]
+fn record_weigh_in(
+    app_handle: AppHandle,
+    roster_id: String,
+    weight_kg: f32,
+    note: Option<String>
+) -> Result<roster::RosterEntry, String> {
+    roster::record_weigh_in(&app_handle, &roster_id, weight_kg, note)
+}
+
+// Comando para obtener las categorías de peso
+#[tauri::command]
+fn get_weight_classes() -> Result<Vec<roster::WeightClass>, String> {
+    Ok(roster::weight_classes())
+}
+
+// Comando para guardar las categorías de peso
+#[tauri::command]
+fn save_weight_classes(app_handle: AppHandle, classes: Vec<roster::WeightClass>) -> Result<Vec<roster::WeightClass>, String> {
+    roster::save_weight_classes(&app_handle, classes)
+}
+
+// Comando para obtener los perfiles de bandas por competidor
+#[tauri::command]
+fn get_limb_profiles() -> Result<Vec<limb_assignment::AssignmentProfile>, String> {
+    Ok(limb_assignment::profiles())
+}
+
+// Comando para guardar qué banda va en cada extremidad de un competidor
+#[tauri::command]
+fn save_limb_profile(
+    app_handle: AppHandle,
+    roster_id: String,
+    assignments: Vec<limb_assignment::LimbAssignment>
+) -> Result<limb_assignment::AssignmentProfile, String> {
+    if roster::get_competitor(&roster_id).is_none() {
+        return Err(format!("No existe el competidor {} en la plantilla", roster_id));
+    }
+    limb_assignment::save_profile(&app_handle, &roster_id, assignments)
+}
+
+// Comando para eliminar el perfil de bandas de un competidor
+#[tauri::command]
+fn delete_limb_profile(app_handle: AppHandle, roster_id: String) -> Result<bool, String> {
+    limb_assignment::delete_profile(&app_handle, &roster_id)
+}
+
+// Comando para conectar todas las bandas del perfil de un competidor
+#[tauri::command]
+async fn connect_competitor_kit(
+    app_handle: AppHandle,
+    competitor_id: u8,
+    roster_id: String
+) -> Result<Vec<limb_assignment::KitConnection>, String> {
+    limb_assignment::connect_kit(Arc::new(app_handle), competitor_id, &roster_id).await
+}
+
+// Comando para listar los torneos
+#[tauri::command]
+fn list_tournaments() -> Result<Vec<tournament::Tournament>, String> {
+    Ok(tournament::list_tournaments())
+}
+
+// Comando para obtener un torneo
+#[tauri::command]
+fn get_tournament(tournament_id: String) -> Result<Option<tournament::Tournament>, String> {
+    Ok(tournament::get_tournament(&tournament_id))
+}
+
+// Comando para crear un torneo
+#[tauri::command]
+fn create_tournament(app_handle: AppHandle, name: String) -> Result<tournament::Tournament, String> {
+    tournament::create_tournament(&app_handle, &name)
+}
+
+// Comando para eliminar un torneo
+#[tauri::command]
+fn delete_tournament(app_handle: AppHandle, tournament_id: String) -> Result<(), String> {
+    tournament::delete_tournament(&app_handle, &tournament_id)
+}
+
+// Comando para añadir una división con sus competidores
+#[tauri::command]
+fn add_tournament_division(
+    app_handle: AppHandle,
+    tournament_id: String,
+    name: String,
+    format: tournament::BracketFormat,
+    rule_set: Option<String>,
+    weight_class: Option<String>,
+    entrants: Vec<String>
+) -> Result<tournament::Tournament, String> {
+    tournament::add_division(&app_handle, &tournament_id, &name, format, rule_set, weight_class, entrants)
+}
+
+// Comando para generar el cuadro de una división
+#[tauri::command]
+fn generate_bracket(app_handle: AppHandle, tournament_id: String, division_id: String) -> Result<tournament::Tournament, String> {
+    tournament::generate_bracket(&app_handle, &tournament_id, &division_id)
+}
+
+// Comando para empezar una pelea del cuadro como sesión de combate
+#[tauri::command]
+fn start_bracket_match(
+    app_handle: AppHandle,
+    tournament_id: String,
+    division_id: String,
+    match_id: String
+) -> Result<scoring::ScoreBoard, String> {
+    tournament::start_match(&app_handle, &tournament_id, &division_id, &match_id)
+}
+
+// Comando para fijar el ganador de una pelea del cuadro
+#[tauri::command]
+fn set_bracket_match_winner(
+    app_handle: AppHandle,
+    tournament_id: String,
+    division_id: String,
+    match_id: String,
+    winner: Option<String>
+) -> Result<tournament::Tournament, String> {
+    tournament::set_match_winner(&app_handle, &tournament_id, &division_id, &match_id, winner)
+}
+
+// Comando para volver a publicar el cuadro (vista del proyector)
+#[tauri::command]
+fn broadcast_bracket(app_handle: AppHandle, tournament_id: String) -> Result<(), String> {
+    tournament::broadcast_tournament(&app_handle, &tournament_id)
+}
+
+// Comando para configurar el panel de jueces que puntúan desde el móvil
+#[tauri::command]
+fn configure_judges(
+    app_handle: AppHandle,
+    judges: Vec<judging::JudgeSeat>
+) -> Result<Vec<judging::JudgeStatus>, String> {
+    judging::configure_panel(&app_handle, judges)
+}
+
+// Comando para obtener el panel de jueces y su conexión
+#[tauri::command]
+fn get_judge_panel() -> Result<Vec<judging::JudgeStatus>, String> {
+    Ok(judging::panel_status())
+}
+
+// Comando para obtener el modelo de golpes activo
+#[tauri::command]
+fn get_strike_model() -> Result<Option<strike_model::TrainedStrikeModel>, String> {
+    Ok(strike_model::active_model())
+}
+
 // Comando para obtener información del sistema BLE
 #[tauri::command]
 async fn get_ble_info() -> Result<String, String> {
@@ -187,20 +911,28 @@ async fn connect_multiple_devices(
             .ok_or("deviceId requerido")?;
         let competitor_id = connection["competitorId"].as_u64()
             .ok_or("competitorId requerido")? as u8;
-        let competitor_name = connection["competitorName"].as_str()
-            .ok_or("competitorName requerido")?;
-        let competitor_weight = connection["competitorWeight"].as_f64()
-            .ok_or("competitorWeight requerido")? as f32;
+        let roster_id = connection["rosterId"].as_str()
+            .ok_or("rosterId requerido")?;
+        // Sin competidor en la plantilla la conexión fallaría igualmente: se informa y se sigue
+        let Some(competitor_name) = roster::get_competitor(roster_id).map(|entry| entry.competitor.name) else {
+            let error_msg = format!("❌ No existe el competidor {} en la plantilla", roster_id);
+            error!(device_id = %device_id, roster_id = %roster_id, "{}", error_msg);
+            results.push(error_msg);
+            continue;
+        };
+        // Extremidad opcional; sin ella se usa el perfil guardado o el nombre de la banda
+        let limb: Option<simple_ble::LimbType> = serde_json::from_value(connection["limb"].clone())
+            .map_err(|e| format!("limb inválido para {}: {}", device_id, e))?;
         
-        info!(device_id = %device_id, competitor_name = %competitor_name, competitor_weight = competitor_weight,
+        info!(device_id = %device_id, roster_id = %roster_id, competitor_name = %competitor_name,
               "🔗 Conectando dispositivo para competidor");
         
         match simple_ble::connect_to_device_with_competitor(
             app_handle_arc.clone(),
             device_id.to_string(),
             competitor_id,
-            competitor_name.to_string(),
-            competitor_weight
+            roster_id.to_string(),
+            limb
         ).await {
             Ok(_) => {
                 let success_msg = format!("✅ {} conectado para {}", device_id, competitor_name);
@@ -227,34 +959,41 @@ async fn connect_multiple_devices(
 // Comando para obtener información del sistema
 #[tauri::command]
 fn get_system_info() -> Result<serde_json::Value, String> {
+    let classifier = strike_classifier::current_config();
     let info = serde_json::json!({
         "version": "1.0.0",
         "system": "Simple BLE Combat Detection",
-        "supported_events": ["punch", "slap", "kick"],
+        "supported_events": classifier.class_names(),
         "supported_limbs": ["LeftHand", "RightHand", "LeftFoot", "RightFoot"],
-        "thresholds": {
-            "punch": "2.5g",
-            "slap": "1.8g", 
-            "kick": "3.0g"
-        },
-        "cooldown_ms": 500
+        "strike_classes": classifier.classes,
+        "min_confidence": classifier.min_confidence,
+        "refractory": simple_ble::SimpleDetectionConfig::default().refractory
     });
     
     Ok(info)
 }
 
-// Comando para obtener estadísticas (placeholder)
+// Comando para obtener estadísticas (derivadas del registro de la sesión, con correcciones)
 #[tauri::command]
 fn get_combat_stats() -> Result<serde_json::Value, String> {
+    let session = scoring::session_stats().unwrap_or_default();
+    let count = |matches: fn(&str) -> bool| -> u32 {
+        session.by_type.iter().filter(|(event_type, _)| matches(event_type)).map(|(_, count)| count).sum()
+    };
+
     let stats = serde_json::json!({
-        "total_events": 0,
-        "punches": 0,
-        "slaps": 0,
-        "kicks": 0,
-        "connected_devices": 0,
-        "last_event_time": null
+        "total_events": session.total_events,
+        "punches": count(|event_type| event_type == "punch"),
+        "slaps": count(|event_type| event_type == "slap"),
+        "kicks": count(|event_type| event_type.starts_with("kick")),
+        "connected_devices": simple_ble::connected_device_handles().len(),
+        "last_event_time": session.last_event_time,
+        "voided_events": session.voided_events,
+        "manual_events": session.manual_events,
+        "by_type": session.by_type,
+        "by_fighter": session.by_fighter
     });
-    
+
     Ok(stats)
 }
 
@@ -321,12 +1060,86 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             start_ble_system,
             scan_ble_devices,
+            start_continuous_scan,
+            stop_continuous_scan,
+            get_discovered_devices,
+            get_gatt_profile,
+            set_gatt_profile,
             connect_to_device,
             connect_to_device_with_competitor,
             connect_multiple_devices,
             disconnect_from_device,
             disconnect_all_devices,
             get_connected_devices,
+            get_connected_devices_details,
+            start_firmware_update,
+            cancel_firmware_update,
+            get_firmware_update_status,
+            get_packet_stats,
+            reset_packet_stats,
+            start_device_calibration,
+            capture_calibration_orientation,
+            cancel_device_calibration,
+            get_device_calibrations,
+            clear_device_calibration,
+            get_force_model,
+            set_force_model,
+            fit_force_model,
+            get_strike_classifier_config,
+            set_strike_classifier_config,
+            start_imu_recording,
+            stop_imu_recording,
+            tag_recorded_strike,
+            list_imu_recordings,
+            get_recording_samples,
+            train_strike_model,
+            load_strike_model,
+            clear_strike_model,
+            get_strike_model,
+            get_filter_config,
+            set_filter_config,
+            start_imu_stream,
+            stop_imu_stream,
+            get_imu_streams,
+            get_sensor_health,
+            get_scoring_rule_sets,
+            save_scoring_rule_set,
+            delete_scoring_rule_set,
+            start_combat_session,
+            open_turn_window,
+            end_combat_round,
+            finish_combat_session,
+            apply_penalty,
+            void_combat_event,
+            add_manual_strike,
+            amend_combat_event,
+            get_scoreboard,
+            get_combat_session,
+            list_combat_sessions,
+            load_combat_session,
+            export_combat_session,
+            list_competitors,
+            get_competitor,
+            save_competitor,
+            delete_competitor,
+            record_weigh_in,
+            get_weight_classes,
+            save_weight_classes,
+            get_limb_profiles,
+            save_limb_profile,
+            delete_limb_profile,
+            connect_competitor_kit,
+            list_tournaments,
+            get_tournament,
+            create_tournament,
+            delete_tournament,
+            add_tournament_division,
+            generate_bracket,
+            start_bracket_match,
+            set_bracket_match_winner,
+            broadcast_bracket,
+            configure_judges,
+            get_judge_panel,
             get_ble_info,
             get_system_info,
             get_combat_stats,
@@ -337,6 +1150,22 @@ pub fn run() {
             // Inicializar sistema de logging optimizado
             init_tracing();
 
+            // Cargar calibraciones de sensores guardadas
+            sensor_calibration::load_calibrations(app.handle());
+            force_model::load_force_model(app.handle());
+            strike_classifier::load_config(app.handle());
+            strike_model::load_active_model(app.handle());
+            signal_filters::load_filter_configs(app.handle());
+            scoring::load_rule_sets(app.handle());
+            judging::attach(app.handle().clone());
+            judging::load_panel(app.handle());
+            roster::load_roster(app.handle());
+            limb_assignment::load_profiles(app.handle());
+            tournament::load_tournaments(app.handle());
+            
+            // Vigilar la salud de las bandas conectadas
+            sensor_health::start_health_monitor(Arc::new(app.handle().clone()));
+
             // Resolver ruta de archivos estáticos
             let resource_path = resolve_static_path(app);
         
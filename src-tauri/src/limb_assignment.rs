@@ -0,0 +1,206 @@
+// Asignación de bandas a extremidades por competidor
+// Perfiles guardados (dispositivo -> extremidad) por competidor de la plantilla, con prioridad:
+// extremidad indicada al conectar > perfil guardado > nombre anunciado por la banda
+// Un peleador no puede tener dos bandas conectadas en la misma extremidad
+
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::simple_ble::{self, LimbType};
+use crate::storage;
+
+const PROFILES_FILE: &str = "limb_assignments.json";
+const ALL_LIMBS: [LimbType; 4] = [LimbType::LeftHand, LimbType::RightHand, LimbType::LeftFoot, LimbType::RightFoot];
+
+/// Banda asignada a una extremidad
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LimbAssignment {
+    pub device_id: String,
+    pub limb: LimbType,
+}
+
+/// Equipo de bandas de un competidor
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AssignmentProfile {
+    pub roster_id: String,
+    pub assignments: Vec<LimbAssignment>,
+    pub updated_at: u64,
+}
+
+/// Resultado de conectar una banda del equipo
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct KitConnection {
+    pub device_id: String,
+    pub limb: LimbType,
+    pub error: Option<String>,
+}
+
+static PROFILES: Lazy<RwLock<BTreeMap<String, AssignmentProfile>>> = Lazy::new(|| RwLock::new(BTreeMap::new()));
+// Bandas conectadas: device_id -> (fighter_id, extremidad)
+static ACTIVE_LIMBS: Lazy<Mutex<HashMap<String, (String, LimbType)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Extremidad según el nombre anunciado; error si el nombre no sigue el patrón BH
+pub fn infer_limb(device_name: &str) -> Result<LimbType, String> {
+    ALL_LIMBS.iter()
+        .find(|limb| device_name.contains(limb.ble_name_pattern()))
+        .copied()
+        .ok_or_else(|| format!(
+            "No se reconoce la extremidad de {}; asígnala manualmente o en el perfil del competidor",
+            device_name
+        ))
+}
+
+/// Extremidad de una banda: la indicada, la del perfil guardado o la del nombre
+pub fn resolve_limb(device_id: &str, device_name: &str, limb_override: Option<LimbType>) -> Result<LimbType, String> {
+    if let Some(limb) = limb_override {
+        return Ok(limb);
+    }
+    let assigned = PROFILES.read().unwrap().values()
+        .flat_map(|profile| &profile.assignments)
+        .find(|assignment| assignment.device_id == device_id)
+        .map(|assignment| assignment.limb);
+    match assigned {
+        Some(limb) => Ok(limb),
+        None => infer_limb(device_name),
+    }
+}
+
+/// Reserva la extremidad de un peleador para una banda; error si ya tiene otra banda en ella
+pub fn claim(device_id: &str, fighter_id: &str, limb: LimbType) -> Result<(), String> {
+    let mut active = ACTIVE_LIMBS.lock().unwrap();
+    let taken = active.iter().find(|(other_id, (other_fighter, other_limb))| {
+        other_id.as_str() != device_id && other_fighter == fighter_id && *other_limb == limb
+    });
+    if let Some((other_id, _)) = taken {
+        return Err(format!("{} ya tiene la banda {} en {}", fighter_id, other_id, limb.name()));
+    }
+    active.insert(device_id.to_string(), (fighter_id.to_string(), limb));
+    Ok(())
+}
+
+/// Libera la extremidad de una banda desconectada
+pub fn release(device_id: &str) {
+    ACTIVE_LIMBS.lock().unwrap().remove(device_id);
+}
+
+/// Libera todas las extremidades
+pub fn release_all() {
+    ACTIVE_LIMBS.lock().unwrap().clear();
+}
+
+/// Carga los perfiles guardados al iniciar la aplicación
+pub fn load_profiles<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(saved) = storage::load_json::<BTreeMap<String, AssignmentProfile>, R>(app_handle, PROFILES_FILE) else {
+        return;
+    };
+    info!(profiles = saved.len(), "🦾 Perfiles de asignación de bandas cargados");
+    *PROFILES.write().unwrap() = saved;
+}
+
+/// Perfiles guardados
+pub fn profiles() -> Vec<AssignmentProfile> {
+    PROFILES.read().unwrap().values().cloned().collect()
+}
+
+/// Perfil de un competidor
+pub fn profile(roster_id: &str) -> Option<AssignmentProfile> {
+    PROFILES.read().unwrap().get(roster_id).cloned()
+}
+
+/// Guarda el equipo de bandas de un competidor
+pub fn save_profile<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    roster_id: &str,
+    assignments: Vec<LimbAssignment>,
+) -> Result<AssignmentProfile, String> {
+    if assignments.is_empty() {
+        return Err("El perfil necesita al menos una banda".to_string());
+    }
+    for (index, assignment) in assignments.iter().enumerate() {
+        let earlier = &assignments[..index];
+        if earlier.iter().any(|other| other.device_id == assignment.device_id) {
+            return Err(format!("La banda {} está asignada dos veces", assignment.device_id));
+        }
+        if earlier.iter().any(|other| other.limb == assignment.limb) {
+            return Err(format!("Hay dos bandas en {}", assignment.limb.name()));
+        }
+    }
+
+    let mut profiles = PROFILES.write().unwrap();
+    // Una banda solo pertenece al equipo de un competidor
+    let conflict = profiles.values()
+        .filter(|profile| profile.roster_id != roster_id)
+        .find_map(|profile| {
+            profile.assignments.iter()
+                .find(|assigned| assignments.iter().any(|assignment| assignment.device_id == assigned.device_id))
+                .map(|assigned| (profile.roster_id.clone(), assigned.device_id.clone()))
+        });
+    if let Some((other_roster_id, device_id)) = conflict {
+        return Err(format!("La banda {} ya está en el perfil de {}", device_id, other_roster_id));
+    }
+
+    let profile = AssignmentProfile {
+        roster_id: roster_id.to_string(),
+        assignments,
+        updated_at: now_ms(),
+    };
+    profiles.insert(roster_id.to_string(), profile.clone());
+    storage::save_json(app_handle, PROFILES_FILE, &*profiles)?;
+    info!(roster_id = %roster_id, bands = profile.assignments.len(), "🦾 Perfil de bandas guardado");
+    Ok(profile)
+}
+
+/// Elimina el perfil de un competidor (no es error si no tenía)
+pub fn delete_profile<R: tauri::Runtime>(app_handle: &AppHandle<R>, roster_id: &str) -> Result<bool, String> {
+    let mut profiles = PROFILES.write().unwrap();
+    if profiles.remove(roster_id).is_none() {
+        return Ok(false);
+    }
+    storage::save_json(app_handle, PROFILES_FILE, &*profiles)?;
+    info!(roster_id = %roster_id, "🗑️ Perfil de bandas eliminado");
+    Ok(true)
+}
+
+/// Conecta todas las bandas del perfil de un competidor con su extremidad guardada
+pub async fn connect_kit<R: tauri::Runtime>(
+    app_handle: Arc<AppHandle<R>>,
+    competitor_id: u8,
+    roster_id: &str,
+) -> Result<Vec<KitConnection>, String> {
+    let profile = profile(roster_id)
+        .ok_or_else(|| format!("{} no tiene perfil de bandas", roster_id))?;
+    info!(roster_id = %roster_id, competitor_id = competitor_id, bands = profile.assignments.len(), "🔗 Conectando equipo del competidor");
+
+    let mut results = Vec::new();
+    for LimbAssignment { device_id, limb } in profile.assignments {
+        let result = if simple_ble::is_device_connected(&device_id) {
+            Err("La banda ya está conectada".to_string())
+        } else {
+            simple_ble::connect_to_device_with_competitor(
+                app_handle.clone(),
+                device_id.clone(),
+                competitor_id,
+                roster_id.to_string(),
+                Some(limb),
+            ).await
+        };
+        if let Err(e) = &result {
+            warn!(device_id = %device_id, limb = ?limb, error = %e, "⚠️ Banda del equipo sin conectar");
+        }
+        results.push(KitConnection { device_id, limb, error: result.err() });
+    }
+
+    let connected = results.iter().filter(|result| result.error.is_none()).count();
+    info!(roster_id = %roster_id, connected = connected, total = results.len(), "🏁 Equipo del competidor conectado");
+    Ok(results)
+}
@@ -0,0 +1,143 @@
+// Estimación de orientación por dispositivo (filtro de Madgwick)
+// Fusiona acelerómetro y giroscopio para restar la gravedad y expresar
+// la aceleración lineal en un marco mundial (Z hacia arriba)
+
+// Ganancia de corrección del acelerómetro (mayor = converge más rápido, más ruido)
+const MADGWICK_BETA: f32 = 0.1;
+// Solo se corrige con el acelerómetro cuando la magnitud está cerca de 1g;
+// durante un golpe la orientación se integra únicamente con el giroscopio
+const MAX_ACC_DEVIATION_G: f32 = 0.3;
+
+// Intervalo por defecto (200Hz) y límites del paso de integración (s)
+const DEFAULT_DT_S: f32 = 0.005;
+const MAX_DT_S: f32 = 0.05;
+
+/// Filtro de orientación propio de cada conexión
+#[derive(Debug, Clone)]
+pub struct OrientationFilter {
+    quaternion: [f32; 4], // (w, x, y, z): rota el marco del sensor al marco mundial
+    last_sample_us: Option<u64>,
+    initialized: bool,
+}
+
+impl Default for OrientationFilter {
+    fn default() -> Self {
+        Self {
+            quaternion: [1.0, 0.0, 0.0, 0.0],
+            last_sample_us: None,
+            initialized: false,
+        }
+    }
+}
+
+impl OrientationFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Actualiza la orientación con una muestra (g, °/s) y devuelve la aceleración
+    /// lineal en el marco mundial (g, sin gravedad)
+    pub fn update(&mut self, acc: [f32; 3], gyro: [f32; 3], sample_time_us: u64) -> [f32; 3] {
+        // Primera muestra: alinear directamente con la gravedad medida
+        if !self.initialized {
+            if let Some(quaternion) = quaternion_from_gravity(acc) {
+                self.quaternion = quaternion;
+                self.initialized = true;
+            }
+        } else {
+            let dt = match self.last_sample_us {
+                Some(last) if sample_time_us > last => ((sample_time_us - last) as f32 / 1_000_000.0).min(MAX_DT_S),
+                _ => DEFAULT_DT_S,
+            };
+            self.integrate(acc, gyro, dt);
+        }
+        self.last_sample_us = Some(sample_time_us);
+
+        let world = self.to_world(acc);
+        [world[0], world[1], world[2] - 1.0]
+    }
+
+    /// Aceleración lineal en el marco de la banda (resta la gravedad estimada)
+    pub fn sensor_linear_acc(&self, acc: [f32; 3]) -> [f32; 3] {
+        let [w, x, y, z] = self.quaternion;
+        let gravity = [
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            1.0 - 2.0 * (x * x + y * y),
+        ];
+        [acc[0] - gravity[0], acc[1] - gravity[1], acc[2] - gravity[2]]
+    }
+
+    /// Paso del filtro de Madgwick (versión IMU, sin magnetómetro)
+    fn integrate(&mut self, acc: [f32; 3], gyro: [f32; 3], dt: f32) {
+        let [q0, q1, q2, q3] = self.quaternion;
+        let [gx, gy, gz] = gyro.map(f32::to_radians);
+
+        // Derivada del cuaternión por la velocidad angular
+        let mut q_dot = [
+            0.5 * (-q1 * gx - q2 * gy - q3 * gz),
+            0.5 * (q0 * gx + q2 * gz - q3 * gy),
+            0.5 * (q0 * gy - q1 * gz + q3 * gx),
+            0.5 * (q0 * gz + q1 * gy - q2 * gx),
+        ];
+
+        // Corrección por gradiente hacia la gravedad medida
+        let acc_norm = norm(acc);
+        if acc_norm > 0.0 && (acc_norm - 1.0).abs() <= MAX_ACC_DEVIATION_G {
+            let [ax, ay, az] = acc.map(|axis| axis / acc_norm);
+
+            let s0 = 4.0 * q0 * q2 * q2 + 2.0 * q2 * ax + 4.0 * q0 * q1 * q1 - 2.0 * q1 * ay;
+            let s1 = 4.0 * q1 * q3 * q3 - 2.0 * q3 * ax + 4.0 * q0 * q0 * q1 - 2.0 * q0 * ay - 4.0 * q1
+                + 8.0 * q1 * q1 * q1 + 8.0 * q1 * q2 * q2 + 4.0 * q1 * az;
+            let s2 = 4.0 * q0 * q0 * q2 + 2.0 * q0 * ax + 4.0 * q2 * q3 * q3 - 2.0 * q3 * ay - 4.0 * q2
+                + 8.0 * q2 * q1 * q1 + 8.0 * q2 * q2 * q2 + 4.0 * q2 * az;
+            let s3 = 4.0 * q1 * q1 * q3 - 2.0 * q1 * ax + 4.0 * q2 * q2 * q3 - 2.0 * q2 * ay;
+
+            let s_norm = (s0 * s0 + s1 * s1 + s2 * s2 + s3 * s3).sqrt();
+            if s_norm > 0.0 {
+                q_dot[0] -= MADGWICK_BETA * s0 / s_norm;
+                q_dot[1] -= MADGWICK_BETA * s1 / s_norm;
+                q_dot[2] -= MADGWICK_BETA * s2 / s_norm;
+                q_dot[3] -= MADGWICK_BETA * s3 / s_norm;
+            }
+        }
+
+        let q = [q0 + q_dot[0] * dt, q1 + q_dot[1] * dt, q2 + q_dot[2] * dt, q3 + q_dot[3] * dt];
+        let q_norm = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+        if q_norm > 0.0 {
+            self.quaternion = q.map(|component| component / q_norm);
+        }
+    }
+
+    /// Rota un vector del marco del sensor al marco mundial
+    fn to_world(&self, v: [f32; 3]) -> [f32; 3] {
+        let [w, x, y, z] = self.quaternion;
+        [
+            (1.0 - 2.0 * (y * y + z * z)) * v[0] + 2.0 * (x * y - w * z) * v[1] + 2.0 * (x * z + w * y) * v[2],
+            2.0 * (x * y + w * z) * v[0] + (1.0 - 2.0 * (x * x + z * z)) * v[1] + 2.0 * (y * z - w * x) * v[2],
+            2.0 * (x * z - w * y) * v[0] + 2.0 * (y * z + w * x) * v[1] + (1.0 - 2.0 * (x * x + y * y)) * v[2],
+        ]
+    }
+}
+
+/// Cuaternión que lleva la gravedad medida al eje +Z mundial
+fn quaternion_from_gravity(acc: [f32; 3]) -> Option<[f32; 4]> {
+    let acc_norm = norm(acc);
+    if acc_norm < 0.5 {
+        return None; // Sin referencia de gravedad fiable
+    }
+    let [ux, uy, uz] = acc.map(|axis| axis / acc_norm);
+
+    // q = [1 + u·z, u × z] normalizado
+    let w = 1.0 + uz;
+    if w < 1e-6 {
+        return Some([0.0, 1.0, 0.0, 0.0]); // Sensor invertido: 180° sobre X
+    }
+    let q = [w, uy, -ux, 0.0];
+    let q_norm = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2]).sqrt();
+    Some(q.map(|component| component / q_norm))
+}
+
+fn norm(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
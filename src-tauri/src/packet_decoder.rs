@@ -0,0 +1,431 @@
+// Decodificador versionado de paquetes IMU
+// Soporta el formato original de 14 bytes y los formatos con encabezado, secuencia y CRC (incluido por lotes)
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Formatos soportados:
+// Legado:        [limb, batería, acc xyz, gyro xyz]                          14 bytes
+// Legado + hora: [legado, hora banda u32 LE (µs)]                            18 bytes
+// V1:            [0xB1, secuencia u16 LE, legado, hora u32 LE, CRC-16 LE]    23 bytes
+// V2 (lote):     [0xB2, secuencia u16 LE, limb, batería, N, hora base u32 LE (µs),
+//                 periodo u16 LE (µs), N x (acc xyz, gyro xyz), CRC-16 LE]   14 + 12N bytes
+const LEGACY_PACKET_SIZE: usize = 14;
+const LEGACY_TIMESTAMPED_PACKET_SIZE: usize = 18;
+const V1_PACKET_SIZE: usize = 23;
+const V2_HEADER_SIZE: usize = 12;
+const V2_SAMPLE_SIZE: usize = 12;
+
+// Byte de encabezado: nibble alto 0xB (marca), nibble bajo = versión
+// El formato legado empieza con limb_id (1-4), por lo que no hay ambigüedad
+const HEADER_MARKER: u8 = 0xB0;
+const HEADER_MARKER_MASK: u8 = 0xF0;
+
+// Secuencias hasta esta distancia por detrás de la última son duplicados o paquetes atrasados;
+// un salto mayor hacia atrás es un reinicio del contador de la banda (resincronización)
+const DUPLICATE_WINDOW: u16 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum PacketVersion {
+    Legacy,
+    LegacyTimestamped,
+    V1,
+    V2Batch,
+}
+
+// Muestra decodificada en unidades crudas del sensor
+#[derive(Debug, Clone)]
+pub struct DecodedSample {
+    pub limb_id: u8,
+    pub battery_level: u8,
+    pub acc: [i16; 3],
+    pub gyro: [i16; 3],
+    pub device_clock_us: Option<u32>, // Hora de la banda si el formato la incluye
+}
+
+// Paquete decodificado; los formatos por lote traen varias muestras consecutivas
+#[derive(Debug, Clone)]
+pub struct DecodedPacket {
+    pub version: PacketVersion,
+    pub sequence: Option<u16>,
+    pub samples: Vec<DecodedSample>, // En orden cronológico
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    Malformed(usize),       // Longitud inesperada
+    UnsupportedVersion(u8),
+    Checksum,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Malformed(len) => write!(f, "Paquete con longitud inesperada ({} bytes)", len),
+            DecodeError::UnsupportedVersion(version) => write!(f, "Versión de paquete no soportada: {}", version),
+            DecodeError::Checksum => write!(f, "CRC de paquete incorrecto"),
+        }
+    }
+}
+
+/// Decodifica una notificación BLE según su encabezado y longitud
+pub fn decode_packet(data: &[u8]) -> Result<DecodedPacket, DecodeError> {
+    let Some(&first) = data.first() else {
+        return Err(DecodeError::Malformed(0));
+    };
+
+    if first & HEADER_MARKER_MASK == HEADER_MARKER {
+        return match first & !HEADER_MARKER_MASK {
+            1 => decode_v1(data),
+            2 => decode_v2_batch(data),
+            version => Err(DecodeError::UnsupportedVersion(version)),
+        };
+    }
+
+    match data.len() {
+        LEGACY_PACKET_SIZE => Ok(DecodedPacket {
+            version: PacketVersion::Legacy,
+            sequence: None,
+            samples: vec![decode_sample(data, None)],
+        }),
+        LEGACY_TIMESTAMPED_PACKET_SIZE => Ok(DecodedPacket {
+            version: PacketVersion::LegacyTimestamped,
+            sequence: None,
+            samples: vec![decode_sample(data, Some(read_u32(&data[14..18])))],
+        }),
+        len => Err(DecodeError::Malformed(len)),
+    }
+}
+
+fn decode_v1(data: &[u8]) -> Result<DecodedPacket, DecodeError> {
+    if data.len() != V1_PACKET_SIZE {
+        return Err(DecodeError::Malformed(data.len()));
+    }
+
+    verify_crc(data)?;
+
+    Ok(DecodedPacket {
+        version: PacketVersion::V1,
+        sequence: Some(u16::from_le_bytes([data[1], data[2]])),
+        samples: vec![decode_sample(&data[3..17], Some(read_u32(&data[17..21])))],
+    })
+}
+
+fn decode_v2_batch(data: &[u8]) -> Result<DecodedPacket, DecodeError> {
+    if data.len() < V2_HEADER_SIZE + V2_SAMPLE_SIZE + 2 {
+        return Err(DecodeError::Malformed(data.len()));
+    }
+
+    let count = data[5] as usize;
+    if count == 0 || data.len() != V2_HEADER_SIZE + count * V2_SAMPLE_SIZE + 2 {
+        return Err(DecodeError::Malformed(data.len()));
+    }
+    verify_crc(data)?;
+
+    let limb_id = data[3];
+    let battery_level = data[4];
+    let base_clock_us = read_u32(&data[6..10]);
+    let period_us = u16::from_le_bytes([data[10], data[11]]) as u32;
+
+    let samples = data[V2_HEADER_SIZE..V2_HEADER_SIZE + count * V2_SAMPLE_SIZE]
+        .chunks_exact(V2_SAMPLE_SIZE)
+        .enumerate()
+        .map(|(index, block)| {
+            let axis = |offset: usize| i16::from_le_bytes([block[offset], block[offset + 1]]);
+            DecodedSample {
+                limb_id,
+                battery_level,
+                acc: [axis(0), axis(2), axis(4)],
+                gyro: [axis(6), axis(8), axis(10)],
+                device_clock_us: Some(base_clock_us.wrapping_add(period_us * index as u32)),
+            }
+        })
+        .collect();
+
+    Ok(DecodedPacket {
+        version: PacketVersion::V2Batch,
+        sequence: Some(u16::from_le_bytes([data[1], data[2]])),
+        samples,
+    })
+}
+
+// Verifica el CRC-16 de los últimos dos bytes sobre el resto del paquete
+fn verify_crc(data: &[u8]) -> Result<(), DecodeError> {
+    let crc_offset = data.len() - 2;
+    let expected_crc = u16::from_le_bytes([data[crc_offset], data[crc_offset + 1]]);
+    if crc16_ccitt(&data[..crc_offset]) != expected_crc {
+        return Err(DecodeError::Checksum);
+    }
+    Ok(())
+}
+
+// Decodifica el bloque común de 14 bytes (limb, batería, acc xyz, gyro xyz)
+fn decode_sample(block: &[u8], device_clock_us: Option<u32>) -> DecodedSample {
+    let axis = |index: usize| i16::from_le_bytes([block[index], block[index + 1]]);
+
+    DecodedSample {
+        limb_id: block[0],
+        battery_level: block[1],
+        acc: [axis(2), axis(4), axis(6)],
+        gyro: [axis(8), axis(10), axis(12)],
+        device_clock_us,
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// CRC-16/CCITT-FALSE (polinomio 0x1021, valor inicial 0xFFFF)
+pub fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+// Estadísticas de recepción por dispositivo
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct PacketStats {
+    pub received: u64,           // Notificaciones recibidas
+    pub decoded: u64,            // Paquetes decodificados correctamente
+    pub samples: u64,            // Muestras IMU entregadas al detector
+    pub lost: u64,               // Huecos en la secuencia
+    pub duplicates: u64,         // Secuencias repetidas o atrasadas
+    pub resyncs: u64,            // Saltos atrás del contador (reinicio de la banda)
+    pub checksum_failures: u64,
+    pub malformed: u64,          // Longitud o versión no reconocida
+    pub last_sequence: Option<u16>,
+    pub last_version: Option<PacketVersion>,
+}
+
+impl PacketStats {
+    /// Registra un paquete decodificado; devuelve false si es duplicado y debe descartarse
+    pub fn record_packet(&mut self, packet: &DecodedPacket) -> bool {
+        self.received += 1;
+        self.last_version = Some(packet.version);
+
+        let Some(sequence) = packet.sequence else {
+            self.decoded += 1;
+            self.samples += packet.samples.len() as u64;
+            return true; // Formatos legados: sin secuencia que verificar
+        };
+
+        if let Some(last) = self.last_sequence {
+            let delta = sequence.wrapping_sub(last);
+            if last.wrapping_sub(sequence) <= DUPLICATE_WINDOW {
+                self.duplicates += 1;
+                return false;
+            }
+            if delta > u16::MAX / 2 {
+                self.resyncs += 1;
+            } else {
+                self.lost += (delta - 1) as u64;
+            }
+        }
+
+        self.last_sequence = Some(sequence);
+        self.decoded += 1;
+        self.samples += packet.samples.len() as u64;
+        true
+    }
+
+    /// Nueva conexión: la banda puede haber reiniciado su contador de secuencia
+    pub fn begin_connection(&mut self) {
+        self.last_sequence = None;
+    }
+
+    pub fn record_error(&mut self, error: &DecodeError) {
+        self.received += 1;
+        match error {
+            DecodeError::Checksum => self.checksum_failures += 1,
+            DecodeError::Malformed(_) | DecodeError::UnsupportedVersion(_) => self.malformed += 1,
+        }
+    }
+
+    /// Porcentaje de pérdida sobre los paquetes esperados
+    pub fn loss_rate(&self) -> f32 {
+        let expected = self.decoded + self.lost;
+        if expected == 0 { 0.0 } else { self.lost as f32 / expected as f32 }
+    }
+}
+
+// Estadísticas globales por dispositivo (cada conexión actualiza su propia entrada)
+type DeviceStatsMap = HashMap<String, Arc<Mutex<PacketStats>>>;
+static PACKET_STATS: Lazy<Arc<Mutex<DeviceStatsMap>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Obtiene (o crea) las estadísticas de un dispositivo
+/// El stream de notificaciones guarda el Arc para no buscar en el mapa a 200Hz
+pub fn stats_for_device(device_id: &str) -> Arc<Mutex<PacketStats>> {
+    PACKET_STATS.lock().unwrap()
+        .entry(device_id.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Estadísticas de un dispositivo para una conexión que empieza (sin secuencia previa)
+pub fn begin_connection(device_id: &str) -> Arc<Mutex<PacketStats>> {
+    let stats = stats_for_device(device_id);
+    stats.lock().unwrap().begin_connection();
+    stats
+}
+
+// Resumen serializable para el frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DevicePacketStats {
+    pub device_id: String,
+    pub loss_rate: f32,
+    pub stats: PacketStats,
+}
+
+/// Copia de las estadísticas de todos los dispositivos
+pub fn get_packet_stats() -> Vec<DevicePacketStats> {
+    PACKET_STATS.lock().unwrap()
+        .iter()
+        .map(|(device_id, stats)| {
+            let stats = stats.lock().unwrap().clone();
+            DevicePacketStats {
+                device_id: device_id.clone(),
+                loss_rate: stats.loss_rate(),
+                stats,
+            }
+        })
+        .collect()
+}
+
+/// Reinicia las estadísticas sin romper las referencias de conexiones activas
+pub fn reset_packet_stats() {
+    for stats in PACKET_STATS.lock().unwrap().values() {
+        *stats.lock().unwrap() = PacketStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_crc(mut packet: Vec<u8>) -> Vec<u8> {
+        let crc = crc16_ccitt(&packet);
+        packet.extend_from_slice(&crc.to_le_bytes());
+        packet
+    }
+
+    fn v1_packet(sequence: u16) -> Vec<u8> {
+        let mut packet = vec![0xB1];
+        packet.extend_from_slice(&sequence.to_le_bytes());
+        packet.extend_from_slice(&[2, 87]);
+        for axis in [100i16, -200, 300, -400, 500, -600] {
+            packet.extend_from_slice(&axis.to_le_bytes());
+        }
+        packet.extend_from_slice(&1_000_000u32.to_le_bytes());
+        with_crc(packet)
+    }
+
+    fn sequenced(sequence: u16) -> DecodedPacket {
+        DecodedPacket { version: PacketVersion::V1, sequence: Some(sequence), samples: Vec::new() }
+    }
+
+    #[test]
+    fn crc16_matches_ccitt_false_check_value() {
+        assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+        assert_eq!(crc16_ccitt(&[]), 0xFFFF);
+    }
+
+    #[test]
+    fn decodes_v1_packet() {
+        let packet = decode_packet(&v1_packet(42)).unwrap();
+        assert_eq!(packet.version, PacketVersion::V1);
+        assert_eq!(packet.sequence, Some(42));
+
+        let sample = &packet.samples[0];
+        assert_eq!((sample.limb_id, sample.battery_level), (2, 87));
+        assert_eq!(sample.acc, [100, -200, 300]);
+        assert_eq!(sample.gyro, [-400, 500, -600]);
+        assert_eq!(sample.device_clock_us, Some(1_000_000));
+    }
+
+    #[test]
+    fn rejects_v1_packet_with_bad_crc() {
+        let mut data = v1_packet(1);
+        data[5] ^= 0x01;
+        assert_eq!(decode_packet(&data).unwrap_err(), DecodeError::Checksum);
+    }
+
+    #[test]
+    fn decodes_v2_batch_with_sample_clock() {
+        let mut packet = vec![0xB2];
+        packet.extend_from_slice(&7u16.to_le_bytes());
+        packet.extend_from_slice(&[3, 50, 3]);
+        packet.extend_from_slice(&u32::MAX.to_le_bytes()); // La hora base da la vuelta en el lote
+        packet.extend_from_slice(&5000u16.to_le_bytes());
+        for sample in 0..3i16 {
+            for axis in 0..6i16 {
+                packet.extend_from_slice(&(sample * 10 + axis).to_le_bytes());
+            }
+        }
+        let data = with_crc(packet);
+        assert_eq!(data.len(), 14 + 12 * 3);
+
+        let packet = decode_packet(&data).unwrap();
+        assert_eq!(packet.version, PacketVersion::V2Batch);
+        assert_eq!(packet.sequence, Some(7));
+        assert_eq!(packet.samples.len(), 3);
+        assert_eq!(packet.samples[2].acc, [20, 21, 22]);
+        assert_eq!(packet.samples[2].gyro, [23, 24, 25]);
+        let clocks: Vec<_> = packet.samples.iter().map(|sample| sample.device_clock_us).collect();
+        assert_eq!(clocks, vec![Some(u32::MAX), Some(4999), Some(9999)]);
+    }
+
+    #[test]
+    fn rejects_v2_batch_with_wrong_sample_count() {
+        let mut packet = vec![0xB2, 0, 0, 1, 50, 2];
+        packet.extend_from_slice(&[0; 6]);
+        packet.extend_from_slice(&[0; 12]); // Anuncia 2 muestras y trae 1
+        let data = with_crc(packet);
+        assert_eq!(decode_packet(&data).unwrap_err(), DecodeError::Malformed(data.len()));
+    }
+
+    #[test]
+    fn counts_losses_across_sequence_wrap() {
+        let mut stats = PacketStats::default();
+        assert!(stats.record_packet(&sequenced(65534)));
+        assert!(stats.record_packet(&sequenced(65535)));
+        assert!(stats.record_packet(&sequenced(1))); // Falta el 0
+        assert_eq!(stats.lost, 1);
+        assert_eq!(stats.duplicates, 0);
+    }
+
+    #[test]
+    fn drops_duplicates_and_late_packets() {
+        let mut stats = PacketStats::default();
+        assert!(stats.record_packet(&sequenced(1000)));
+        assert!(!stats.record_packet(&sequenced(1000)));
+        assert!(!stats.record_packet(&sequenced(990)));
+        assert_eq!(stats.duplicates, 2);
+        assert_eq!(stats.last_sequence, Some(1000));
+    }
+
+    #[test]
+    fn large_backward_jump_resyncs_instead_of_dropping() {
+        let mut stats = PacketStats::default();
+        assert!(stats.record_packet(&sequenced(30_000)));
+        assert!(stats.record_packet(&sequenced(3))); // La banda reinició su contador
+        assert!(stats.record_packet(&sequenced(4)));
+        assert_eq!((stats.resyncs, stats.duplicates, stats.lost), (1, 0, 0));
+    }
+
+    #[test]
+    fn new_connection_forgets_previous_sequence() {
+        let mut stats = PacketStats::default();
+        assert!(stats.record_packet(&sequenced(20)));
+        stats.begin_connection();
+        assert!(stats.record_packet(&sequenced(5)));
+        assert_eq!((stats.resyncs, stats.duplicates, stats.lost), (0, 0, 0));
+    }
+}
@@ -0,0 +1,284 @@
+// Plantilla persistente de competidores: datos personales, historial de pesajes y categorías de peso
+// Las conexiones referencian al competidor por su id para que la fuerza use siempre el último pesaje
+
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::storage;
+
+const ROSTER_FILE: &str = "roster.json";
+// Pesos aceptados en un pesaje (kg)
+const MIN_WEIGHT_KG: f32 = 20.0;
+const MAX_WEIGHT_KG: f32 = 300.0;
+
+/// Guardia del competidor
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stance {
+    Orthodox,
+    Southpaw,
+    Switch,
+}
+
+/// Pesaje registrado
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WeighIn {
+    pub weight_kg: f32,
+    pub at: u64,
+    pub note: Option<String>,
+}
+
+/// Competidor de la plantilla
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Competitor {
+    pub id: String,
+    #[serde(default)]
+    pub external_id: Option<String>, // Id del peleador en la interfaz (Firestore), si viene de allí
+    pub name: String,
+    pub stance: Option<Stance>,
+    pub club: Option<String>,
+    pub photo_path: Option<String>,
+    pub weight_history: Vec<WeighIn>, // Del más antiguo al más reciente
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl Competitor {
+    /// Peso del último pesaje
+    pub fn current_weight(&self) -> Option<f32> {
+        self.weight_history.last().map(|weigh_in| weigh_in.weight_kg)
+    }
+}
+
+/// Datos editables de un competidor (sin id crea uno nuevo, o actualiza el de external_id)
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct CompetitorProfile {
+    pub id: Option<String>,
+    pub external_id: Option<String>,
+    pub name: String,
+    pub stance: Option<Stance>,
+    pub club: Option<String>,
+    pub photo_path: Option<String>,
+    pub weight_kg: Option<f32>, // Pesaje inicial al crear
+}
+
+/// Categoría de peso (hasta max_kg incluido; sin límite para la última)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WeightClass {
+    pub name: String,
+    pub max_kg: Option<f32>,
+}
+
+/// Competidor con su peso actual y categoría
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RosterEntry {
+    #[serde(flatten)]
+    pub competitor: Competitor,
+    pub current_weight: Option<f32>,
+    pub weight_class: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct Roster {
+    #[serde(default)]
+    competitors: BTreeMap<String, Competitor>,
+    #[serde(default)]
+    weight_classes: Vec<WeightClass>, // Ordenadas por peso máximo
+}
+
+impl Roster {
+    fn entry(&self, competitor: &Competitor) -> RosterEntry {
+        let current_weight = competitor.current_weight();
+        let weight_class = current_weight.and_then(|weight| {
+            self.weight_classes.iter()
+                .find(|class| class.max_kg.is_none_or(|max_kg| weight <= max_kg))
+                .map(|class| class.name.clone())
+        });
+        RosterEntry {
+            competitor: competitor.clone(),
+            current_weight,
+            weight_class,
+        }
+    }
+}
+
+static ROSTER: Lazy<RwLock<Roster>> = Lazy::new(|| RwLock::new(Roster::default()));
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn validate_weight(weight_kg: f32) -> Result<(), String> {
+    if !(MIN_WEIGHT_KG..=MAX_WEIGHT_KG).contains(&weight_kg) {
+        return Err(format!("Peso fuera de rango: {} kg ({}-{} kg)", weight_kg, MIN_WEIGHT_KG, MAX_WEIGHT_KG));
+    }
+    Ok(())
+}
+
+// Texto opcional sin espacios sobrantes; vacío cuenta como None
+fn clean(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// Carga la plantilla guardada al iniciar la aplicación
+pub fn load_roster<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(saved) = storage::load_json::<Roster, R>(app_handle, ROSTER_FILE) else {
+        return;
+    };
+    info!(competitors = saved.competitors.len(), weight_classes = saved.weight_classes.len(), "👥 Plantilla de competidores cargada");
+    *ROSTER.write().unwrap() = saved;
+}
+
+/// Competidores de la plantilla
+pub fn list_competitors() -> Vec<RosterEntry> {
+    let roster = ROSTER.read().unwrap();
+    roster.competitors.values().map(|competitor| roster.entry(competitor)).collect()
+}
+
+/// Competidor por id
+pub fn get_competitor(id: &str) -> Option<RosterEntry> {
+    let roster = ROSTER.read().unwrap();
+    roster.competitors.get(id).map(|competitor| roster.entry(competitor))
+}
+
+/// Crea o actualiza un competidor
+pub fn save_competitor<R: tauri::Runtime>(app_handle: &AppHandle<R>, profile: CompetitorProfile) -> Result<RosterEntry, String> {
+    let name = profile.name.trim().to_string();
+    if name.is_empty() {
+        return Err("El competidor necesita un nombre".to_string());
+    }
+    if let Some(weight_kg) = profile.weight_kg {
+        validate_weight(weight_kg)?;
+    }
+
+    let now = now_ms();
+    let external_id = clean(profile.external_id);
+    let mut roster = ROSTER.write().unwrap();
+    let existing = external_id.as_ref().and_then(|external_id| {
+        roster.competitors.values()
+            .find(|competitor| competitor.external_id.as_ref() == Some(external_id))
+            .map(|competitor| competitor.id.clone())
+    });
+    let id = match profile.id.or(existing) {
+        Some(id) => {
+            if !roster.competitors.contains_key(&id) {
+                return Err(format!("No existe el competidor {}", id));
+            }
+            id
+        }
+        None => {
+            let mut id = format!("competitor_{}", now);
+            let mut suffix = 1;
+            while roster.competitors.contains_key(&id) {
+                suffix += 1;
+                id = format!("competitor_{}_{}", now, suffix);
+            }
+            id
+        }
+    };
+
+    let competitor = roster.competitors.entry(id.clone()).or_insert_with(|| Competitor {
+        id: id.clone(),
+        external_id: None,
+        name: String::new(),
+        stance: None,
+        club: None,
+        photo_path: None,
+        weight_history: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    });
+    competitor.name = name;
+    if external_id.is_some() {
+        competitor.external_id = external_id;
+    }
+    competitor.stance = profile.stance;
+    competitor.club = clean(profile.club);
+    competitor.photo_path = clean(profile.photo_path);
+    competitor.updated_at = now;
+    // Solo se registra un pesaje si el peso cambia
+    if let Some(weight_kg) = profile.weight_kg.filter(|&weight_kg| competitor.current_weight() != Some(weight_kg)) {
+        competitor.weight_history.push(WeighIn { weight_kg, at: now, note: None });
+    }
+
+    let competitor = competitor.clone();
+    storage::save_json(app_handle, ROSTER_FILE, &*roster)?;
+    info!(competitor_id = %competitor.id, name = %competitor.name, weight = ?competitor.current_weight(), "👤 Competidor guardado");
+    Ok(roster.entry(&competitor))
+}
+
+/// Elimina un competidor de la plantilla
+pub fn delete_competitor<R: tauri::Runtime>(app_handle: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let mut roster = ROSTER.write().unwrap();
+    roster.competitors.remove(id)
+        .ok_or_else(|| format!("No existe el competidor {}", id))?;
+    storage::save_json(app_handle, ROSTER_FILE, &*roster)?;
+    info!(competitor_id = %id, "🗑️ Competidor eliminado");
+    Ok(())
+}
+
+/// Registra un pesaje; las bandas conectadas usan el nuevo peso desde el siguiente golpe
+pub fn record_weigh_in<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+    weight_kg: f32,
+    note: Option<String>,
+) -> Result<RosterEntry, String> {
+    validate_weight(weight_kg)?;
+
+    let now = now_ms();
+    let mut roster = ROSTER.write().unwrap();
+    let competitor = roster.competitors.get_mut(id)
+        .ok_or_else(|| format!("No existe el competidor {}", id))?;
+    competitor.weight_history.push(WeighIn { weight_kg, at: now, note: clean(note) });
+    competitor.updated_at = now;
+
+    let competitor = competitor.clone();
+    storage::save_json(app_handle, ROSTER_FILE, &*roster)?;
+    info!(competitor_id = %id, weight_kg = weight_kg, "⚖️ Pesaje registrado");
+    Ok(roster.entry(&competitor))
+}
+
+/// Peso actual de un competidor (se consulta al calcular la fuerza de cada golpe)
+pub fn current_weight(id: &str) -> Option<f32> {
+    ROSTER.read().unwrap().competitors.get(id)?.current_weight()
+}
+
+/// Categorías de peso configuradas
+pub fn weight_classes() -> Vec<WeightClass> {
+    ROSTER.read().unwrap().weight_classes.clone()
+}
+
+/// Reemplaza las categorías de peso
+pub fn save_weight_classes<R: tauri::Runtime>(app_handle: &AppHandle<R>, mut classes: Vec<WeightClass>) -> Result<Vec<WeightClass>, String> {
+    if classes.iter().any(|class| class.name.trim().is_empty()) {
+        return Err("Cada categoría necesita un nombre".to_string());
+    }
+    if classes.iter().filter(|class| class.max_kg.is_none()).count() > 1 {
+        return Err("Solo una categoría puede no tener límite de peso".to_string());
+    }
+    if let Some(class) = classes.iter().find(|class| class.max_kg.is_some_and(|max_kg| validate_weight(max_kg).is_err())) {
+        return Err(format!("Límite de peso fuera de rango en {}", class.name));
+    }
+
+    // La categoría sin límite va al final
+    classes.sort_by(|a, b| a.max_kg.unwrap_or(f32::INFINITY).total_cmp(&b.max_kg.unwrap_or(f32::INFINITY)));
+    if let Some(pair) = classes.windows(2).find(|pair| pair[0].max_kg == pair[1].max_kg) {
+        return Err(format!("Las categorías {} y {} tienen el mismo límite", pair[0].name, pair[1].name));
+    }
+    if let Some((index, class)) = classes.iter().enumerate().find(|(index, class)| classes[..*index].iter().any(|other| other.name == class.name)) {
+        return Err(format!("La categoría {} está repetida (posición {})", class.name, index + 1));
+    }
+
+    let mut roster = ROSTER.write().unwrap();
+    roster.weight_classes = classes.clone();
+    storage::save_json(app_handle, ROSTER_FILE, &*roster)?;
+    info!(weight_classes = classes.len(), "⚖️ Categorías de peso guardadas");
+    Ok(classes)
+}
@@ -0,0 +1,1372 @@
+// Motor de puntuación: convierte los eventos de combate en puntos por peleador y asalto
+// Reglas configurables (puntos por golpe, mínimos de fuerza/confianza, bonus por récord, penalizaciones)
+// La sesión guarda el registro de eventos y los resultados de cada asalto
+// En modo por turnos solo cuenta un golpe del peleador activo dentro de la ventana de golpe
+// Las correcciones del árbitro quedan auditadas y se recalcula todo desde el registro
+// Las tarjetas de los jueces (judging.rs) se guardan en la misma sesión
+
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+use crate::broadcast_ws::ws_broadcast;
+use crate::force_model::ForceModelRecord;
+use crate::judging::{self, FoulMarker, JudgeCard, JudgingSummary};
+use crate::simple_ble::{self, CompetitorMaxStats, SimpleCombatEvent};
+use crate::velocity::VelocityMethod;
+use crate::storage;
+use crate::strike_classifier::UNKNOWN_STRIKE;
+use crate::tournament;
+
+const RULE_SETS_FILE: &str = "scoring_rules.json";
+const SESSIONS_DIR: &str = "sessions";
+const DEFAULT_RULE_SET: &str = "standard";
+const DEFAULT_TURN_WINDOW_MS: u64 = 5_000;
+
+/// Modalidad del combate
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    #[default]
+    Continuous, // Cualquier banda puntúa en cualquier momento
+    TurnBased,  // Un golpe por turno del peleador activo (liga de bofetadas)
+}
+
+fn default_turn_window_ms() -> u64 {
+    DEFAULT_TURN_WINDOW_MS
+}
+
+/// Cómo se decide el ganador del combate
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchDecision {
+    TotalPoints, // Suma de todos los asaltos
+    RoundsWon,   // Asaltos ganados
+    TotalForce,  // Suma de la fuerza de los golpes de cada turno (modo por turnos)
+    Judges,      // Mayoría de las tarjetas de los jueces (10-point must)
+}
+
+/// Reglas de puntuación
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScoringRuleSet {
+    pub name: String,
+    pub rounds: u32,
+    pub points: BTreeMap<String, i32>,  // Puntos por tipo de golpe; los no listados no puntúan
+    pub min_confidence: f32,            // Confianza mínima para contar un golpe
+    pub min_force: Option<f32>,         // Fuerza mínima (N) para contar un golpe
+    pub record_bonus: i32,              // Puntos extra por cada récord batido
+    pub penalties: BTreeMap<String, i32>, // Puntos restados por tipo de falta
+    pub decision: MatchDecision,
+    #[serde(default)]
+    pub mode: MatchMode,
+    #[serde(default = "default_turn_window_ms")]
+    pub turn_window_ms: u64,            // Tiempo para golpear una vez abierto el turno
+}
+
+impl Default for ScoringRuleSet {
+    fn default() -> Self {
+        let points = [("punch", 1), ("slap", 1), ("front_kick", 2), ("side_kick", 2), ("round_kick", 3), ("kickdown", 3)];
+        let penalties = [("warning", 0), ("foul", 1), ("serious_foul", 3)];
+        Self {
+            name: DEFAULT_RULE_SET.to_string(),
+            rounds: 3,
+            points: points.iter().map(|&(class, points)| (class.to_string(), points)).collect(),
+            min_confidence: 0.6,
+            min_force: None,
+            record_bonus: 1,
+            penalties: penalties.iter().map(|&(kind, points)| (kind.to_string(), points)).collect(),
+            decision: MatchDecision::TotalPoints,
+            mode: MatchMode::Continuous,
+            turn_window_ms: DEFAULT_TURN_WINDOW_MS,
+        }
+    }
+}
+
+impl ScoringRuleSet {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("El conjunto de reglas necesita un nombre".to_string());
+        }
+        if self.rounds == 0 {
+            return Err("El combate necesita al menos un asalto".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.min_confidence) {
+            return Err("La confianza mínima debe estar entre 0 y 1".to_string());
+        }
+        if self.penalties.values().any(|&points| points < 0) {
+            return Err("Las penalizaciones se expresan como puntos a restar (>= 0)".to_string());
+        }
+        if self.mode == MatchMode::TurnBased && self.turn_window_ms == 0 {
+            return Err("El modo por turnos necesita una ventana de golpe".to_string());
+        }
+        if self.decision == MatchDecision::TotalForce && self.mode != MatchMode::TurnBased {
+            return Err("La decisión por fuerza total solo aplica al modo por turnos".to_string());
+        }
+        Ok(())
+    }
+
+    // Puntos de un golpe; Err con el motivo si no cuenta
+    // Los golpes del árbitro no pasan por los mínimos del sensor
+    fn score_strike(&self, event: &SimpleCombatEvent, records: usize, manual: bool) -> Result<i32, String> {
+        if event.event_type == UNKNOWN_STRIKE {
+            return Err("golpe sin clasificar".to_string());
+        }
+        if manual {
+            // Sin comprobaciones de confianza ni fuerza
+        } else if event.confidence < self.min_confidence {
+            return Err(format!("confianza {:.2} < {:.2}", event.confidence, self.min_confidence));
+        } else if let Some(min_force) = self.min_force {
+            match event.force {
+                Some(force) if force >= min_force => {}
+                Some(force) => return Err(format!("fuerza {:.0} N < {:.0} N", force, min_force)),
+                None => return Err("fuerza no disponible".to_string()),
+            }
+        }
+        let points = self.points.get(&event.event_type)
+            .ok_or_else(|| format!("{} no puntúa en estas reglas", event.event_type))?;
+        Ok(points + self.record_bonus * records as i32)
+    }
+}
+
+/// Entrada del registro de la sesión
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SessionEntry {
+    pub id: u64,
+    pub round: u32,
+    pub fighter_id: String,
+    pub timestamp: u64,
+    pub points: i32,
+    #[serde(flatten)]
+    pub kind: EntryKind,
+    #[serde(default)]
+    pub voided: bool,   // Anulado por el árbitro (se conserva para la auditoría)
+    #[serde(default)]
+    pub manual: bool,   // Añadido por el árbitro
+}
+
+/// Corrección del árbitro sobre una entrada del registro
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AuditRecord {
+    pub at: u64,
+    pub referee: String,
+    pub reason: String,
+    pub entry_id: u64,
+    #[serde(flatten)]
+    pub action: AuditAction,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AuditAction {
+    Void,
+    Add,
+    Amend {
+        field: String,
+        previous: serde_json::Value,
+        updated: serde_json::Value,
+    },
+}
+
+/// Cambios de un golpe registrado (los campos None no se tocan)
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct StrikeAmendment {
+    pub event_type: Option<String>,
+    pub fighter_id: Option<String>,
+    pub force: Option<f32>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EntryKind {
+    Strike {
+        event: Box<SimpleCombatEvent>,
+        records: Vec<String>,         // Récords batidos con este golpe
+        rejected: Option<String>,     // Motivo si no puntúa
+    },
+    Penalty {
+        penalty: String,
+        note: Option<String>,
+    },
+}
+
+/// Resultado de un asalto terminado
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RoundResult {
+    pub round: u32,
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub scores: BTreeMap<String, i32>,
+    pub winner: Option<String>, // None = empate
+}
+
+/// Turno en curso (modo por turnos)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TurnState {
+    pub number: u32,
+    pub fighter_id: String,
+    pub window_opened_at: Option<u64>, // None hasta que el árbitro abre la ventana
+    pub window_closes_at: Option<u64>,
+}
+
+/// Resultado de un turno
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TurnResult {
+    pub number: u32,
+    pub round: u32,
+    pub fighter_id: String,
+    pub entry_id: Option<u64>,     // Golpe aceptado en el registro; None si no golpeó a tiempo
+    pub event_type: Option<String>,
+    pub force: Option<f32>,
+    pub points: i32,
+    pub finished_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    InProgress,
+    Finished,
+}
+
+/// Sesión de combate: reglas, peleadores, registro de eventos y asaltos
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CombatSession {
+    pub id: String,
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+    pub rule_set: ScoringRuleSet,
+    pub fighters: BTreeMap<String, String>, // fighter_id -> nombre del competidor
+    #[serde(default)]
+    pub roster_ids: BTreeMap<String, String>, // fighter_id -> competidor de la plantilla
+    pub status: SessionStatus,
+    pub current_round: u32,
+    pub round_started_at: u64,
+    pub rounds: Vec<RoundResult>,
+    pub event_log: Vec<SessionEntry>,
+    pub winner: Option<String>,
+    #[serde(default)]
+    pub turn_order: Vec<String>,
+    pub turn: Option<TurnState>,
+    #[serde(default)]
+    pub turns: Vec<TurnResult>,
+    #[serde(default)]
+    pub audit_log: Vec<AuditRecord>,
+    #[serde(default)]
+    pub judges: BTreeMap<String, String>, // judge_id -> nombre
+    #[serde(default)]
+    pub judge_cards: Vec<JudgeCard>,
+    #[serde(default)]
+    pub fouls: Vec<FoulMarker>,
+}
+
+impl CombatSession {
+    /// Puntos por peleador derivados del registro (todos los asaltos con None)
+    pub fn scores(&self, round: Option<u32>) -> BTreeMap<String, i32> {
+        let mut scores: BTreeMap<String, i32> = self.fighters.keys().map(|id| (id.clone(), 0)).collect();
+        let entries = self.event_log.iter()
+            .filter(|entry| !entry.voided && round.is_none_or(|round| entry.round == round));
+        for entry in entries {
+            *scores.entry(entry.fighter_id.clone()).or_default() += entry.points;
+        }
+        scores
+    }
+
+    /// Fuerza acumulada (N) por peleador en los turnos (todos los asaltos con None)
+    pub fn turn_forces(&self, round: Option<u32>) -> BTreeMap<String, i32> {
+        let mut forces: BTreeMap<String, i32> = self.fighters.keys().map(|id| (id.clone(), 0)).collect();
+        for turn in self.turns.iter().filter(|turn| round.is_none_or(|round| turn.round == round)) {
+            *forces.entry(turn.fighter_id.clone()).or_default() += turn.force.unwrap_or(0.0).round() as i32;
+        }
+        forces
+    }
+
+    // Lo que decide el combate según las reglas
+    fn match_tally(&self) -> BTreeMap<String, i32> {
+        match self.rule_set.decision {
+            MatchDecision::TotalPoints => self.scores(None),
+            MatchDecision::TotalForce => self.turn_forces(None),
+            MatchDecision::Judges => judging::decision(self)
+                .map(|decision| decision.cards_won)
+                .unwrap_or_else(|| self.fighters.keys().map(|id| (id.clone(), 0)).collect()),
+            MatchDecision::RoundsWon => {
+                let mut rounds_won: BTreeMap<String, i32> = self.fighters.keys().map(|id| (id.clone(), 0)).collect();
+                for winner in self.rounds.iter().filter_map(|round| round.winner.as_ref()) {
+                    *rounds_won.entry(winner.clone()).or_default() += 1;
+                }
+                rounds_won
+            }
+        }
+    }
+
+    /// Recalcula récords, puntos, turnos, asaltos y ganador desde el registro
+    /// Devuelve las estadísticas máximas derivadas
+    fn recompute(&mut self) -> std::collections::HashMap<String, CompetitorMaxStats> {
+        let (strikes, max_stats, records) = self.replay_strikes();
+
+        // En modo por turnos solo puntúan los golpes que resolvieron un turno (o los del árbitro)
+        let turn_strikes: HashSet<u64> = self.turns.iter().filter_map(|turn| turn.entry_id).collect();
+        let continuous = self.rule_set.mode == MatchMode::Continuous;
+        for (&index, strike_records) in strikes.iter().zip(records) {
+            let entry = &mut self.event_log[index];
+            let eligible = continuous || entry.manual || turn_strikes.contains(&entry.id);
+            let EntryKind::Strike { event, records, rejected } = &mut entry.kind else {
+                continue;
+            };
+            *records = strike_records.iter().map(|record| record.to_string()).collect();
+            if !eligible {
+                entry.points = 0;
+                continue;
+            }
+            (entry.points, *rejected) = match self.rule_set.score_strike(event, records.len(), entry.manual) {
+                Ok(points) => (points, None),
+                Err(reason) => (0, Some(reason)),
+            };
+        }
+        for entry in self.event_log.iter_mut().filter(|entry| entry.voided) {
+            entry.points = 0;
+            if let EntryKind::Strike { records, .. } = &mut entry.kind {
+                records.clear();
+            }
+        }
+
+        // Resultados de turnos con el golpe corregido
+        for turn in &mut self.turns {
+            let Some(entry) = turn.entry_id.and_then(|id| self.event_log.iter().find(|entry| entry.id == id)) else {
+                continue;
+            };
+            match (&entry.kind, entry.voided) {
+                (EntryKind::Strike { event, .. }, false) => {
+                    turn.fighter_id = entry.fighter_id.clone();
+                    turn.event_type = Some(event.event_type.clone());
+                    turn.force = event.force;
+                    turn.points = entry.points;
+                }
+                _ => {
+                    turn.event_type = None;
+                    turn.force = None;
+                    turn.points = 0;
+                }
+            }
+        }
+
+        self.refresh_results();
+        max_stats
+    }
+
+    /// Recalcula los resultados de los asaltos cerrados y, si terminó, el ganador del combate
+    pub(crate) fn refresh_results(&mut self) {
+        let tallies: Vec<_> = self.rounds.iter()
+            .map(|round| (self.scores(Some(round.round)), self.round_winner(round.round)))
+            .collect();
+        for (round, (scores, winner)) in self.rounds.iter_mut().zip(tallies) {
+            round.scores = scores;
+            round.winner = winner;
+        }
+        if self.status == SessionStatus::Finished {
+            self.winner = self.match_winner();
+        }
+    }
+
+    // Ganador de un asalto; con jueces hace falta la mayoría del panel
+    fn round_winner(&self, round: u32) -> Option<String> {
+        let tally = self.round_tally(round);
+        match self.rule_set.decision {
+            MatchDecision::Judges => judging::majority_winner(&tally, self.judges.len()),
+            _ => leader(&tally),
+        }
+    }
+
+    // Ganador del combate; con jueces hace falta la mayoría del panel
+    fn match_winner(&self) -> Option<String> {
+        let tally = self.match_tally();
+        match self.rule_set.decision {
+            MatchDecision::Judges => judging::majority_winner(&tally, self.judges.len()),
+            _ => leader(&tally),
+        }
+    }
+
+    // Lo que decide un asalto según las reglas
+    fn round_tally(&self, round: u32) -> BTreeMap<String, i32> {
+        match self.rule_set.decision {
+            MatchDecision::TotalForce => self.turn_forces(Some(round)),
+            MatchDecision::Judges => judging::round_tally(self, round),
+            MatchDecision::TotalPoints | MatchDecision::RoundsWon => self.scores(Some(round)),
+        }
+    }
+
+    // Cierra el turno en curso y pasa al siguiente peleador
+    fn advance_turn(&mut self, accepted: Option<&SessionEntry>, now: u64) -> Option<TurnResult> {
+        let turn = self.turn.take()?;
+        let event = accepted.and_then(|entry| match &entry.kind {
+            EntryKind::Strike { event, .. } => Some(event),
+            EntryKind::Penalty { .. } => None,
+        });
+        let result = TurnResult {
+            number: turn.number,
+            round: self.current_round,
+            fighter_id: turn.fighter_id.clone(),
+            entry_id: accepted.map(|entry| entry.id),
+            event_type: event.map(|event| event.event_type.clone()),
+            force: event.and_then(|event| event.force),
+            points: accepted.map_or(0, |entry| entry.points),
+            finished_at: now,
+        };
+        self.turns.push(result.clone());
+
+        let position = self.turn_order.iter().position(|id| *id == turn.fighter_id).unwrap_or(0);
+        self.turn = Some(TurnState {
+            number: turn.number + 1,
+            fighter_id: self.turn_order[(position + 1) % self.turn_order.len()].clone(),
+            window_opened_at: None,
+            window_closes_at: None,
+        });
+        Some(result)
+    }
+
+    // Golpes vigentes en orden cronológico con los récords que bate cada uno
+    fn replay_strikes(&self) -> (Vec<usize>, std::collections::HashMap<String, CompetitorMaxStats>, Vec<Vec<&'static str>>) {
+        let mut strikes: Vec<usize> = (0..self.event_log.len())
+            .filter(|&index| !self.event_log[index].voided && matches!(self.event_log[index].kind, EntryKind::Strike { .. }))
+            .collect();
+        strikes.sort_by_key(|&index| (self.event_log[index].timestamp, self.event_log[index].id));
+        let (max_stats, records) = simple_ble::replay_max_stats(strikes.iter().filter_map(|&index| {
+            match &self.event_log[index].kind {
+                EntryKind::Strike { event, .. } => Some(event.as_ref()),
+                EntryKind::Penalty { .. } => None,
+            }
+        }));
+        (strikes, max_stats, records)
+    }
+
+    // Registra un golpe de las bandas con sus récords, puntos y, en modo por turnos, el turno que resuelve
+    fn register_strike(&mut self, event: &SimpleCombatEvent, now: u64) -> StrikeOutcome {
+        // Modo por turnos: fuera de una ventana abierta las bandas no puntúan
+        let turn_check = match (&self.rule_set.mode, &self.turn) {
+            (MatchMode::Continuous, _) => Ok(()),
+            (MatchMode::TurnBased, Some(TurnState { fighter_id, window_closes_at: Some(closes_at), .. })) => {
+                if *fighter_id != event.fighter_id {
+                    Err("no es su turno".to_string())
+                } else if now > *closes_at {
+                    Err("fuera de la ventana de golpe".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+            // Sin ventana abierta el golpe queda registrado como rechazado, sin puntos
+            (MatchMode::TurnBased, _) => Err("ventana cerrada".to_string()),
+        };
+
+        // El golpe entra en el registro y sus récords se derivan de él, como en una corrección
+        let entry_id = self.push_entry(&event.fighter_id, event.timestamp, 0, EntryKind::Strike {
+            event: Box::new(event.clone()),
+            records: Vec::new(),
+            rejected: None,
+        }).id;
+        let (strikes, max_stats, replayed) = self.replay_strikes();
+        let new_records = strikes.iter().zip(replayed)
+            .find(|(&index, _)| self.event_log[index].id == entry_id)
+            .map(|(_, records)| records)
+            .unwrap_or_default();
+
+        // El primer golpe del peleador activo es el resultado del turno, aunque no puntúe
+        let takes_turn = self.rule_set.mode == MatchMode::TurnBased && turn_check.is_ok();
+        let (points, rejected) = match turn_check.and_then(|_| self.rule_set.score_strike(event, new_records.len(), false)) {
+            Ok(points) => (points, None),
+            Err(reason) => (0, Some(reason)),
+        };
+        let last = self.event_log.last_mut().expect("el golpe se acaba de registrar");
+        last.points = points;
+        if let EntryKind::Strike { records, rejected: entry_rejected, .. } = &mut last.kind {
+            *records = new_records.iter().map(|record| record.to_string()).collect();
+            *entry_rejected = rejected;
+        }
+        let entry = last.clone();
+        let turn_result = if takes_turn { self.advance_turn(Some(&entry), now) } else { None };
+        StrikeOutcome { entry, records: new_records, max_stats, turn_result }
+    }
+
+    // Cierra el asalto en curso; tras el último termina el combate
+    fn close_round(&mut self, now: u64) {
+        let result = RoundResult {
+            round: self.current_round,
+            started_at: self.round_started_at,
+            ended_at: now,
+            winner: self.round_winner(self.current_round),
+            scores: self.scores(Some(self.current_round)),
+        };
+        // Un turno con la ventana abierta no pasa al asalto siguiente
+        if let Some(turn) = self.turn.as_mut() {
+            turn.window_opened_at = None;
+            turn.window_closes_at = None;
+        }
+        info!(round = result.round, winner = ?result.winner, scores = ?result.scores, "🔔 Asalto terminado");
+        self.rounds.push(result);
+
+        if self.current_round < self.rule_set.rounds {
+            self.current_round += 1;
+            self.round_started_at = now;
+        } else {
+            finish(self, now);
+        }
+    }
+
+    // Marca una entrada como anulada (sin recalcular)
+    fn void(&mut self, entry_id: u64) -> Result<Vec<(u64, AuditAction)>, String> {
+        let entry = self.event_log.iter_mut().find(|entry| entry.id == entry_id)
+            .ok_or_else(|| format!("No existe la entrada {}", entry_id))?;
+        if entry.voided {
+            return Err(format!("La entrada {} ya está anulada", entry_id));
+        }
+        entry.voided = true;
+        Ok(vec![(entry_id, AuditAction::Void)])
+    }
+
+    // Cambia los campos de un golpe registrado (sin recalcular)
+    fn amend(&mut self, entry_id: u64, amendment: StrikeAmendment) -> Result<Vec<(u64, AuditAction)>, String> {
+        let new_competitor = match &amendment.fighter_id {
+            Some(fighter_id) => Some(self.fighters.get(fighter_id).cloned()
+                .ok_or_else(|| format!("{} no participa en la sesión", fighter_id))?),
+            None => None,
+        };
+        let entry = self.event_log.iter_mut().find(|entry| entry.id == entry_id)
+            .ok_or_else(|| format!("No existe la entrada {}", entry_id))?;
+        if entry.voided {
+            return Err(format!("La entrada {} está anulada", entry_id));
+        }
+        let EntryKind::Strike { event, .. } = &mut entry.kind else {
+            return Err(format!("La entrada {} no es un golpe", entry_id));
+        };
+
+        let mut actions = Vec::new();
+        let mut change = |field: &str, previous: serde_json::Value, updated: serde_json::Value| {
+            if previous != updated {
+                actions.push((entry_id, AuditAction::Amend { field: field.to_string(), previous, updated }));
+            }
+        };
+        if let Some(event_type) = amendment.event_type {
+            change("event_type", event.event_type.clone().into(), event_type.clone().into());
+            event.event_type = event_type;
+        }
+        if let (Some(fighter_id), Some(competitor_name)) = (amendment.fighter_id, new_competitor) {
+            change("fighter_id", event.fighter_id.clone().into(), fighter_id.clone().into());
+            event.fighter_id = fighter_id.clone();
+            event.competitor_name = competitor_name;
+            entry.fighter_id = fighter_id;
+        }
+        if let Some(force) = amendment.force {
+            change("force", serde_json::json!(event.force), serde_json::json!(force));
+            event.force = Some(force);
+        }
+
+        if actions.is_empty() {
+            return Err("La corrección no cambia nada".to_string());
+        }
+        Ok(actions)
+    }
+
+    fn push_entry(&mut self, fighter_id: &str, timestamp: u64, points: i32, kind: EntryKind) -> SessionEntry {
+        let entry = SessionEntry {
+            id: self.event_log.last().map_or(1, |last| last.id + 1),
+            round: self.current_round,
+            fighter_id: fighter_id.to_string(),
+            timestamp,
+            points,
+            kind,
+            voided: false,
+            manual: false,
+        };
+        self.event_log.push(entry.clone());
+        entry
+    }
+
+    fn scoreboard(&self) -> ScoreBoard {
+        ScoreBoard {
+            session_id: self.id.clone(),
+            status: self.status,
+            current_round: self.current_round,
+            total_rounds: self.rule_set.rounds,
+            fighters: self.fighters.clone(),
+            round_scores: self.scores(Some(self.current_round)),
+            total_scores: self.scores(None),
+            rounds: self.rounds.clone(),
+            winner: self.winner.clone(),
+            mode: self.rule_set.mode,
+            turn: self.turn.clone(),
+            turns: self.turns.clone(),
+            judging: judging::summary(self),
+        }
+    }
+}
+
+// Resultado de registrar un golpe de las bandas
+struct StrikeOutcome {
+    entry: SessionEntry,
+    records: Vec<&'static str>,   // Récords que bate el golpe
+    max_stats: std::collections::HashMap<String, CompetitorMaxStats>,
+    turn_result: Option<TurnResult>,
+}
+
+/// Marcador publicado al frontend y por WebSocket
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScoreBoard {
+    pub session_id: String,
+    pub status: SessionStatus,
+    pub current_round: u32,
+    pub total_rounds: u32,
+    pub fighters: BTreeMap<String, String>,
+    pub round_scores: BTreeMap<String, i32>,
+    pub total_scores: BTreeMap<String, i32>,
+    pub rounds: Vec<RoundResult>,
+    pub winner: Option<String>,
+    pub mode: MatchMode,
+    pub turn: Option<TurnState>,
+    pub turns: Vec<TurnResult>,
+    pub judging: Option<JudgingSummary>,
+}
+
+// Mayor puntuación; None si hay empate en cabeza
+pub(crate) fn leader(scores: &BTreeMap<String, i32>) -> Option<String> {
+    let best = scores.values().max()?;
+    let mut leaders = scores.iter().filter(|&(_, points)| points == best);
+    let (fighter_id, _) = leaders.next()?;
+    leaders.next().is_none().then(|| fighter_id.clone())
+}
+
+static RULE_SETS: Lazy<RwLock<BTreeMap<String, ScoringRuleSet>>> = Lazy::new(|| {
+    let standard = ScoringRuleSet::default();
+    RwLock::new(BTreeMap::from([(standard.name.clone(), standard)]))
+});
+static ACTIVE_SESSION: Lazy<Mutex<Option<CombatSession>>> = Lazy::new(|| Mutex::new(None));
+// Ordena las escrituras del archivo de sesión: se toma con ACTIVE_SESSION bloqueada
+static SESSION_WRITER: Mutex<()> = Mutex::new(());
+// Hay un guardado diferido de golpes pendiente
+static SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+// Los golpes seguidos se agrupan en una sola escritura
+const STRIKE_SAVE_DELAY: Duration = Duration::from_millis(250);
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Conjuntos de reglas disponibles
+pub fn rule_sets() -> Vec<ScoringRuleSet> {
+    RULE_SETS.read().unwrap().values().cloned().collect()
+}
+
+/// Guarda (o reemplaza) un conjunto de reglas
+pub fn save_rule_set<R: tauri::Runtime>(app_handle: &AppHandle<R>, rule_set: ScoringRuleSet) -> Result<(), String> {
+    rule_set.validate()?;
+    let mut rule_sets = RULE_SETS.write().unwrap();
+    rule_sets.insert(rule_set.name.clone(), rule_set);
+    storage::save_json(app_handle, RULE_SETS_FILE, &*rule_sets)
+}
+
+/// Elimina un conjunto de reglas (el estándar no se puede eliminar)
+pub fn delete_rule_set<R: tauri::Runtime>(app_handle: &AppHandle<R>, name: &str) -> Result<(), String> {
+    if name == DEFAULT_RULE_SET {
+        return Err("El conjunto de reglas estándar no se puede eliminar".to_string());
+    }
+    let mut rule_sets = RULE_SETS.write().unwrap();
+    rule_sets.remove(name)
+        .ok_or_else(|| format!("No existe el conjunto de reglas {}", name))?;
+    storage::save_json(app_handle, RULE_SETS_FILE, &*rule_sets)
+}
+
+/// Carga los conjuntos de reglas guardados al iniciar la aplicación
+pub fn load_rule_sets<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(saved) = storage::load_json::<BTreeMap<String, ScoringRuleSet>, R>(app_handle, RULE_SETS_FILE) else {
+        return;
+    };
+
+    let mut rule_sets = RULE_SETS.write().unwrap();
+    for (name, rule_set) in saved {
+        match rule_set.validate() {
+            Ok(()) => {
+                rule_sets.insert(name, rule_set);
+            }
+            Err(e) => error!(name = %name, error = %e, "❌ Conjunto de reglas guardado inválido, se ignora"),
+        }
+    }
+    info!(rule_sets = rule_sets.len(), "📏 Reglas de puntuación cargadas");
+}
+
+fn session_path<R: tauri::Runtime>(app_handle: &AppHandle<R>, session_id: &str) -> Result<PathBuf, String> {
+    if session_id.contains(['/', '\\']) || session_id.starts_with('.') {
+        return Err(format!("Identificador de sesión inválido: {}", session_id));
+    }
+    Ok(storage::data_subdir(app_handle, SESSIONS_DIR)?.join(format!("{}.json", session_id)))
+}
+
+fn save_session<R: tauri::Runtime>(app_handle: &AppHandle<R>, session: &CombatSession) -> Result<(), String> {
+    let _writer = SESSION_WRITER.lock().unwrap();
+    storage::save_json_at(&session_path(app_handle, &session.id)?, session)
+}
+
+// Guarda la sesión activa fuera de la tarea BLE; los golpes que lleguen mientras tanto
+// entran en la misma escritura
+fn schedule_strike_save<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    if SAVE_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STRIKE_SAVE_DELAY).await;
+        let result = tokio::task::spawn_blocking(move || {
+            let (session, _writer) = {
+                let active = ACTIVE_SESSION.lock().unwrap();
+                SAVE_PENDING.store(false, Ordering::SeqCst);
+                let Some(session) = active.clone() else {
+                    return Ok(());
+                };
+                // El turno de escritura se toma antes de soltar la sesión para que un
+                // guardado posterior no quede pisado por esta instantánea
+                (session, SESSION_WRITER.lock().unwrap())
+            };
+            storage::save_json_at(&session_path(&app_handle, &session.id)?, &session)
+        }).await;
+        match result {
+            Ok(Err(e)) => error!(error = %e, "❌ Error guardando la sesión tras el golpe"),
+            Err(e) => error!(error = %e, "❌ Error en la tarea de guardado de la sesión"),
+            Ok(Ok(())) => {}
+        }
+    });
+}
+
+// Publica el marcador al frontend y por WebSocket
+fn publish<R: tauri::Runtime>(app_handle: &AppHandle<R>, message_type: &str, scoreboard: &ScoreBoard, entry: Option<&SessionEntry>) {
+    if let Err(e) = app_handle.emit("score-update", scoreboard) {
+        error!(error = %e, "Error emitiendo marcador");
+    }
+    ws_broadcast(&serde_json::json!({
+        "type": message_type,
+        "data": scoreboard,
+        "entry": entry,
+        "timestamp": now_ms(),
+    }));
+
+    // Los resultados (y sus correcciones) avanzan el cuadro si la pelea es de un torneo
+    if scoreboard.status == SessionStatus::Finished {
+        tournament::record_result(app_handle, scoreboard);
+    }
+}
+
+/// Empieza una sesión de combate con un conjunto de reglas guardado
+pub fn start_session<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    rule_set_name: Option<&str>,
+    fighters: BTreeMap<String, String>,
+    roster_ids: BTreeMap<String, String>,
+    turn_order: Option<Vec<String>>,
+) -> Result<ScoreBoard, String> {
+    if fighters.len() < 2 {
+        return Err("El combate necesita al menos dos peleadores".to_string());
+    }
+    if let Some(unknown) = roster_ids.keys().find(|id| !fighters.contains_key(*id)) {
+        return Err(format!("{} tiene competidor de la plantilla pero no está en el combate", unknown));
+    }
+    // Los competidores indicados prevalecen sobre los de las bandas ya conectadas
+    let mut linked = simple_ble::assigned_competitors();
+    linked.retain(|fighter_id, _| fighters.contains_key(fighter_id));
+    linked.extend(roster_ids);
+    let rule_set_name = rule_set_name.unwrap_or(DEFAULT_RULE_SET);
+    let rule_set = RULE_SETS.read().unwrap().get(rule_set_name).cloned()
+        .ok_or_else(|| format!("No existe el conjunto de reglas {}", rule_set_name))?;
+
+    // Orden de turnos: el indicado o el de los identificadores
+    let turn_order = turn_order.unwrap_or_else(|| fighters.keys().cloned().collect());
+    if let Some(unknown) = turn_order.iter().find(|id| !fighters.contains_key(*id)) {
+        return Err(format!("{} está en el orden de turnos pero no en el combate", unknown));
+    }
+    if turn_order.is_empty() {
+        return Err("El orden de turnos está vacío".to_string());
+    }
+    let turn = (rule_set.mode == MatchMode::TurnBased).then(|| TurnState {
+        number: 1,
+        fighter_id: turn_order[0].clone(),
+        window_opened_at: None,
+        window_closes_at: None,
+    });
+
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    if active.as_ref().is_some_and(|session| session.status == SessionStatus::InProgress) {
+        return Err("Ya hay una sesión de combate en curso".to_string());
+    }
+
+    let started_at = now_ms();
+    let session = CombatSession {
+        id: format!("session_{}", started_at),
+        started_at,
+        ended_at: None,
+        rule_set,
+        fighters,
+        roster_ids: linked,
+        status: SessionStatus::InProgress,
+        current_round: 1,
+        round_started_at: started_at,
+        rounds: Vec::new(),
+        event_log: Vec::new(),
+        winner: None,
+        turn_order,
+        turn,
+        turns: Vec::new(),
+        audit_log: Vec::new(),
+        judges: judging::panel(),
+        judge_cards: Vec::new(),
+        fouls: Vec::new(),
+    };
+    save_session(app_handle, &session)?;
+
+    let scoreboard = session.scoreboard();
+    let fighters: Vec<String> = session.fighters.keys().cloned().collect();
+    info!(session_id = %session.id, rule_set = %session.rule_set.name, fighters = ?session.fighters.keys(),
+          "🏁 Sesión de combate iniciada");
+    *active = Some(session);
+    drop(active);
+
+    // Los récords de la sesión se derivan de su propio registro
+    simple_ble::replace_max_stats(&fighters, Default::default());
+    publish(app_handle, "score_update", &scoreboard, None);
+    Ok(scoreboard)
+}
+
+/// Asocia un peleador de la sesión en curso con su competidor de la plantilla (al conectar una banda)
+pub fn link_competitor<R: tauri::Runtime>(app_handle: &AppHandle<R>, fighter_id: &str, roster_id: &str) {
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let Some(session) = active.as_mut()
+        .filter(|session| session.status == SessionStatus::InProgress && session.fighters.contains_key(fighter_id)) else {
+        return;
+    };
+    match session.roster_ids.insert(fighter_id.to_string(), roster_id.to_string()) {
+        Some(previous) if previous == roster_id => return,
+        Some(previous) => warn!(fighter_id = %fighter_id, previous = %previous, roster_id = %roster_id,
+                                "⚠️ La banda conectada cambia el competidor del peleador"),
+        None => info!(fighter_id = %fighter_id, roster_id = %roster_id, "🔗 Competidor asociado a la sesión"),
+    }
+    if let Err(e) = save_session(app_handle, session) {
+        error!(error = %e, "❌ Error guardando la sesión tras asociar el competidor");
+    }
+}
+
+/// Puntúa un evento detectado si hay una sesión en curso con ese peleador
+/// Devuelve false si ninguna sesión lo registró
+pub fn score_event<R: tauri::Runtime>(app_handle: &AppHandle<R>, event: &SimpleCombatEvent) -> bool {
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let Some(session) = active.as_mut().filter(|session| session.status == SessionStatus::InProgress) else {
+        return false;
+    };
+    if !session.fighters.contains_key(&event.fighter_id) {
+        return false;
+    }
+
+    let outcome = session.register_strike(event, now_ms());
+    let scoreboard = session.scoreboard();
+    let fighters: Vec<String> = session.fighters.keys().cloned().collect();
+    drop(active);
+
+    // Cada golpe se guarda enseguida para no perder el asalto si la aplicación se cierra,
+    // pero sin escribir el archivo desde la tarea de notificaciones BLE
+    schedule_strike_save(app_handle);
+
+    simple_ble::store_max_stats(&fighters, outcome.max_stats);
+    simple_ble::announce_new_records(app_handle, event, &outcome.records);
+    if let Some(turn_result) = outcome.turn_result {
+        info!(fighter_id = %turn_result.fighter_id, turn = turn_result.number, force = ?turn_result.force,
+              "👋 Turno completado");
+    }
+    publish(app_handle, "score_update", &scoreboard, Some(&outcome.entry));
+    true
+}
+
+/// Abre la ventana de golpe del turno en curso; al vencer sin golpe el turno se pierde
+pub fn open_turn_window<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<ScoreBoard, String> {
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let session = active.as_mut().filter(|session| session.status == SessionStatus::InProgress)
+        .ok_or("No hay una sesión de combate en curso")?;
+    let window_ms = session.rule_set.turn_window_ms;
+    let turn = session.turn.as_mut()
+        .ok_or("La sesión no está en modo por turnos")?;
+    if turn.window_opened_at.is_some() {
+        return Err(format!("La ventana del turno {} ya está abierta", turn.number));
+    }
+
+    let now = now_ms();
+    turn.window_opened_at = Some(now);
+    turn.window_closes_at = Some(now + window_ms);
+    let turn_number = turn.number;
+    info!(fighter_id = %turn.fighter_id, turn = turn_number, window_ms = window_ms, "⏱️ Ventana de golpe abierta");
+    let scoreboard = session.scoreboard();
+    drop(active);
+
+    publish(app_handle, "turn_update", &scoreboard, None);
+
+    // Vencimiento de esta ventana (no de otra reabierta después en el mismo turno)
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(window_ms)).await;
+        expire_turn(&app_handle, turn_number, now);
+    });
+    Ok(scoreboard)
+}
+
+// Cierra el turno sin golpe si la ventana abierta en window_opened_at sigue abierta
+fn expire_turn<R: tauri::Runtime>(app_handle: &AppHandle<R>, turn_number: u32, window_opened_at: u64) {
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let Some(session) = active.as_mut().filter(|session| session.status == SessionStatus::InProgress) else {
+        return;
+    };
+    let expired = session.turn.as_ref()
+        .is_some_and(|turn| turn.number == turn_number && turn.window_opened_at == Some(window_opened_at));
+    if !expired {
+        return; // El peleador ya golpeó, el turno cambió o la ventana se cerró y se reabrió
+    }
+
+    let Some(turn_result) = session.advance_turn(None, now_ms()) else {
+        return;
+    };
+    if let Err(e) = save_session(app_handle, session) {
+        error!(error = %e, "❌ Error guardando la sesión tras el turno");
+    }
+    let scoreboard = session.scoreboard();
+    drop(active);
+
+    info!(fighter_id = %turn_result.fighter_id, turn = turn_result.number, "⌛ Turno perdido: sin golpe en la ventana");
+    publish(app_handle, "turn_update", &scoreboard, None);
+}
+
+/// Aplica una penalización del árbitro al peleador
+pub fn apply_penalty<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    fighter_id: &str,
+    penalty: &str,
+    note: Option<String>,
+) -> Result<ScoreBoard, String> {
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let session = active.as_mut().filter(|session| session.status == SessionStatus::InProgress)
+        .ok_or("No hay una sesión de combate en curso")?;
+    if !session.fighters.contains_key(fighter_id) {
+        return Err(format!("{} no participa en la sesión", fighter_id));
+    }
+    let points = *session.rule_set.penalties.get(penalty)
+        .ok_or_else(|| format!("Penalización desconocida: {}", penalty))?;
+
+    let entry = session.push_entry(fighter_id, now_ms(), -points, EntryKind::Penalty { penalty: penalty.to_string(), note });
+    save_session(app_handle, session)?;
+    let scoreboard = session.scoreboard();
+    drop(active);
+
+    info!(fighter_id = %fighter_id, penalty = %penalty, points = points, "🟨 Penalización aplicada");
+    publish(app_handle, "score_update", &scoreboard, Some(&entry));
+    Ok(scoreboard)
+}
+
+/// Cierra el asalto en curso; tras el último decide el ganador del combate
+pub fn end_round<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<ScoreBoard, String> {
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let session = active.as_mut().filter(|session| session.status == SessionStatus::InProgress)
+        .ok_or("No hay una sesión de combate en curso")?;
+
+    session.close_round(now_ms());
+    save_session(app_handle, session)?;
+    let scoreboard = session.scoreboard();
+    drop(active);
+
+    let message_type = if scoreboard.status == SessionStatus::Finished { "match_result" } else { "round_result" };
+    publish(app_handle, message_type, &scoreboard, None);
+    Ok(scoreboard)
+}
+
+/// Termina el combate antes de completar los asaltos (el asalto en curso no cuenta como ganado)
+pub fn finish_session<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<ScoreBoard, String> {
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let session = active.as_mut().filter(|session| session.status == SessionStatus::InProgress)
+        .ok_or("No hay una sesión de combate en curso")?;
+
+    finish(session, now_ms());
+    save_session(app_handle, session)?;
+    let scoreboard = session.scoreboard();
+    drop(active);
+
+    publish(app_handle, "match_result", &scoreboard, None);
+    Ok(scoreboard)
+}
+
+fn finish(session: &mut CombatSession, now: u64) {
+    let tally = session.match_tally();
+
+    session.winner = session.match_winner();
+    session.status = SessionStatus::Finished;
+    session.turn = None;
+    session.ended_at = Some(now);
+    info!(session_id = %session.id, winner = ?session.winner, tally = ?tally, "🏆 Combate terminado");
+}
+
+// Aplica una corrección del árbitro, la audita, recalcula desde el registro y publica
+fn apply_correction<R, F>(app_handle: &AppHandle<R>, referee: &str, reason: &str, correction: F) -> Result<ScoreBoard, String>
+where
+    R: tauri::Runtime,
+    F: FnOnce(&mut CombatSession) -> Result<Vec<(u64, AuditAction)>, String>,
+{
+    if referee.trim().is_empty() || reason.trim().is_empty() {
+        return Err("Las correcciones necesitan el árbitro y el motivo".to_string());
+    }
+
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let session = active.as_mut().ok_or("No hay una sesión de combate")?;
+    let actions = correction(session)?;
+
+    let now = now_ms();
+    for (entry_id, action) in actions {
+        info!(session_id = %session.id, entry_id = entry_id, referee = %referee, action = ?action, "🧑‍⚖️ Corrección del árbitro");
+        session.audit_log.push(AuditRecord {
+            at: now,
+            referee: referee.to_string(),
+            reason: reason.to_string(),
+            entry_id,
+            action,
+        });
+    }
+    let max_stats = session.recompute();
+    save_session(app_handle, session)?;
+    let scoreboard = session.scoreboard();
+    let fighters: Vec<String> = session.fighters.keys().cloned().collect();
+    drop(active);
+
+    simple_ble::replace_max_stats(&fighters, max_stats);
+    publish(app_handle, "score_correction", &scoreboard, None);
+    Ok(scoreboard)
+}
+
+/// Anula una entrada del registro (golpe o penalización)
+pub fn void_entry<R: tauri::Runtime>(app_handle: &AppHandle<R>, entry_id: u64, referee: &str, reason: &str) -> Result<ScoreBoard, String> {
+    apply_correction(app_handle, referee, reason, |session| session.void(entry_id))
+}
+
+/// Añade un golpe que los sensores no detectaron
+pub fn add_manual_strike<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    fighter_id: &str,
+    event_type: &str,
+    force: Option<f32>,
+    round: Option<u32>,
+    referee: &str,
+    reason: &str,
+) -> Result<ScoreBoard, String> {
+    apply_correction(app_handle, referee, reason, |session| {
+        let competitor_name = session.fighters.get(fighter_id).cloned()
+            .ok_or_else(|| format!("{} no participa en la sesión", fighter_id))?;
+        if event_type.trim().is_empty() {
+            return Err("El golpe necesita un tipo".to_string());
+        }
+        let round = round.unwrap_or(session.current_round);
+        if round == 0 || round > session.current_round {
+            return Err(format!("Asalto {} fuera de rango (1-{})", round, session.current_round));
+        }
+
+        let now = now_ms();
+        let event = SimpleCombatEvent {
+            event_type: event_type.to_string(),
+            limb_name: "Manual".to_string(),
+            fighter_id: fighter_id.to_string(),
+            competitor_name,
+            velocity: None,
+            velocity_method: VelocityMethod::Manual,
+            acceleration: None,
+            force,
+            force_model: ForceModelRecord { name: "manual".to_string(), parameters: serde_json::Value::Null },
+            timestamp: now,
+            device_timestamp: None,
+            received_at: now,
+            duration_ms: 0,
+            confidence: 1.0,
+        };
+        let entry_id = session.push_entry(fighter_id, now, 0, EntryKind::Strike {
+            event: Box::new(event),
+            records: Vec::new(),
+            rejected: None,
+        }).id;
+        if let Some(entry) = session.event_log.last_mut() {
+            entry.round = round;
+            entry.manual = true;
+        }
+        Ok(vec![(entry_id, AuditAction::Add)])
+    })
+}
+
+/// Corrige el tipo, el peleador o la fuerza de un golpe registrado
+pub fn amend_strike<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    entry_id: u64,
+    amendment: StrikeAmendment,
+    referee: &str,
+    reason: &str,
+) -> Result<ScoreBoard, String> {
+    apply_correction(app_handle, referee, reason, |session| session.amend(entry_id, amendment))
+}
+
+/// Modifica la sesión actual, la guarda y publica el marcador
+pub(crate) fn update_session<R, T, F>(app_handle: &AppHandle<R>, message_type: &str, update: F) -> Result<T, String>
+where
+    R: tauri::Runtime,
+    F: FnOnce(&mut CombatSession) -> Result<T, String>,
+{
+    let mut active = ACTIVE_SESSION.lock().unwrap();
+    let session = active.as_mut().ok_or("No hay una sesión de combate")?;
+    let value = update(session)?;
+    save_session(app_handle, session)?;
+    let scoreboard = session.scoreboard();
+    drop(active);
+
+    publish(app_handle, message_type, &scoreboard, None);
+    Ok(value)
+}
+
+/// Estadísticas de la sesión actual derivadas del registro (sin entradas anuladas)
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SessionStats {
+    pub session_id: String,
+    pub total_events: u32,
+    pub voided_events: u32,
+    pub manual_events: u32,
+    pub by_type: BTreeMap<String, u32>,
+    pub by_fighter: BTreeMap<String, u32>,
+    pub last_event_time: Option<u64>,
+}
+
+pub fn session_stats() -> Option<SessionStats> {
+    let active = ACTIVE_SESSION.lock().unwrap();
+    let session = active.as_ref()?;
+
+    let mut stats = SessionStats { session_id: session.id.clone(), ..SessionStats::default() };
+    for entry in &session.event_log {
+        let EntryKind::Strike { event, .. } = &entry.kind else {
+            continue;
+        };
+        if entry.voided {
+            stats.voided_events += 1;
+            continue;
+        }
+        stats.total_events += 1;
+        stats.manual_events += entry.manual as u32;
+        *stats.by_type.entry(event.event_type.clone()).or_default() += 1;
+        *stats.by_fighter.entry(entry.fighter_id.clone()).or_default() += 1;
+        stats.last_event_time = stats.last_event_time.max(Some(entry.timestamp));
+    }
+    Some(stats)
+}
+
+/// Marcador de la sesión actual (o de la última terminada)
+pub fn current_scoreboard() -> Option<ScoreBoard> {
+    ACTIVE_SESSION.lock().unwrap().as_ref().map(CombatSession::scoreboard)
+}
+
+/// Copia completa de la sesión actual
+pub fn current_session() -> Option<CombatSession> {
+    ACTIVE_SESSION.lock().unwrap().clone()
+}
+
+/// Sesiones guardadas (identificadores, de la más antigua a la más reciente)
+pub fn list_sessions<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<Vec<String>, String> {
+    let dir = storage::data_subdir(app_handle, SESSIONS_DIR)?;
+    let mut ids: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Error leyendo sesiones: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter_map(|name| name.strip_suffix(".json").map(str::to_string))
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// Lee una sesión guardada
+pub fn load_session<R: tauri::Runtime>(app_handle: &AppHandle<R>, session_id: &str) -> Result<CombatSession, String> {
+    let path = session_path(app_handle, session_id)?;
+    storage::load_json_at(&path)
+        .ok_or_else(|| format!("No se pudo leer la sesión {}", session_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(rule_set: ScoringRuleSet) -> CombatSession {
+        let fighters: BTreeMap<String, String> = [("fighter_1", "Ana"), ("fighter_2", "Bea")].iter()
+            .map(|&(id, name)| (id.to_string(), name.to_string()))
+            .collect();
+        let turn = (rule_set.mode == MatchMode::TurnBased).then(|| TurnState {
+            number: 1,
+            fighter_id: "fighter_1".to_string(),
+            window_opened_at: None,
+            window_closes_at: None,
+        });
+        CombatSession {
+            id: "session_1".to_string(),
+            started_at: 0,
+            ended_at: None,
+            rule_set,
+            turn_order: fighters.keys().cloned().collect(),
+            fighters,
+            roster_ids: BTreeMap::new(),
+            status: SessionStatus::InProgress,
+            current_round: 1,
+            round_started_at: 0,
+            rounds: Vec::new(),
+            event_log: Vec::new(),
+            winner: None,
+            turn,
+            turns: Vec::new(),
+            audit_log: Vec::new(),
+            judges: BTreeMap::new(),
+            judge_cards: Vec::new(),
+            fouls: Vec::new(),
+        }
+    }
+
+    fn turn_based(decision: MatchDecision) -> ScoringRuleSet {
+        ScoringRuleSet { mode: MatchMode::TurnBased, decision, ..ScoringRuleSet::default() }
+    }
+
+    fn strike(fighter_id: &str, event_type: &str, force: f32, timestamp: u64) -> SimpleCombatEvent {
+        SimpleCombatEvent {
+            event_type: event_type.to_string(),
+            limb_name: "Mano Derecha".to_string(),
+            fighter_id: fighter_id.to_string(),
+            competitor_name: String::new(),
+            velocity: None,
+            velocity_method: VelocityMethod::Heuristic,
+            acceleration: None,
+            force: Some(force),
+            force_model: ForceModelRecord { name: "prueba".to_string(), parameters: serde_json::Value::Null },
+            timestamp,
+            device_timestamp: None,
+            received_at: timestamp,
+            duration_ms: 20,
+            confidence: 0.9,
+        }
+    }
+
+    fn open_window(session: &mut CombatSession, now: u64) {
+        let turn = session.turn.as_mut().unwrap();
+        turn.window_opened_at = Some(now);
+        turn.window_closes_at = Some(now + session.rule_set.turn_window_ms);
+    }
+
+    fn rejected(entry: &SessionEntry) -> Option<&str> {
+        match &entry.kind {
+            EntryKind::Strike { rejected, .. } => rejected.as_deref(),
+            EntryKind::Penalty { .. } => None,
+        }
+    }
+
+    #[test]
+    fn voiding_the_record_strike_restores_the_previous_record() {
+        let mut session = session(ScoringRuleSet::default());
+        let first = session.register_strike(&strike("fighter_1", "punch", 500.0, 100), 100);
+        let second = session.register_strike(&strike("fighter_1", "punch", 600.0, 200), 200);
+        assert_eq!(second.records, vec!["force"]);
+        assert_eq!(second.max_stats["fighter_1"].max_force, 600.0);
+
+        session.void(second.entry.id).unwrap();
+        let max_stats = session.recompute();
+        assert_eq!(max_stats["fighter_1"].max_force, 500.0);
+        let holder = session.event_log.iter().find(|entry| entry.id == first.entry.id).unwrap();
+        assert!(matches!(&holder.kind, EntryKind::Strike { records, .. } if records == &["force"]));
+        // Golpe (1) más el récord (1); el anulado no cuenta
+        assert_eq!(session.scores(None)["fighter_1"], 2);
+    }
+
+    #[test]
+    fn amending_the_fighter_moves_the_points() {
+        let mut session = session(ScoringRuleSet::default());
+        let kick = session.register_strike(&strike("fighter_1", "round_kick", 400.0, 100), 100);
+        assert_eq!(session.scores(None)["fighter_1"], 4);
+
+        let amendment = StrikeAmendment { fighter_id: Some("fighter_2".to_string()), ..StrikeAmendment::default() };
+        session.amend(kick.entry.id, amendment).unwrap();
+        let max_stats = session.recompute();
+        assert_eq!(session.scores(None)["fighter_1"], 0);
+        assert_eq!(session.scores(None)["fighter_2"], 4);
+        assert!(!max_stats.contains_key("fighter_1"));
+        assert_eq!(max_stats["fighter_2"].competitor_name, "Bea");
+    }
+
+    #[test]
+    fn turn_based_strikes_outside_the_turn_score_nothing() {
+        let mut session = session(turn_based(MatchDecision::TotalPoints));
+
+        let early = session.register_strike(&strike("fighter_1", "slap", 300.0, 50), 50);
+        assert_eq!((early.entry.points, rejected(&early.entry)), (0, Some("ventana cerrada")));
+
+        open_window(&mut session, 100);
+        let wrong = session.register_strike(&strike("fighter_2", "slap", 300.0, 200), 200);
+        assert_eq!((wrong.entry.points, rejected(&wrong.entry)), (0, Some("no es su turno")));
+        let late_at = 100 + session.rule_set.turn_window_ms + 1;
+        let late = session.register_strike(&strike("fighter_1", "slap", 300.0, late_at), late_at);
+        assert_eq!((late.entry.points, rejected(&late.entry)), (0, Some("fuera de la ventana de golpe")));
+        assert!(session.turns.is_empty());
+        assert_eq!(session.turn.as_ref().unwrap().fighter_id, "fighter_1");
+
+        open_window(&mut session, 10_000);
+        let accepted = session.register_strike(&strike("fighter_1", "slap", 300.0, 10_100), 10_100);
+        assert!(accepted.entry.points > 0);
+        assert_eq!(accepted.turn_result.unwrap().entry_id, Some(accepted.entry.id));
+        assert_eq!(session.turn.as_ref().unwrap().fighter_id, "fighter_2");
+        assert_eq!(session.scores(None)["fighter_1"], accepted.entry.points);
+    }
+
+    #[test]
+    fn rounds_won_ignores_the_points_margin() {
+        let rule_set = ScoringRuleSet { decision: MatchDecision::RoundsWon, ..ScoringRuleSet::default() };
+        let mut session = session(rule_set);
+        // fighter_1 gana dos asaltos por poco; fighter_2 gana el último con mucha diferencia
+        let rounds: [&[(&str, &str)]; 3] = [
+            &[("fighter_1", "front_kick"), ("fighter_2", "punch")],
+            &[("fighter_1", "front_kick"), ("fighter_2", "punch")],
+            &[("fighter_2", "kickdown"), ("fighter_2", "kickdown"), ("fighter_2", "kickdown")],
+        ];
+        let mut timestamp = 0;
+        for strikes in rounds {
+            for &(fighter_id, event_type) in strikes {
+                timestamp += 100;
+                // Fuerza decreciente: solo el primer golpe de cada uno bate récords
+                session.register_strike(&strike(fighter_id, event_type, 1_000.0 - timestamp as f32 / 10.0, timestamp), timestamp);
+            }
+            session.close_round(timestamp);
+        }
+
+        assert_eq!(session.status, SessionStatus::Finished);
+        assert!(session.scores(None)["fighter_2"] > session.scores(None)["fighter_1"]);
+        assert_eq!(session.winner.as_deref(), Some("fighter_1"));
+    }
+
+    #[test]
+    fn total_force_sums_the_turns() {
+        let mut session = session(turn_based(MatchDecision::TotalForce));
+        session.rule_set.rounds = 1;
+        // fighter_1 golpea más fuerte una vez; fighter_2 suma más fuerza en dos turnos
+        let turns = [("fighter_1", 700.0), ("fighter_2", 400.0), ("fighter_1", 0.0), ("fighter_2", 400.0)];
+        let mut now = 0;
+        for (fighter_id, force) in turns {
+            now += 10_000;
+            open_window(&mut session, now);
+            if force > 0.0 {
+                session.register_strike(&strike(fighter_id, "slap", force, now + 100), now + 100);
+            } else {
+                // Sin golpe en la ventana: el turno se pierde
+                session.advance_turn(None, now + session.rule_set.turn_window_ms);
+            }
+        }
+        session.close_round(now + 20_000);
+
+        assert_eq!(session.turn_forces(None)["fighter_1"], 700);
+        assert_eq!(session.turn_forces(None)["fighter_2"], 800);
+        assert_eq!(session.winner.as_deref(), Some("fighter_2"));
+    }
+}
@@ -0,0 +1,447 @@
+// Calibración del sensor por dispositivo
+// Banda quieta: sesgo del giroscopio y vector de gravedad (ángulo de montaje)
+// Seis orientaciones (opcional): sesgo y escala por eje del acelerómetro
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+use crate::storage;
+
+const CALIBRATIONS_FILE: &str = "calibrations.json";
+
+// Muestras por fase (200Hz)
+const STILL_SAMPLES: u32 = 400;       // 2 s con la banda quieta
+const ORIENTATION_SAMPLES: u32 = 200; // 1 s por orientación
+const ORIENTATION_COUNT: usize = 6;
+
+// Umbrales para considerar la banda en reposo (unidades sin calibrar)
+const MAX_STILL_GYRO_DPS: f32 = 15.0;
+const MAX_STILL_ACC_DEVIATION_G: f32 = 0.35;
+
+// Rango mínimo entre orientaciones opuestas para aceptar la escala (g)
+const MIN_AXIS_SPAN_G: f32 = 1.2;
+
+// Calibración de una banda (aplicada a valores ya escalados a g y °/s)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DeviceCalibration {
+    pub device_id: String,
+    pub gyro_bias: [f32; 3],           // °/s
+    pub acc_bias: [f32; 3],            // g
+    pub acc_scale: [f32; 3],           // Factor multiplicativo por eje
+    pub gravity: [f32; 3],             // Gravedad en reposo (g, marco del sensor calibrado)
+    pub mount_rotation: [[f32; 3]; 3], // Rota la gravedad en reposo al eje +Z
+    pub six_orientation: bool,
+    pub calibrated_at: u64,
+}
+
+impl DeviceCalibration {
+    /// Aplica sesgo, escala y rotación de montaje a una muestra escalada
+    pub fn apply(&self, acc: [f32; 3], gyro: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+        let acc = [
+            (acc[0] - self.acc_bias[0]) * self.acc_scale[0],
+            (acc[1] - self.acc_bias[1]) * self.acc_scale[1],
+            (acc[2] - self.acc_bias[2]) * self.acc_scale[2],
+        ];
+        let gyro = [
+            gyro[0] - self.gyro_bias[0],
+            gyro[1] - self.gyro_bias[1],
+            gyro[2] - self.gyro_bias[2],
+        ];
+
+        (rotate(&self.mount_rotation, acc), rotate(&self.mount_rotation, gyro))
+    }
+}
+
+// Estado de una calibración en curso enviado al frontend
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CalibrationStatus {
+    pub device_id: String,
+    pub phase: String,               // "collecting_still", "awaiting_orientation", "collecting_orientation", "completed", "failed", "cancelled"
+    pub six_orientation: bool,
+    pub orientations_captured: usize,
+    pub samples_collected: u32,
+    pub message: Option<String>,
+}
+
+// Promedio de muestras con verificación de reposo
+#[derive(Debug, Default, Clone)]
+struct SampleAccumulator {
+    count: u32,
+    sum_acc: [f32; 3],
+    sum_gyro: [f32; 3],
+}
+
+impl SampleAccumulator {
+    fn add(&mut self, acc: [f32; 3], gyro: [f32; 3]) {
+        self.count += 1;
+        for axis in 0..3 {
+            self.sum_acc[axis] += acc[axis];
+            self.sum_gyro[axis] += gyro[axis];
+        }
+    }
+
+    fn mean_acc(&self) -> [f32; 3] {
+        self.sum_acc.map(|sum| sum / self.count.max(1) as f32)
+    }
+
+    fn mean_gyro(&self) -> [f32; 3] {
+        self.sum_gyro.map(|sum| sum / self.count.max(1) as f32)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CalibrationPhase {
+    CollectingStill,
+    AwaitingOrientation,
+    CollectingOrientation,
+}
+
+// Sesión de calibración de una banda
+struct CalibrationSession {
+    six_orientation: bool,
+    phase: CalibrationPhase,
+    current: SampleAccumulator,
+    still: Option<SampleAccumulator>,
+    orientations: Vec<[f32; 3]>,
+}
+
+impl CalibrationSession {
+    fn status(&self, device_id: &str, message: Option<String>) -> CalibrationStatus {
+        CalibrationStatus {
+            device_id: device_id.to_string(),
+            phase: match self.phase {
+                CalibrationPhase::CollectingStill => "collecting_still",
+                CalibrationPhase::AwaitingOrientation => "awaiting_orientation",
+                CalibrationPhase::CollectingOrientation => "collecting_orientation",
+            }.to_string(),
+            six_orientation: self.six_orientation,
+            orientations_captured: self.orientations.len(),
+            samples_collected: self.current.count,
+            message,
+        }
+    }
+}
+
+// Resultado de procesar una muestra de calibración
+enum SessionUpdate {
+    Collecting,
+    Status(CalibrationStatus),
+    Completed(Result<DeviceCalibration, String>),
+}
+
+// Calibraciones guardadas por device_id
+static CALIBRATIONS: Lazy<RwLock<HashMap<String, DeviceCalibration>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+// Incrementa con cada cambio para que las conexiones refresquen su copia
+static CALIBRATION_REVISION: AtomicU64 = AtomicU64::new(0);
+
+// Sesiones de calibración activas
+static CALIBRATION_SESSIONS: Lazy<Arc<Mutex<HashMap<String, CalibrationSession>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+// Evita bloquear el mapa de sesiones a 200Hz cuando no hay calibraciones
+static ACTIVE_SESSIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Carga las calibraciones guardadas al iniciar la aplicación
+pub fn load_calibrations<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let stored: Vec<DeviceCalibration> = storage::load_json(app_handle, CALIBRATIONS_FILE).unwrap_or_default();
+    info!(calibrations = stored.len(), "📐 Calibraciones de sensores cargadas");
+
+    let mut calibrations = CALIBRATIONS.write().unwrap();
+    for calibration in stored {
+        calibrations.insert(calibration.device_id.clone(), calibration);
+    }
+    CALIBRATION_REVISION.fetch_add(1, Ordering::Relaxed);
+}
+
+fn persist_calibrations<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let calibrations: Vec<DeviceCalibration> = CALIBRATIONS.read().unwrap().values().cloned().collect();
+    if let Err(e) = storage::save_json(app_handle, CALIBRATIONS_FILE, &calibrations) {
+        error!(error = %e, "❌ Error guardando calibraciones");
+    }
+}
+
+/// Revisión actual del almacén de calibraciones
+pub fn calibration_revision() -> u64 {
+    CALIBRATION_REVISION.load(Ordering::Relaxed)
+}
+
+/// Calibración guardada de una banda
+pub fn get_calibration(device_id: &str) -> Option<DeviceCalibration> {
+    CALIBRATIONS.read().unwrap().get(device_id).cloned()
+}
+
+/// Todas las calibraciones guardadas
+pub fn get_calibrations() -> Vec<DeviceCalibration> {
+    CALIBRATIONS.read().unwrap().values().cloned().collect()
+}
+
+/// Elimina la calibración de una banda
+pub fn clear_calibration<R: tauri::Runtime>(app_handle: &AppHandle<R>, device_id: &str) -> Result<(), String> {
+    let removed = CALIBRATIONS.write().unwrap().remove(device_id);
+    if removed.is_none() {
+        return Err(format!("No hay calibración guardada para {}", device_id));
+    }
+
+    CALIBRATION_REVISION.fetch_add(1, Ordering::Relaxed);
+    persist_calibrations(app_handle);
+    Ok(())
+}
+
+/// Inicia (o reinicia) la calibración de una banda conectada
+pub fn start_calibration<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    device_id: &str,
+    six_orientation: bool,
+) -> CalibrationStatus {
+    let session = CalibrationSession {
+        six_orientation,
+        phase: CalibrationPhase::CollectingStill,
+        current: SampleAccumulator::default(),
+        still: None,
+        orientations: Vec::with_capacity(ORIENTATION_COUNT),
+    };
+    let status = session.status(device_id, Some("Mantener la banda quieta".to_string()));
+
+    let mut sessions = CALIBRATION_SESSIONS.lock().unwrap();
+    if sessions.insert(device_id.to_string(), session).is_none() {
+        ACTIVE_SESSIONS.fetch_add(1, Ordering::Relaxed);
+    }
+    drop(sessions);
+
+    info!(device_id = %device_id, six_orientation = six_orientation, "📐 Calibración iniciada");
+    emit_status(app_handle, &status);
+    status
+}
+
+/// Captura la siguiente orientación en la calibración de seis posiciones
+pub fn capture_orientation<R: tauri::Runtime>(app_handle: &AppHandle<R>, device_id: &str) -> Result<CalibrationStatus, String> {
+    let mut sessions = CALIBRATION_SESSIONS.lock().unwrap();
+    let session = sessions.get_mut(device_id)
+        .ok_or_else(|| format!("No hay calibración en curso para {}", device_id))?;
+
+    if session.phase != CalibrationPhase::AwaitingOrientation {
+        return Err("La calibración no está esperando una nueva orientación".to_string());
+    }
+
+    session.phase = CalibrationPhase::CollectingOrientation;
+    session.current = SampleAccumulator::default();
+    let status = session.status(device_id, Some("Mantener la banda quieta en la nueva orientación".to_string()));
+    drop(sessions);
+
+    emit_status(app_handle, &status);
+    Ok(status)
+}
+
+/// Cancela la calibración en curso
+pub fn cancel_calibration<R: tauri::Runtime>(app_handle: &AppHandle<R>, device_id: &str) -> Result<(), String> {
+    let session = CALIBRATION_SESSIONS.lock().unwrap().remove(device_id)
+        .ok_or_else(|| format!("No hay calibración en curso para {}", device_id))?;
+    ACTIVE_SESSIONS.fetch_sub(1, Ordering::Relaxed);
+
+    let mut status = session.status(device_id, Some("Calibración cancelada".to_string()));
+    status.phase = "cancelled".to_string();
+    emit_status(app_handle, &status);
+    Ok(())
+}
+
+/// Entrega una muestra escalada (sin calibrar) a la sesión activa de la banda
+/// CRÍTICO: se llama a 200Hz; sale de inmediato si no hay calibraciones en curso
+pub fn feed_sample<R: tauri::Runtime>(app_handle: &AppHandle<R>, device_id: &str, acc: [f32; 3], gyro: [f32; 3]) {
+    if ACTIVE_SESSIONS.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    let update = {
+        let mut sessions = CALIBRATION_SESSIONS.lock().unwrap();
+        let Some(session) = sessions.get_mut(device_id) else {
+            return;
+        };
+
+        let update = update_session(session, device_id, acc, gyro);
+        if matches!(update, SessionUpdate::Completed(_)) {
+            sessions.remove(device_id);
+            ACTIVE_SESSIONS.fetch_sub(1, Ordering::Relaxed);
+        }
+        update
+    };
+
+    match update {
+        SessionUpdate::Collecting => {}
+        SessionUpdate::Status(status) => emit_status(app_handle, &status),
+        SessionUpdate::Completed(Ok(calibration)) => {
+            info!(device_id = %device_id, gyro_bias = ?calibration.gyro_bias, gravity = ?calibration.gravity,
+                  "✅ Calibración completada");
+            CALIBRATIONS.write().unwrap().insert(device_id.to_string(), calibration);
+            CALIBRATION_REVISION.fetch_add(1, Ordering::Relaxed);
+            persist_calibrations(app_handle);
+
+            emit_status(app_handle, &CalibrationStatus {
+                device_id: device_id.to_string(),
+                phase: "completed".to_string(),
+                six_orientation: false,
+                orientations_captured: 0,
+                samples_collected: 0,
+                message: None,
+            });
+        }
+        SessionUpdate::Completed(Err(e)) => {
+            warn!(device_id = %device_id, error = %e, "⚠️ Calibración fallida");
+            emit_status(app_handle, &CalibrationStatus {
+                device_id: device_id.to_string(),
+                phase: "failed".to_string(),
+                six_orientation: false,
+                orientations_captured: 0,
+                samples_collected: 0,
+                message: Some(e),
+            });
+        }
+    }
+}
+
+fn update_session(session: &mut CalibrationSession, device_id: &str, acc: [f32; 3], gyro: [f32; 3]) -> SessionUpdate {
+    let target = match session.phase {
+        CalibrationPhase::CollectingStill => STILL_SAMPLES,
+        CalibrationPhase::CollectingOrientation => ORIENTATION_SAMPLES,
+        CalibrationPhase::AwaitingOrientation => return SessionUpdate::Collecting,
+    };
+
+    // Reiniciar la fase si la banda se mueve
+    if !is_still(acc, gyro) {
+        let restarted = session.current.count > 0;
+        session.current = SampleAccumulator::default();
+        return if restarted {
+            SessionUpdate::Status(session.status(device_id, Some("Movimiento detectado, reiniciando captura".to_string())))
+        } else {
+            SessionUpdate::Collecting
+        };
+    }
+
+    session.current.add(acc, gyro);
+    if session.current.count < target {
+        return SessionUpdate::Collecting;
+    }
+
+    // Fase completada
+    let captured = std::mem::take(&mut session.current);
+    match session.phase {
+        CalibrationPhase::CollectingStill => session.still = Some(captured),
+        CalibrationPhase::CollectingOrientation => session.orientations.push(captured.mean_acc()),
+        CalibrationPhase::AwaitingOrientation => {}
+    }
+
+    if session.six_orientation && session.orientations.len() < ORIENTATION_COUNT {
+        session.phase = CalibrationPhase::AwaitingOrientation;
+        let message = format!("Girar la banda a la orientación {} de {}", session.orientations.len() + 1, ORIENTATION_COUNT);
+        return SessionUpdate::Status(session.status(device_id, Some(message)));
+    }
+
+    SessionUpdate::Completed(compute_calibration(device_id, session))
+}
+
+fn is_still(acc: [f32; 3], gyro: [f32; 3]) -> bool {
+    let acc_magnitude = norm(acc);
+    norm(gyro) <= MAX_STILL_GYRO_DPS && (acc_magnitude - 1.0).abs() <= MAX_STILL_ACC_DEVIATION_G
+}
+
+/// Calcula la calibración final con las capturas de la sesión
+fn compute_calibration(device_id: &str, session: &CalibrationSession) -> Result<DeviceCalibration, String> {
+    let still = session.still.as_ref()
+        .ok_or_else(|| "Falta la captura en reposo".to_string())?;
+
+    // Sesgo y escala del acelerómetro a partir de orientaciones opuestas
+    let (acc_bias, acc_scale) = if session.six_orientation {
+        let mut bias = [0.0; 3];
+        let mut scale = [1.0; 3];
+        for axis in 0..3 {
+            let max = session.orientations.iter().map(|o| o[axis]).fold(f32::MIN, f32::max);
+            let min = session.orientations.iter().map(|o| o[axis]).fold(f32::MAX, f32::min);
+            let span = max - min;
+            if span < MIN_AXIS_SPAN_G {
+                return Err(format!("Orientaciones insuficientes para el eje {}: rango {:.2} g", axis, span));
+            }
+            bias[axis] = (max + min) / 2.0;
+            scale[axis] = 2.0 / span;
+        }
+        (bias, scale)
+    } else {
+        ([0.0; 3], [1.0; 3])
+    };
+
+    let rest_acc = still.mean_acc();
+    let gravity = [
+        (rest_acc[0] - acc_bias[0]) * acc_scale[0],
+        (rest_acc[1] - acc_bias[1]) * acc_scale[1],
+        (rest_acc[2] - acc_bias[2]) * acc_scale[2],
+    ];
+    if norm(gravity) < 0.5 {
+        return Err("Vector de gravedad inválido en reposo".to_string());
+    }
+
+    Ok(DeviceCalibration {
+        device_id: device_id.to_string(),
+        gyro_bias: still.mean_gyro(),
+        acc_bias,
+        acc_scale,
+        gravity,
+        mount_rotation: rotation_to_z(gravity),
+        six_orientation: session.six_orientation,
+        calibrated_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    })
+}
+
+/// Matriz de rotación que lleva la dirección `v` al eje +Z (Rodrigues)
+fn rotation_to_z(v: [f32; 3]) -> [[f32; 3]; 3] {
+    let n = norm(v);
+    let u = [v[0] / n, v[1] / n, v[2] / n];
+
+    // Eje de giro k = u x z, cos = u · z
+    let k = [u[1], -u[0], 0.0];
+    let sin = norm(k);
+    let cos = u[2];
+
+    if sin < 1e-6 {
+        return if cos > 0.0 {
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        } else {
+            [[1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, -1.0]] // Banda invertida
+        };
+    }
+
+    let skew = [[0.0, -k[2], k[1]], [k[2], 0.0, -k[0]], [-k[1], k[0], 0.0]];
+    let factor = (1.0 - cos) / (sin * sin);
+
+    let mut rotation = [[0.0; 3]; 3];
+    for row in 0..3 {
+        for col in 0..3 {
+            let identity = if row == col { 1.0 } else { 0.0 };
+            let skew_squared: f32 = (0..3).map(|i| skew[row][i] * skew[i][col]).sum();
+            rotation[row][col] = identity + skew[row][col] + skew_squared * factor;
+        }
+    }
+    rotation
+}
+
+fn rotate(matrix: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    [
+        matrix[0][0] * v[0] + matrix[0][1] * v[1] + matrix[0][2] * v[2],
+        matrix[1][0] * v[0] + matrix[1][1] * v[1] + matrix[1][2] * v[2],
+        matrix[2][0] * v[0] + matrix[2][1] * v[1] + matrix[2][2] * v[2],
+    ]
+}
+
+fn norm(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+fn emit_status<R: tauri::Runtime>(app_handle: &AppHandle<R>, status: &CalibrationStatus) {
+    if let Err(e) = app_handle.emit("calibration-progress", status) {
+        error!(error = %e, "Error emitiendo progreso de calibración");
+    }
+}
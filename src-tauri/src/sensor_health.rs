@@ -0,0 +1,358 @@
+// Diagnóstico de salud de cada banda a partir del stream de notificaciones
+// Frecuencia real, huecos, paquetes malformados, saturación, ejes congelados y tendencia de RSSI
+
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+use crate::packet_decoder::{self, PacketStats};
+use crate::simple_ble;
+
+pub const EXPECTED_SAMPLE_RATE_HZ: f32 = 200.0;
+const MONITOR_INTERVAL_MS: u64 = 2_000;
+// Ventana de cálculo de la frecuencia real y de la saturación
+const RATE_WINDOW_MS: u64 = 1_000;
+// Un intervalo entre muestras mayor que esto es un hueco
+const GAP_THRESHOLD_MS: u64 = 25;
+// Sin muestras durante este tiempo la banda se considera muda
+const NO_DATA_MS: u64 = 2_000;
+// Muestras idénticas consecutivas para considerar un eje congelado (0.5 s a 200Hz)
+const STUCK_SAMPLES: u32 = 100;
+// Valor crudo a partir del cual un eje se considera saturado (el sensor recorta antes del fondo de escala)
+pub const SATURATION_RAW: i16 = 32_000;
+// Lecturas de RSSI conservadas para la tendencia (1 min con el intervalo del monitor)
+const RSSI_HISTORY: usize = 30;
+
+// Umbrales de diagnóstico
+const MIN_RATE_RATIO: f32 = 0.9;
+const MAX_SATURATED_RATIO: f32 = 0.01;
+const MAX_MALFORMED_RATIO: f32 = 0.02;
+const MAX_LOSS_RATIO: f32 = 0.05;
+const WEAK_RSSI_DBM: i16 = -85;
+const RSSI_DROP_DBM_PER_MIN: f32 = -10.0;
+
+const AXIS_NAMES: [&str; 6] = ["acc_x", "acc_y", "acc_z", "gyro_x", "gyro_y", "gyro_z"];
+
+/// Problema detectado en una banda
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum HealthIssue {
+    NoData { silent_ms: u64 },
+    LowSampleRate { sample_rate_hz: f32 },
+    Gaps { count: u64, longest_ms: u64 },
+    MalformedPackets { ratio: f32 },
+    PacketLoss { ratio: f32 },
+    Saturated { axes: Vec<String> },
+    Stuck { axes: Vec<String> },
+    WeakSignal { rssi: i16 },
+    SignalDropping { dbm_per_min: f32 },
+}
+
+impl HealthIssue {
+    // Problemas que impiden usar la banda en un combate
+    fn is_critical(&self) -> bool {
+        matches!(self, HealthIssue::NoData { .. } | HealthIssue::Stuck { .. } | HealthIssue::LowSampleRate { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// Informe de salud de una banda
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeviceHealthReport {
+    pub device_id: String,
+    pub status: HealthStatus,
+    pub issues: Vec<HealthIssue>,
+    pub sample_rate_hz: f32,
+    pub expected_rate_hz: f32,
+    pub gaps: u64,                 // Desde el informe anterior
+    pub longest_gap_ms: u64,
+    pub malformed_packets: u64,    // Desde el informe anterior
+    pub lost_packets: u64,
+    pub saturated_ratio: [f32; 6], // Por eje (acc xyz, gyro xyz)
+    pub stuck_axes: Vec<String>,
+    pub rssi: Option<i16>,
+    pub rssi_trend_dbm_per_min: Option<f32>,
+    pub last_sample_at: Option<u64>,
+    pub checked_at: u64,
+}
+
+// Contadores de una banda; la conexión guarda el Arc para no buscar en el mapa a 200Hz
+#[derive(Debug, Default)]
+pub struct DeviceHealth {
+    monitored_since: u64,
+    last_sample_at: Option<u64>,
+    // Ventana de frecuencia en curso y la última completa
+    window_start: Option<u64>,
+    window_samples: u32,
+    window_saturated: [u32; 6],
+    sample_rate_hz: f32,
+    saturated_ratio: [f32; 6],
+    // Huecos desde el último informe
+    gaps: u64,
+    longest_gap_ms: u64,
+    // Ejes congelados
+    last_raw: Option<[i16; 6]>,
+    repeat_counts: [u32; 6],
+    // Totales de paquetes del informe anterior (para calcular diferencias)
+    last_packet_totals: Option<PacketStats>,
+    rssi_history: VecDeque<(u64, i16)>,
+}
+
+/// Indica si un valor crudo está en el límite del rango del sensor
+pub fn is_saturated(value: i16) -> bool {
+    value.unsigned_abs() >= SATURATION_RAW as u16
+}
+
+impl DeviceHealth {
+    /// Registra una muestra cruda con su hora host (ms)
+    /// CRÍTICO: se llama a 200Hz; solo contadores
+    pub fn record_sample(&mut self, timestamp: u64, raw: [i16; 6]) {
+        if let Some(last) = self.last_sample_at {
+            let interval = timestamp.saturating_sub(last);
+            if interval > GAP_THRESHOLD_MS {
+                self.gaps += 1;
+                self.longest_gap_ms = self.longest_gap_ms.max(interval);
+            }
+        }
+        self.last_sample_at = Some(timestamp);
+
+        let last_raw = self.last_raw.replace(raw);
+        for (axis, &value) in raw.iter().enumerate() {
+            if is_saturated(value) {
+                self.window_saturated[axis] += 1;
+            }
+            let repeated = last_raw.is_some_and(|last| last[axis] == value);
+            self.repeat_counts[axis] = if repeated { self.repeat_counts[axis].saturating_add(1) } else { 0 };
+        }
+
+        // Cerrar la ventana de frecuencia; un reloj que retrocede la reinicia
+        let window_start = *self.window_start.get_or_insert(timestamp);
+        self.window_samples += 1;
+        let elapsed = timestamp.saturating_sub(window_start);
+        if timestamp < window_start || elapsed >= RATE_WINDOW_MS {
+            if elapsed > 0 {
+                self.sample_rate_hz = self.window_samples as f32 * 1000.0 / elapsed as f32;
+                self.saturated_ratio = self.window_saturated.map(|count| count as f32 / self.window_samples as f32);
+            }
+            self.window_start = Some(timestamp);
+            self.window_samples = 0;
+            self.window_saturated = [0; 6];
+        }
+    }
+
+    fn record_rssi(&mut self, now: u64, rssi: i16) {
+        if self.rssi_history.len() == RSSI_HISTORY {
+            self.rssi_history.pop_front();
+        }
+        self.rssi_history.push_back((now, rssi));
+    }
+
+    // Pendiente por mínimos cuadrados en dBm/min
+    fn rssi_trend(&self) -> Option<f32> {
+        if self.rssi_history.len() < 3 {
+            return None;
+        }
+        let (t0, _) = *self.rssi_history.front()?;
+        let points: Vec<(f32, f32)> = self.rssi_history.iter()
+            .map(|&(time, rssi)| (time.saturating_sub(t0) as f32 / 60_000.0, rssi as f32))
+            .collect();
+        let count = points.len() as f32;
+        let mean_t = points.iter().map(|p| p.0).sum::<f32>() / count;
+        let mean_r = points.iter().map(|p| p.1).sum::<f32>() / count;
+        let covariance: f32 = points.iter().map(|p| (p.0 - mean_t) * (p.1 - mean_r)).sum();
+        let variance: f32 = points.iter().map(|p| (p.0 - mean_t).powi(2)).sum();
+        (variance > 0.0).then(|| covariance / variance)
+    }
+
+    /// Genera el informe y reinicia los contadores por intervalo
+    fn report(&mut self, device_id: &str, packet_stats: &PacketStats, now: u64) -> DeviceHealthReport {
+        let previous = self.last_packet_totals.replace(packet_stats.clone()).unwrap_or_default();
+        let received = packet_stats.received.saturating_sub(previous.received);
+        let malformed = (packet_stats.malformed + packet_stats.checksum_failures)
+            .saturating_sub(previous.malformed + previous.checksum_failures);
+        let lost = packet_stats.lost.saturating_sub(previous.lost);
+        let decoded = packet_stats.decoded.saturating_sub(previous.decoded);
+
+        let stuck_axes: Vec<String> = AXIS_NAMES.iter().zip(self.repeat_counts)
+            .filter(|&(_, count)| count >= STUCK_SAMPLES)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let saturated_axes: Vec<String> = AXIS_NAMES.iter().zip(self.saturated_ratio)
+            .filter(|&(_, ratio)| ratio > MAX_SATURATED_RATIO)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        let rssi = self.rssi_history.back().map(|&(_, rssi)| rssi);
+        let rssi_trend = self.rssi_trend();
+
+        let mut issues = Vec::new();
+        let silent_ms = now.saturating_sub(self.last_sample_at.unwrap_or(self.monitored_since));
+        if silent_ms >= NO_DATA_MS {
+            issues.push(HealthIssue::NoData { silent_ms });
+        } else if self.sample_rate_hz > 0.0 && self.sample_rate_hz < EXPECTED_SAMPLE_RATE_HZ * MIN_RATE_RATIO {
+            issues.push(HealthIssue::LowSampleRate { sample_rate_hz: self.sample_rate_hz });
+        }
+        if self.gaps > 0 {
+            issues.push(HealthIssue::Gaps { count: self.gaps, longest_ms: self.longest_gap_ms });
+        }
+        if received > 0 && malformed as f32 / received as f32 > MAX_MALFORMED_RATIO {
+            issues.push(HealthIssue::MalformedPackets { ratio: malformed as f32 / received as f32 });
+        }
+        if decoded + lost > 0 && lost as f32 / (decoded + lost) as f32 > MAX_LOSS_RATIO {
+            issues.push(HealthIssue::PacketLoss { ratio: lost as f32 / (decoded + lost) as f32 });
+        }
+        if !saturated_axes.is_empty() {
+            issues.push(HealthIssue::Saturated { axes: saturated_axes });
+        }
+        if !stuck_axes.is_empty() {
+            issues.push(HealthIssue::Stuck { axes: stuck_axes.clone() });
+        }
+        if let Some(rssi) = rssi.filter(|&rssi| rssi < WEAK_RSSI_DBM) {
+            issues.push(HealthIssue::WeakSignal { rssi });
+        }
+        if let Some(trend) = rssi_trend.filter(|&trend| trend < RSSI_DROP_DBM_PER_MIN) {
+            issues.push(HealthIssue::SignalDropping { dbm_per_min: trend });
+        }
+
+        let status = if issues.iter().any(HealthIssue::is_critical) {
+            HealthStatus::Unhealthy
+        } else if issues.is_empty() {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Degraded
+        };
+
+        let report = DeviceHealthReport {
+            device_id: device_id.to_string(),
+            status,
+            issues,
+            sample_rate_hz: self.sample_rate_hz,
+            expected_rate_hz: EXPECTED_SAMPLE_RATE_HZ,
+            gaps: self.gaps,
+            longest_gap_ms: self.longest_gap_ms,
+            malformed_packets: malformed,
+            lost_packets: lost,
+            saturated_ratio: self.saturated_ratio,
+            stuck_axes,
+            rssi,
+            rssi_trend_dbm_per_min: rssi_trend,
+            last_sample_at: self.last_sample_at,
+            checked_at: now,
+        };
+
+        self.gaps = 0;
+        self.longest_gap_ms = 0;
+        report
+    }
+}
+
+type DeviceHealthMap = HashMap<String, Arc<Mutex<DeviceHealth>>>;
+static DEVICE_HEALTH: Lazy<Arc<Mutex<DeviceHealthMap>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+// Último informe por dispositivo (consultado por el comando de diagnóstico)
+static LAST_REPORTS: Lazy<Mutex<HashMap<String, DeviceHealthReport>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static MONITOR_STARTED: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// Reinicia y devuelve los contadores de una conexión nueva
+pub fn monitor_device(device_id: &str) -> Arc<Mutex<DeviceHealth>> {
+    // Los totales de paquetes sobreviven a las reconexiones: partir de los actuales
+    let packet_totals = packet_decoder::stats_for_device(device_id).lock().unwrap().clone();
+    let health = Arc::new(Mutex::new(DeviceHealth {
+        monitored_since: now_ms(),
+        last_packet_totals: Some(packet_totals),
+        ..DeviceHealth::default()
+    }));
+    DEVICE_HEALTH.lock().unwrap().insert(device_id.to_string(), health.clone());
+    LAST_REPORTS.lock().unwrap().remove(device_id);
+    health
+}
+
+/// Deja de vigilar una banda desconectada
+pub fn forget_device(device_id: &str) {
+    DEVICE_HEALTH.lock().unwrap().remove(device_id);
+    LAST_REPORTS.lock().unwrap().remove(device_id);
+}
+
+// Calcula los informes de todas las bandas vigiladas
+fn check_devices() -> Vec<DeviceHealthReport> {
+    let now = now_ms();
+    let monitored: Vec<(String, Arc<Mutex<DeviceHealth>>)> = DEVICE_HEALTH.lock().unwrap()
+        .iter()
+        .map(|(device_id, health)| (device_id.clone(), health.clone()))
+        .collect();
+
+    let reports: Vec<DeviceHealthReport> = monitored.into_iter()
+        .map(|(device_id, health)| {
+            let packet_stats = packet_decoder::stats_for_device(&device_id).lock().unwrap().clone();
+            health.lock().unwrap().report(&device_id, &packet_stats, now)
+        })
+        .collect();
+
+    let mut last_reports = LAST_REPORTS.lock().unwrap();
+    for report in &reports {
+        last_reports.insert(report.device_id.clone(), report.clone());
+    }
+    reports
+}
+
+/// Diagnóstico actual de una banda o de todas
+pub fn get_health(device_id: Option<&str>) -> Vec<DeviceHealthReport> {
+    let reports = LAST_REPORTS.lock().unwrap();
+    reports.values()
+        .filter(|report| device_id.is_none_or(|id| report.device_id == id))
+        .cloned()
+        .collect()
+}
+
+/// Lanza la revisión periódica: lee RSSI, calcula informes y emite "sensor-health"
+pub fn start_health_monitor<R: tauri::Runtime>(app_handle: Arc<AppHandle<R>>) {
+    if MONITOR_STARTED.set(()).is_err() {
+        return;
+    }
+
+    info!(interval_ms = MONITOR_INTERVAL_MS, "🩺 Monitor de salud de sensores iniciado");
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_millis(MONITOR_INTERVAL_MS));
+        loop {
+            interval.tick().await;
+
+            for (device_id, device) in simple_ble::connected_device_handles() {
+                let Some(health) = DEVICE_HEALTH.lock().unwrap().get(&device_id).cloned() else {
+                    continue;
+                };
+                // No todas las plataformas exponen el RSSI de una conexión activa
+                if let Ok(rssi) = device.rssi().await {
+                    health.lock().unwrap().record_rssi(now_ms(), rssi);
+                }
+            }
+
+            let reports = check_devices();
+            if reports.is_empty() {
+                continue;
+            }
+            for report in reports.iter().filter(|report| report.status == HealthStatus::Unhealthy) {
+                warn!(device_id = %report.device_id, issues = ?report.issues, "⚠️ Banda con problemas de salud");
+            }
+            if let Err(e) = app_handle.emit("sensor-health", &reports) {
+                error!(error = %e, "Error emitiendo salud de sensores");
+            }
+        }
+    });
+}
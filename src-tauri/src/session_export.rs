@@ -0,0 +1,570 @@
+// Exportación de sesiones para análisis: golpes, estadísticas por asalto y muestras IMU
+// Cada tabla se escribe en CSV y JSON Lines (y Parquet con la feature parquet-export),
+// junto con metadata.json: competidores, reglas y configuración de detección
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+use crate::force_model;
+use crate::imu_recording::{self, RecordedSample};
+use crate::roster::{self, RosterEntry};
+use crate::scoring::{self, CombatSession, EntryKind, SessionStatus};
+use crate::signal_filters;
+use crate::simple_ble::SimpleDetectionConfig;
+use crate::storage;
+use crate::strike_classifier;
+use crate::tournament;
+use crate::velocity::VelocityMethod;
+
+/// Formato de los archivos exportados
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// Resultado de una exportación
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ExportSummary {
+    pub session_id: String,
+    pub directory: String,
+    pub files: Vec<String>,
+    pub events: usize,
+    pub rounds: usize,
+    pub imu_samples: usize,
+}
+
+/// Golpe del registro (campos y unidades de SimpleCombatEvent)
+#[derive(Debug, Clone, serde::Serialize)]
+struct EventRow {
+    entry_id: u64,
+    round: u32,
+    fighter_id: String,
+    roster_id: Option<String>,
+    competitor_name: String,
+    event_type: String,
+    limb_name: String,
+    velocity: Option<f32>,
+    velocity_method: VelocityMethod,
+    acceleration: Option<f32>,
+    force: Option<f32>,
+    force_model: String,
+    force_model_parameters: String, // JSON
+    timestamp: u64,
+    device_timestamp: Option<u64>,
+    received_at: u64,
+    duration_ms: u64,
+    confidence: f32,
+    points: i32,
+    manual: bool,
+    voided: bool,
+    rejected: Option<String>,
+}
+
+const EVENT_COLUMNS: &[&str] = &[
+    "entry_id", "round", "fighter_id", "roster_id", "competitor_name", "event_type", "limb_name",
+    "velocity", "velocity_method", "acceleration", "force", "force_model", "force_model_parameters",
+    "timestamp", "device_timestamp", "received_at", "duration_ms", "confidence",
+    "points", "manual", "voided", "rejected",
+];
+
+/// Estadísticas de un peleador en un asalto (sin golpes anulados)
+#[derive(Debug, Clone, serde::Serialize)]
+struct RoundRow {
+    round: u32,
+    fighter_id: String,
+    roster_id: Option<String>,
+    competitor_name: String,
+    started_at: u64,
+    ended_at: Option<u64>, // None = asalto en curso
+    points: i32,
+    strikes: u32,
+    scored_strikes: u32,
+    voided_strikes: u32,
+    manual_strikes: u32,
+    penalties: u32,
+    max_force: Option<f32>,
+    avg_force: Option<f32>,
+    max_velocity: Option<f32>,
+    max_acceleration: Option<f32>,
+    round_winner: Option<String>,
+}
+
+const ROUND_COLUMNS: &[&str] = &[
+    "round", "fighter_id", "roster_id", "competitor_name", "started_at", "ended_at",
+    "points", "strikes", "scored_strikes", "voided_strikes", "manual_strikes", "penalties",
+    "max_force", "avg_force", "max_velocity", "max_acceleration", "round_winner",
+];
+
+/// Muestra IMU grabada durante la sesión, un eje por columna
+#[derive(Debug, Clone, serde::Serialize)]
+struct ImuRow {
+    device_id: String,
+    timestamp: u64,
+    limb_id: u8,
+    raw_acc_x: i16,
+    raw_acc_y: i16,
+    raw_acc_z: i16,
+    raw_gyro_x: i16,
+    raw_gyro_y: i16,
+    raw_gyro_z: i16,
+    acc_x: f32,
+    acc_y: f32,
+    acc_z: f32,
+    gyro_x: f32,
+    gyro_y: f32,
+    gyro_z: f32,
+    filtered_acc_x: f32,
+    filtered_acc_y: f32,
+    filtered_acc_z: f32,
+    filtered_gyro_x: f32,
+    filtered_gyro_y: f32,
+    filtered_gyro_z: f32,
+    linear_acc_x: f32,
+    linear_acc_y: f32,
+    linear_acc_z: f32,
+    sensor_linear_acc_x: f32,
+    sensor_linear_acc_y: f32,
+    sensor_linear_acc_z: f32,
+}
+
+impl ImuRow {
+    fn new(device_id: &str, sample: &RecordedSample) -> Self {
+        let motion = &sample.motion;
+        Self {
+            device_id: device_id.to_string(),
+            timestamp: motion.timestamp,
+            limb_id: sample.limb_id,
+            raw_acc_x: sample.raw_acc[0],
+            raw_acc_y: sample.raw_acc[1],
+            raw_acc_z: sample.raw_acc[2],
+            raw_gyro_x: sample.raw_gyro[0],
+            raw_gyro_y: sample.raw_gyro[1],
+            raw_gyro_z: sample.raw_gyro[2],
+            acc_x: sample.acc[0],
+            acc_y: sample.acc[1],
+            acc_z: sample.acc[2],
+            gyro_x: sample.gyro[0],
+            gyro_y: sample.gyro[1],
+            gyro_z: sample.gyro[2],
+            filtered_acc_x: sample.filtered_acc[0],
+            filtered_acc_y: sample.filtered_acc[1],
+            filtered_acc_z: sample.filtered_acc[2],
+            filtered_gyro_x: sample.filtered_gyro[0],
+            filtered_gyro_y: sample.filtered_gyro[1],
+            filtered_gyro_z: sample.filtered_gyro[2],
+            linear_acc_x: motion.linear_acc[0],
+            linear_acc_y: motion.linear_acc[1],
+            linear_acc_z: motion.linear_acc[2],
+            sensor_linear_acc_x: motion.sensor_linear_acc[0],
+            sensor_linear_acc_y: motion.sensor_linear_acc[1],
+            sensor_linear_acc_z: motion.sensor_linear_acc[2],
+        }
+    }
+}
+
+const IMU_COLUMNS: &[&str] = &[
+    "device_id", "timestamp", "limb_id",
+    "raw_acc_x", "raw_acc_y", "raw_acc_z", "raw_gyro_x", "raw_gyro_y", "raw_gyro_z",
+    "acc_x", "acc_y", "acc_z", "gyro_x", "gyro_y", "gyro_z",
+    "filtered_acc_x", "filtered_acc_y", "filtered_acc_z", "filtered_gyro_x", "filtered_gyro_y", "filtered_gyro_z",
+    "linear_acc_x", "linear_acc_y", "linear_acc_z", "sensor_linear_acc_x", "sensor_linear_acc_y", "sensor_linear_acc_z",
+];
+
+const PARQUET_DISABLED: &str = "Exportación Parquet no disponible (compilar con la feature parquet-export)";
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+// Unidades de las columnas exportadas (iguales en todos los formatos)
+fn units() -> serde_json::Value {
+    serde_json::json!({
+        "timestamp": "ms (hora host, época Unix)",
+        "received_at": "ms (hora host, época Unix)",
+        "started_at": "ms (hora host, época Unix)",
+        "ended_at": "ms (hora host, época Unix)",
+        "device_timestamp": "µs (reloj de la banda)",
+        "duration_ms": "ms",
+        "velocity": "m/s",
+        "acceleration": "m/s²",
+        "force": "N",
+        "max_velocity": "m/s",
+        "max_acceleration": "m/s²",
+        "max_force": "N",
+        "avg_force": "N",
+        "confidence": "0.0 - 1.0",
+        // Columnas IMU por eje (_x, _y, _z)
+        "raw_acc": "cuentas del sensor",
+        "raw_gyro": "cuentas del sensor",
+        "acc": "g",
+        "filtered_acc": "g",
+        "linear_acc": "g (marco mundial, sin gravedad)",
+        "sensor_linear_acc": "g (marco de la banda, sin gravedad)",
+        "gyro": "°/s",
+        "filtered_gyro": "°/s"
+    })
+}
+
+// Competidor de la plantilla de cada peleador según la sesión
+// (las sesiones guardadas antes de registrarlo usan la pelea del cuadro, si la hay)
+fn fighter_roster(session: &CombatSession) -> BTreeMap<String, RosterEntry> {
+    let roster_ids = if session.roster_ids.is_empty() {
+        tournament::session_competitors(&session.id)
+    } else {
+        session.roster_ids.clone()
+    };
+    roster_ids.iter()
+        .filter_map(|(fighter_id, roster_id)| Some((fighter_id.clone(), roster::get_competitor(roster_id)?)))
+        .collect()
+}
+
+fn event_rows(session: &CombatSession, roster_ids: &BTreeMap<String, String>) -> Vec<EventRow> {
+    session.event_log.iter()
+        .filter_map(|entry| {
+            let EntryKind::Strike { event, rejected, .. } = &entry.kind else {
+                return None;
+            };
+            Some(EventRow {
+                entry_id: entry.id,
+                round: entry.round,
+                fighter_id: entry.fighter_id.clone(),
+                roster_id: roster_ids.get(&entry.fighter_id).cloned(),
+                competitor_name: event.competitor_name.clone(),
+                event_type: event.event_type.clone(),
+                limb_name: event.limb_name.clone(),
+                velocity: event.velocity,
+                velocity_method: event.velocity_method,
+                acceleration: event.acceleration,
+                force: event.force,
+                force_model: event.force_model.name.clone(),
+                force_model_parameters: event.force_model.parameters.to_string(),
+                timestamp: event.timestamp,
+                device_timestamp: event.device_timestamp,
+                received_at: event.received_at,
+                duration_ms: event.duration_ms,
+                confidence: event.confidence,
+                points: entry.points,
+                manual: entry.manual,
+                voided: entry.voided,
+                rejected: rejected.clone(),
+            })
+        })
+        .collect()
+}
+
+fn round_rows(session: &CombatSession, roster_ids: &BTreeMap<String, String>) -> Vec<RoundRow> {
+    // Asaltos terminados y, si la sesión sigue, el asalto en curso
+    let mut rounds: Vec<(u32, u64, Option<u64>, Option<String>)> = session.rounds.iter()
+        .map(|round| (round.round, round.started_at, Some(round.ended_at), round.winner.clone()))
+        .collect();
+    if session.status == SessionStatus::InProgress && !session.rounds.iter().any(|round| round.round == session.current_round) {
+        rounds.push((session.current_round, session.round_started_at, None, None));
+    }
+
+    let mut rows = Vec::new();
+    for (round, started_at, ended_at, round_winner) in rounds {
+        let scores = session.scores(Some(round));
+        for (fighter_id, competitor_name) in &session.fighters {
+            let entries: Vec<_> = session.event_log.iter()
+                .filter(|entry| entry.round == round && &entry.fighter_id == fighter_id)
+                .collect();
+            let strikes: Vec<_> = entries.iter()
+                .filter(|entry| !entry.voided)
+                .filter_map(|entry| match &entry.kind {
+                    EntryKind::Strike { event, rejected, .. } => Some((event, rejected.is_none())),
+                    EntryKind::Penalty { .. } => None,
+                })
+                .collect();
+            let forces: Vec<f32> = strikes.iter().filter_map(|(event, _)| event.force).collect();
+            let max = |values: &mut dyn Iterator<Item = f32>| values.reduce(f32::max);
+
+            rows.push(RoundRow {
+                round,
+                fighter_id: fighter_id.clone(),
+                roster_id: roster_ids.get(fighter_id).cloned(),
+                competitor_name: competitor_name.clone(),
+                started_at,
+                ended_at,
+                points: scores.get(fighter_id).copied().unwrap_or(0),
+                strikes: strikes.len() as u32,
+                scored_strikes: strikes.iter().filter(|(_, scored)| *scored).count() as u32,
+                voided_strikes: entries.iter().filter(|entry| entry.voided && matches!(entry.kind, EntryKind::Strike { .. })).count() as u32,
+                manual_strikes: entries.iter().filter(|entry| !entry.voided && entry.manual).count() as u32,
+                penalties: entries.iter().filter(|entry| !entry.voided && matches!(entry.kind, EntryKind::Penalty { .. })).count() as u32,
+                max_force: max(&mut forces.iter().copied()),
+                avg_force: (!forces.is_empty()).then(|| forces.iter().sum::<f32>() / forces.len() as f32),
+                max_velocity: max(&mut strikes.iter().filter_map(|(event, _)| event.velocity)),
+                max_acceleration: max(&mut strikes.iter().filter_map(|(event, _)| event.acceleration)),
+                round_winner: round_winner.clone(),
+            });
+        }
+    }
+    rows
+}
+
+// Muestras de las grabaciones IMU que caen dentro de la sesión, con un resumen por grabación
+fn imu_rows<R: tauri::Runtime>(app_handle: &AppHandle<R>, session: &CombatSession) -> Result<(Vec<ImuRow>, Vec<serde_json::Value>), String> {
+    let window = session.started_at..=session.ended_at.unwrap_or(u64::MAX);
+    let mut rows = Vec::new();
+    let mut recordings = Vec::new();
+    for file_name in imu_recording::list_recordings(app_handle)? {
+        let path = imu_recording::recording_path(app_handle, &file_name)?;
+        // Sin cargar las grabaciones que no se solapan con la sesión
+        match imu_recording::recording_span(&path) {
+            Ok((started_at, ended_at)) if ended_at < *window.start() || started_at > *window.end() => continue,
+            Ok(_) => {}
+            Err(e) => {
+                warn!(file_name = %file_name, error = %e, "⚠️ Grabación omitida en la exportación");
+                continue;
+            }
+        }
+        let recording = match imu_recording::load_recording(&path) {
+            Ok(recording) => recording,
+            Err(e) => {
+                warn!(file_name = %file_name, error = %e, "⚠️ Grabación omitida en la exportación");
+                continue;
+            }
+        };
+
+        let samples: Vec<ImuRow> = recording.samples.iter()
+            .filter(|sample| window.contains(&sample.motion.timestamp))
+            .map(|sample| ImuRow::new(&recording.device_id, sample))
+            .collect();
+        if samples.is_empty() {
+            continue;
+        }
+        let labels: Vec<_> = recording.labels.iter()
+            .filter(|(timestamp, _)| window.contains(timestamp))
+            .map(|(timestamp, strike_type)| serde_json::json!({ "timestamp": timestamp, "strike_type": strike_type }))
+            .collect();
+        recordings.push(serde_json::json!({
+            "file_name": file_name,
+            "device_id": recording.device_id,
+            "samples": samples.len(),
+            "labels": labels
+        }));
+        rows.extend(samples);
+    }
+    Ok((rows, recordings))
+}
+
+// Valor de una celda CSV (texto entre comillas si hace falta)
+fn csv_cell(value: Option<&serde_json::Value>) -> String {
+    let text = match value {
+        None | Some(serde_json::Value::Null) => return String::new(),
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(value) => value.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn write_csv<T: Serialize>(path: &Path, columns: &[&str], rows: &[T]) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|e| format!("Error creando {}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    let mut write = |line: String| writeln!(writer, "{}", line)
+        .map_err(|e| format!("Error escribiendo {}: {}", path.display(), e));
+
+    write(columns.join(","))?;
+    for row in rows {
+        let value = serde_json::to_value(row)
+            .map_err(|e| format!("Error serializando fila: {}", e))?;
+        let cells: Vec<String> = columns.iter().map(|column| csv_cell(value.get(*column))).collect();
+        write(cells.join(","))?;
+    }
+    writer.flush().map_err(|e| format!("Error escribiendo {}: {}", path.display(), e))
+}
+
+fn write_jsonl<T: Serialize>(path: &Path, rows: &[T]) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|e| format!("Error creando {}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    for row in rows {
+        serde_json::to_writer(&mut writer, row)
+            .map_err(|e| format!("Error serializando fila: {}", e))?;
+        writer.write_all(b"\n")
+            .map_err(|e| format!("Error escribiendo {}: {}", path.display(), e))?;
+    }
+    writer.flush().map_err(|e| format!("Error escribiendo {}: {}", path.display(), e))
+}
+
+#[cfg(feature = "parquet-export")]
+fn write_parquet<T: Serialize>(path: &Path, columns: &[&str], rows: &[T]) -> Result<(), String> {
+    use arrow_schema::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    let values = rows.iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Error serializando fila: {}", e))?;
+    let inferred = arrow_json::reader::infer_json_schema_from_iterator(values.iter().map(Ok))
+        .map_err(|e| format!("Error infiriendo esquema Parquet: {}", e))?;
+    // Columnas en el mismo orden que el CSV (columnas siempre vacías como nulas)
+    let fields: Vec<Field> = columns.iter()
+        .map(|column| inferred.field_with_name(column).cloned().unwrap_or_else(|_| Field::new(*column, DataType::Null, true)))
+        .collect();
+    let schema = Arc::new(Schema::new(fields));
+
+    let mut decoder = arrow_json::ReaderBuilder::new(schema.clone())
+        .build_decoder()
+        .map_err(|e| format!("Error preparando Parquet: {}", e))?;
+    decoder.serialize(&values).map_err(|e| format!("Error convirtiendo filas a Parquet: {}", e))?;
+
+    let file = File::create(path)
+        .map_err(|e| format!("Error creando {}: {}", path.display(), e))?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(file, schema, None)
+        .map_err(|e| format!("Error creando {}: {}", path.display(), e))?;
+    if let Some(batch) = decoder.flush().map_err(|e| format!("Error convirtiendo filas a Parquet: {}", e))? {
+        writer.write(&batch).map_err(|e| format!("Error escribiendo {}: {}", path.display(), e))?;
+    }
+    writer.close().map_err(|e| format!("Error cerrando {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet-export"))]
+fn write_parquet<T: Serialize>(_path: &Path, _columns: &[&str], _rows: &[T]) -> Result<(), String> {
+    Err(PARQUET_DISABLED.to_string())
+}
+
+// Escribe una tabla en cada formato pedido y devuelve los nombres de archivo
+fn write_table<T: Serialize>(
+    dir: &Path,
+    name: &str,
+    columns: &[&str],
+    rows: &[T],
+    formats: &[ExportFormat],
+) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    for format in formats {
+        let file_name = format!("{}.{}", name, format.extension());
+        let path = dir.join(&file_name);
+        match format {
+            ExportFormat::Csv => write_csv(&path, columns, rows)?,
+            ExportFormat::Jsonl => write_jsonl(&path, rows)?,
+            ExportFormat::Parquet => write_parquet(&path, columns, rows)?,
+        }
+        files.push(file_name);
+    }
+    Ok(files)
+}
+
+/// Exporta una sesión (la actual con None) a <directorio>/<id de sesión>/
+/// Sin formatos se exporta CSV y JSON Lines
+pub fn export_session<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    session_id: Option<&str>,
+    directory: &str,
+    formats: &[ExportFormat],
+) -> Result<ExportSummary, String> {
+    let formats = if formats.is_empty() { &[ExportFormat::Csv, ExportFormat::Jsonl][..] } else { formats };
+    if formats.contains(&ExportFormat::Parquet) && cfg!(not(feature = "parquet-export")) {
+        return Err(PARQUET_DISABLED.to_string());
+    }
+
+    // La sesión en curso se toma de memoria; las demás, del archivo guardado
+    let current = scoring::current_session();
+    let session = match session_id {
+        None => current.ok_or_else(|| "No hay sesión de combate".to_string())?,
+        Some(id) => match current.filter(|session| session.id == id) {
+            Some(session) => session,
+            None => scoring::load_session(app_handle, id)?,
+        },
+    };
+
+    let dir: PathBuf = Path::new(directory).join(&session.id);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Error creando directorio {}: {}", dir.display(), e))?;
+
+    let fighters = fighter_roster(&session);
+    let roster_ids: BTreeMap<String, String> = fighters.iter()
+        .map(|(fighter_id, entry)| (fighter_id.clone(), entry.competitor.id.clone()))
+        .collect();
+    let events = event_rows(&session, &roster_ids);
+    let rounds = round_rows(&session, &roster_ids);
+    let (imu_samples, recordings) = imu_rows(app_handle, &session)?;
+
+    let mut files = write_table(&dir, "events", EVENT_COLUMNS, &events, formats)?;
+    files.extend(write_table(&dir, "rounds", ROUND_COLUMNS, &rounds, formats)?);
+    files.extend(write_table(&dir, "imu_samples", IMU_COLUMNS, &imu_samples, formats)?);
+
+    // Configuración de detección vigente al exportar (cada golpe guarda su propio modelo de fuerza)
+    let devices: Vec<String> = recordings.iter()
+        .filter_map(|recording| recording["device_id"].as_str().map(str::to_string))
+        .collect();
+    let device_filters: BTreeMap<String, signal_filters::FilterChainConfig> = devices.iter()
+        .filter_map(|device_id| Some((device_id.clone(), signal_filters::device_filter_config(device_id)?)))
+        .collect();
+    let competitors: Vec<_> = session.fighters.iter()
+        .map(|(fighter_id, name)| serde_json::json!({
+            "fighter_id": fighter_id,
+            "competitor_name": name,
+            "roster": fighters.get(fighter_id)
+        }))
+        .collect();
+    let metadata = serde_json::json!({
+        "exported_at": now_ms(),
+        "session": {
+            "id": session.id,
+            "started_at": session.started_at,
+            "ended_at": session.ended_at,
+            "status": session.status,
+            "rounds": session.rounds.len(),
+            "winner": session.winner,
+            "rule_set": session.rule_set
+        },
+        "competitors": competitors,
+        "detection": {
+            "config": SimpleDetectionConfig::default(),
+            "device_filters": device_filters,
+            "classifier": strike_classifier::current_config(),
+            "force_model": force_model::current_force_model()
+        },
+        "recordings": recordings,
+        "units": units(),
+        "files": files
+    });
+    storage::save_json_at(&dir.join("metadata.json"), &metadata)?;
+    files.push("metadata.json".to_string());
+
+    info!(
+        session_id = %session.id,
+        directory = %dir.display(),
+        events = events.len(),
+        rounds = rounds.len(),
+        imu_samples = imu_samples.len(),
+        "📦 Sesión exportada"
+    );
+    Ok(ExportSummary {
+        session_id: session.id,
+        directory: dir.display().to_string(),
+        files,
+        events: events.len(),
+        rounds: rounds.len(),
+        imu_samples: imu_samples.len(),
+    })
+}
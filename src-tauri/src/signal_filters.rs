@@ -0,0 +1,261 @@
+// Cadena de filtros por eje antes de la detección
+// Mediana (rechazo de picos) -> Butterworth pasa-bajos en acc/gyro; pasa-altos sobre la aceleración lineal
+
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::f32::consts::{FRAC_1_SQRT_2, PI};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use tauri::AppHandle;
+use tracing::{error, info};
+
+use crate::storage;
+
+const FILTER_CONFIGS_FILE: &str = "filter_configs.json";
+const MAX_MEDIAN_WINDOW: usize = 15;
+
+/// Parámetros de la cadena de filtros (None o 0 desactiva una etapa)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FilterChainConfig {
+    pub sample_rate_hz: f32,
+    pub median_window: usize,              // Muestras (impar); 0 o 1 desactiva
+    pub low_pass_cutoff_hz: Option<f32>,   // Butterworth 2º orden sobre acc y gyro
+    pub high_pass_cutoff_hz: Option<f32>,  // Butterworth 2º orden sobre la aceleración lineal (quita DC residual)
+}
+
+impl Default for FilterChainConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate_hz: 200.0,
+            median_window: 3,
+            low_pass_cutoff_hz: Some(25.0),
+            high_pass_cutoff_hz: Some(0.3),
+        }
+    }
+}
+
+impl FilterChainConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sample_rate_hz <= 0.0 {
+            return Err("La frecuencia de muestreo debe ser positiva".to_string());
+        }
+        if self.median_window > MAX_MEDIAN_WINDOW {
+            return Err(format!("La ventana de mediana no puede superar {} muestras", MAX_MEDIAN_WINDOW));
+        }
+
+        let nyquist = self.sample_rate_hz / 2.0;
+        for (name, cutoff) in [("pasa-bajos", self.low_pass_cutoff_hz), ("pasa-altos", self.high_pass_cutoff_hz)] {
+            if let Some(cutoff) = cutoff {
+                if cutoff <= 0.0 || cutoff >= nyquist {
+                    return Err(format!("Frecuencia de corte {} fuera de rango (0, {} Hz)", name, nyquist));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Sección bicuadrática (forma directa II transpuesta)
+#[derive(Debug, Clone)]
+struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+    dc_gain: f32,
+    z: [f32; 2],
+    primed: bool,
+}
+
+impl Biquad {
+    // Coeficientes Butterworth de 2º orden (Q = 1/√2)
+    fn butterworth(cutoff_hz: f32, sample_rate_hz: f32, high_pass: bool) -> Self {
+        let w0 = 2.0 * PI * cutoff_hz / sample_rate_hz;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * FRAC_1_SQRT_2);
+        let a0 = 1.0 + alpha;
+
+        let b = if high_pass {
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0]
+        } else {
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0]
+        };
+
+        Self {
+            b: b.map(|coefficient| coefficient / a0),
+            a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+            dc_gain: if high_pass { 0.0 } else { 1.0 },
+            z: [0.0; 2],
+            primed: false,
+        }
+    }
+
+    fn process(&mut self, x: f32) -> f32 {
+        // Arrancar en régimen permanente para evitar el transitorio del primer valor
+        if !self.primed {
+            let y = self.dc_gain * x;
+            self.z = [y - self.b[0] * x, self.b[2] * x - self.a[1] * y];
+            self.primed = true;
+        }
+
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+// Mediana móvil para rechazar picos aislados
+#[derive(Debug, Clone)]
+struct MedianFilter {
+    window: usize,
+    values: VecDeque<f32>,
+}
+
+impl MedianFilter {
+    fn process(&mut self, x: f32) -> f32 {
+        if self.values.len() == self.window {
+            self.values.pop_front();
+        }
+        self.values.push_back(x);
+
+        let mut sorted: Vec<f32> = self.values.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        sorted[sorted.len() / 2]
+    }
+}
+
+// Etapas aplicadas a un eje
+#[derive(Debug, Clone)]
+struct AxisChain {
+    median: Option<MedianFilter>,
+    low_pass: Option<Biquad>,
+}
+
+impl AxisChain {
+    fn new(config: &FilterChainConfig) -> Self {
+        Self {
+            median: (config.median_window > 1).then(|| MedianFilter {
+                window: config.median_window,
+                values: VecDeque::with_capacity(config.median_window),
+            }),
+            low_pass: config.low_pass_cutoff_hz
+                .map(|cutoff| Biquad::butterworth(cutoff, config.sample_rate_hz, false)),
+        }
+    }
+
+    fn process(&mut self, mut x: f32) -> f32 {
+        if let Some(median) = &mut self.median {
+            x = median.process(x);
+        }
+        if let Some(low_pass) = &mut self.low_pass {
+            x = low_pass.process(x);
+        }
+        x
+    }
+}
+
+/// Estado de filtros de una conexión
+#[derive(Debug, Clone)]
+pub struct SignalFilters {
+    config: FilterChainConfig,
+    acc: [AxisChain; 3],
+    gyro: [AxisChain; 3],
+    linear_high_pass: Option<[Biquad; 3]>,
+}
+
+impl SignalFilters {
+    pub fn new(config: &FilterChainConfig) -> Self {
+        let chain = || AxisChain::new(config);
+        Self {
+            config: config.clone(),
+            acc: [chain(), chain(), chain()],
+            gyro: [chain(), chain(), chain()],
+            linear_high_pass: config.high_pass_cutoff_hz.map(|cutoff| {
+                let high_pass = || Biquad::butterworth(cutoff, config.sample_rate_hz, true);
+                [high_pass(), high_pass(), high_pass()]
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &FilterChainConfig {
+        &self.config
+    }
+
+    /// Filtra acc (g) y gyro (°/s) por eje antes de estimar la orientación
+    pub fn filter_motion(&mut self, acc: [f32; 3], gyro: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+        let mut filtered_acc = [0.0; 3];
+        let mut filtered_gyro = [0.0; 3];
+        for axis in 0..3 {
+            filtered_acc[axis] = self.acc[axis].process(acc[axis]);
+            filtered_gyro[axis] = self.gyro[axis].process(gyro[axis]);
+        }
+        (filtered_acc, filtered_gyro)
+    }
+
+    /// Elimina el DC residual de la aceleración lineal
+    pub fn filter_linear_acc(&mut self, linear_acc: [f32; 3]) -> [f32; 3] {
+        match &mut self.linear_high_pass {
+            Some(filters) => [
+                filters[0].process(linear_acc[0]),
+                filters[1].process(linear_acc[1]),
+                filters[2].process(linear_acc[2]),
+            ],
+            None => linear_acc,
+        }
+    }
+}
+
+// Configuración específica por dispositivo (sin entrada = la de SimpleDetectionConfig)
+static DEVICE_FILTER_CONFIGS: Lazy<RwLock<HashMap<String, FilterChainConfig>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+// Incrementa con cada cambio para que las conexiones reconstruyan sus filtros
+static FILTER_CONFIG_REVISION: AtomicU64 = AtomicU64::new(0);
+
+pub fn filter_config_revision() -> u64 {
+    FILTER_CONFIG_REVISION.load(Ordering::Relaxed)
+}
+
+/// Configuración específica del dispositivo, si la tiene
+pub fn device_filter_config(device_id: &str) -> Option<FilterChainConfig> {
+    DEVICE_FILTER_CONFIGS.read().unwrap().get(device_id).cloned()
+}
+
+/// Guarda (o elimina con None) la configuración de un dispositivo
+pub fn set_device_filter_config<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    device_id: &str,
+    config: Option<FilterChainConfig>,
+) -> Result<(), String> {
+    {
+        let mut configs = DEVICE_FILTER_CONFIGS.write().unwrap();
+        match config {
+            Some(config) => {
+                config.validate()?;
+                configs.insert(device_id.to_string(), config);
+            }
+            None => {
+                configs.remove(device_id);
+            }
+        }
+    }
+    FILTER_CONFIG_REVISION.fetch_add(1, Ordering::Relaxed);
+
+    let configs = DEVICE_FILTER_CONFIGS.read().unwrap().clone();
+    storage::save_json(app_handle, FILTER_CONFIGS_FILE, &configs)
+}
+
+/// Carga las configuraciones guardadas al iniciar la aplicación
+pub fn load_filter_configs<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(configs) = storage::load_json::<HashMap<String, FilterChainConfig>, R>(app_handle, FILTER_CONFIGS_FILE) else {
+        return;
+    };
+
+    let (valid, invalid): (HashMap<_, _>, HashMap<_, _>) = configs.into_iter()
+        .partition(|(_, config)| config.validate().is_ok());
+    for device_id in invalid.keys() {
+        error!(device_id = %device_id, "❌ Configuración de filtros guardada inválida, se ignora");
+    }
+
+    info!(devices = valid.len(), "🎛️ Configuraciones de filtros cargadas");
+    *DEVICE_FILTER_CONFIGS.write().unwrap() = valid;
+    FILTER_CONFIG_REVISION.fetch_add(1, Ordering::Relaxed);
+}
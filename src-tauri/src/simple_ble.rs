@@ -1,16 +1,34 @@
 // Implementación ultra-simplificada para detección de bofetadas y patadas BLE
 // Con selección manual de dispositivos desde el frontend
 
-use bluest::{Adapter, Device, Characteristic};
+use bluest::{Adapter, AdvertisingDevice, Device, Characteristic};
 use futures::{StreamExt, Stream};
 use std::time::Duration;
 use std::sync::{Arc, Mutex, OnceLock};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tauri::{AppHandle, Emitter};
 use tracing::{info, error, debug, warn, instrument};
 use tokio::task::JoinHandle;
 use once_cell::sync::Lazy;
 use crate::broadcast_ws::ws_broadcast;
+use crate::gatt_profile;
+use crate::device_info::{self, DeviceInformation};
+use crate::firmware_ota;
+use crate::clock_sync::{host_time_us, ClockSyncEstimator};
+use crate::packet_decoder::{self, DecodedSample, PacketStats};
+use crate::sensor_calibration::{self, DeviceCalibration};
+use crate::orientation::OrientationFilter;
+use crate::velocity::{VelocityEstimator, VelocityMethod};
+use crate::force_model::{self, ForceInput, ForceModelRecord};
+use crate::strike_classifier::{self, MotionSample, StrikeWindow};
+use crate::imu_recording;
+use crate::imu_stream;
+use crate::limb_assignment;
+use crate::roster;
+use crate::scoring;
+use crate::sensor_health::{self, DeviceHealth};
+use crate::signal_filters::{self, FilterChainConfig, SignalFilters};
+use crate::strike_tracker::{RefractoryConfig, StrikeTracker};
 
 // Estructura para representar un dispositivo BLE encontrado
 #[derive(Clone, serde::Serialize, Debug)]
@@ -24,17 +42,30 @@ pub struct BleDevice {
     pub is_connectable: bool,
 }
 
+// Dispositivo conectado con la información leída de sus servicios estándar
+#[derive(Clone, serde::Serialize, Debug)]
+pub struct ConnectedDeviceDetails {
+    pub id: String,
+    pub label: String,                          // Nombre del dispositivo (y competidor si aplica)
+    pub information: Option<DeviceInformation>, // Batería y Device Information Service
+}
+
 // Estructura simple para eventos de combate
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SimpleCombatEvent {
-    pub event_type: String,        // "slap", "kick"
+    pub event_type: String,        // Clase configurada ("punch", "slap", "kickdown", ...) o "unknown"
     pub limb_name: String,         // "Mano Izquierda", "Pie Derecho", etc.
     pub fighter_id: String,        // ID del peleador (ej: "fighter_1", "fighter_2")
     pub competitor_name: String,   // Nombre del competidor
     pub velocity: Option<f32>,     // Velocidad en m/s
+    pub velocity_method: VelocityMethod, // Integración con reposo previo o estimación heurística
     pub acceleration: Option<f32>, // Aceleración en m/s²
     pub force: Option<f32>,        // Fuerza en Newtons
-    pub timestamp: u64,            // Timestamp del evento
+    pub force_model: ForceModelRecord, // Modelo y parámetros que produjeron la fuerza
+    pub timestamp: u64,            // Timestamp del evento (hora host estimada en ms)
+    pub device_timestamp: Option<u64>, // Hora de la banda en µs (si el paquete la incluye)
+    pub received_at: u64,          // Hora host de llegada de la notificación en ms
+    pub duration_ms: u64,          // Duración del golpe (inicio a vuelta bajo el umbral)
     pub confidence: f32,           // Confianza del evento (0.0 - 1.0)
 }
 
@@ -48,6 +79,79 @@ pub struct CompetitorMaxStats {
     pub max_acceleration: f32,
 }
 
+impl CompetitorMaxStats {
+    fn new(event: &SimpleCombatEvent) -> Self {
+        Self {
+            fighter_id: event.fighter_id.clone(),
+            competitor_name: event.competitor_name.clone(),
+            max_force: 0.0,
+            max_velocity: 0.0,
+            max_acceleration: 0.0,
+        }
+    }
+    
+    /// Aplica un evento y devuelve los récords que bate
+    pub(crate) fn register(&mut self, event: &SimpleCombatEvent) -> Vec<&'static str> {
+        let mut new_records = Vec::new();
+        if let Some(force) = event.force.filter(|&force| force > self.max_force) {
+            self.max_force = force;
+            new_records.push("force");
+        }
+        if let Some(velocity) = event.velocity.filter(|&velocity| velocity > self.max_velocity) {
+            self.max_velocity = velocity;
+            new_records.push("velocity");
+        }
+        if let Some(acceleration) = event.acceleration.filter(|&acceleration| acceleration > self.max_acceleration) {
+            self.max_acceleration = acceleration;
+            new_records.push("acceleration");
+        }
+        new_records
+    }
+}
+
+/// Récords que bate cada evento de un registro, en orden (los anulados no se incluyen)
+pub(crate) fn replay_max_stats<'a>(
+    events: impl IntoIterator<Item = &'a SimpleCombatEvent>,
+) -> (HashMap<String, CompetitorMaxStats>, Vec<Vec<&'static str>>) {
+    let mut stats_map: HashMap<String, CompetitorMaxStats> = HashMap::new();
+    let records = events.into_iter()
+        .map(|event| stats_map.entry(event.fighter_id.clone())
+            .or_insert_with(|| CompetitorMaxStats::new(event))
+            .register(event))
+        .collect();
+    (stats_map, records)
+}
+
+/// Sustituye las estadísticas de los peleadores de una sesión por las derivadas de su registro
+/// Los récords de otros peleadores se conservan
+pub(crate) fn store_max_stats(fighters: &[String], mut stats: HashMap<String, CompetitorMaxStats>) {
+    let mut stats_map = MAX_STATS_STORE.lock().unwrap();
+    for fighter_id in fighters {
+        match stats.remove(fighter_id) {
+            Some(fighter_stats) => stats_map.insert(fighter_id.clone(), fighter_stats),
+            None => stats_map.remove(fighter_id),
+        };
+    }
+}
+
+/// Igual que store_max_stats, avisando a los clientes tras una corrección o una sesión nueva
+pub(crate) fn replace_max_stats(fighters: &[String], stats: HashMap<String, CompetitorMaxStats>) {
+    let all_stats: Vec<CompetitorMaxStats> = fighters.iter()
+        .filter_map(|fighter_id| stats.get(fighter_id).cloned())
+        .collect();
+    store_max_stats(fighters, stats);
+    
+    ws_broadcast(&serde_json::json!({
+        "type": "max_stats_rebuilt",
+        "data": all_stats,
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+    }));
+    info!(fighters = all_stats.len(), "🔁 Estadísticas máximas recalculadas desde el registro");
+}
+
 // Store global para estadísticas máximas (usando fighter_id como clave)
 static MAX_STATS_STORE: Lazy<Arc<Mutex<HashMap<String, CompetitorMaxStats>>>> = 
     Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
@@ -63,11 +167,19 @@ pub struct ImuData {
     pub gyro_x: i16,
     pub gyro_y: i16,
     pub gyro_z: i16,
-    pub timestamp: u64,
+    pub acc: [f32; 3],                 // Aceleración calibrada y filtrada (g)
+    pub gyro: [f32; 3],                // Velocidad angular calibrada y filtrada (°/s)
+    pub unfiltered_acc: [f32; 3],      // Antes de la cadena de filtros (g)
+    pub unfiltered_gyro: [f32; 3],     // Antes de la cadena de filtros (°/s)
+    pub linear_acc: [f32; 3],          // Aceleración sin gravedad en marco mundial, Z arriba (g)
+    pub measured_velocity: Option<f32>, // Velocidad máxima integrada en la ventana del golpe (m/s)
+    pub timestamp: u64,                // Hora host estimada de la muestra (ms)
+    pub device_timestamp: Option<u64>, // Hora de la banda (µs, contador desenrollado)
+    pub received_at: u64,              // Hora host de llegada (ms)
 }
 
 // Tipos de extremidades
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum LimbType {
     LeftHand,
     RightHand,
@@ -86,6 +198,15 @@ impl LimbType {
         }
     }
 
+    pub fn id(&self) -> u8 {
+        match self {
+            LimbType::RightHand => 1,
+            LimbType::LeftHand => 2,
+            LimbType::RightFoot => 3,
+            LimbType::LeftFoot => 4,
+        }
+    }
+
     pub fn name(&self) -> &'static str {
         match self {
             LimbType::LeftHand => "Mano Izquierda",
@@ -106,30 +227,25 @@ impl LimbType {
 }
 
 // Configuración eficiente basada en datos reales BLE
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct SimpleDetectionConfig {
     // Factores de escala corregidos basados en datos reales
     pub acc_scale: f32,      // 1000.0 basado en datos reales
     pub gyro_scale: f32,     // 250.0 basado en datos reales
     
-    // Umbrales de detección ajustados
-    pub slap_min_acc: f32,       // 1.5g para bofetadas (manos)
-    pub slap_min_gyro: f32,      // 30°/s para bofetadas
-    pub kick_min_acc: f32,       // 2.0g para patadas (pies)
-    pub kick_max_gyro: f32,      // 20°/s máximo para patadas (movimiento más estable)
-    pub kick_min_acc_z: f32,     // -0.5g mínimo en Z para patadas hacia abajo
+    // Umbrales de disparo (la clase del golpe la decide strike_classifier)
+    pub hand_trigger_acc: f32,   // g de aceleración lineal para manos
+    pub foot_trigger_acc: f32,   // g de aceleración lineal para pies
     
     // Velocidades base realistas por extremidad
     pub hand_base_velocity: f32,  // 10.0 m/s para manos
     pub foot_base_velocity: f32,  // 15.0 m/s para pies
     
-    // Factores antropométricos científicos
-    pub hand_mass_percentage: f32, // 2.7% del peso corporal (Dempster 1955)
-    pub foot_mass_percentage: f32, // 6.2% del peso corporal (Dempster 1955)
-    pub joint_stiffness_factor: f32, // 1.8 factor de rigidez articular
+    // Lógica refractaria: cada golpe se sigue hasta que la señal vuelve a la línea base
+    pub refractory: RefractoryConfig,
     
-    // Sistema de cooldown para evitar eventos duplicados
-    pub cooldown_ms: u64, // Tiempo mínimo entre eventos en milisegundos
+    // Cadena de filtros por eje (cada dispositivo puede sobrescribirla)
+    pub filters: FilterChainConfig,
 }
 
 impl Default for SimpleDetectionConfig {
@@ -140,23 +256,18 @@ impl Default for SimpleDetectionConfig {
             gyro_scale: 250.0,
             
             // Umbrales ajustados basados en datos reales del dispositivo
-            slap_min_acc: 0.8,    // Era 1.5g - Reducido para detectar movimientos normales
-            slap_min_gyro: 3.0,   // Era 30°/s - Reducido drasticamente
-            kick_min_acc: 1.0,    // Era 2.0g - Reducido para pies
-            kick_max_gyro: 10.0,  // Era 20°/s - Reducido
-            kick_min_acc_z: -0.3, // Era -0.5g - Menos restrictivo
+            hand_trigger_acc: 0.8,
+            foot_trigger_acc: 1.0,
             
             // Velocidades base
             hand_base_velocity: 10.0,
             foot_base_velocity: 15.0,
             
-            // Antropometría
-            hand_mass_percentage: 0.027,
-            foot_mass_percentage: 0.062,
-            joint_stiffness_factor: 1.8,
+            // Seguimiento de golpes
+            refractory: RefractoryConfig::default(),
             
-            // Sistema de cooldown
-            cooldown_ms: 500, // 500ms entre eventos para evitar duplicados
+            // Filtros
+            filters: FilterChainConfig::default(),
         }
     }
 }
@@ -165,15 +276,16 @@ impl Default for SimpleDetectionConfig {
 #[derive(Debug, Clone)]
 pub struct CompetitorInfo {
     pub id: u8,
+    pub roster_id: String, // Competidor de la plantilla
     pub name: String,
-    pub weight: f32, // kg
+    pub weight: f32, // kg al conectar (si el competidor desaparece de la plantilla)
 }
 
 // Detector ultra-simple para sistema por turnos
 pub struct SimpleEventDetector {
     config: SimpleDetectionConfig,
     competitor_info: Option<CompetitorInfo>,
-    last_event_time: u64, // Timestamp del último evento detectado
+    tracker: StrikeTracker, // Reposo / en golpe / recuperación
 }
 
 impl SimpleEventDetector {
@@ -181,7 +293,7 @@ impl SimpleEventDetector {
         Self {
             config: SimpleDetectionConfig::default(),
             competitor_info: None,
-            last_event_time: 0, // Inicializar en 0
+            tracker: StrikeTracker::new(),
         }
     }
 
@@ -190,94 +302,81 @@ impl SimpleEventDetector {
         self.competitor_info = Some(info);
     }
 
-    pub fn detect_event(&mut self, data: &ImuData) -> Option<SimpleCombatEvent> {
+    pub fn config(&self) -> &SimpleDetectionConfig {
+        &self.config
+    }
+
+    pub fn detect_event(&mut self, data: &ImuData, window: &StrikeWindow) -> Option<SimpleCombatEvent> {
         let competitor = self.competitor_info.as_ref()?;
         let limb_type = LimbType::from_id(data.limb_id)?;
 
-        // Sistema de cooldown para evitar eventos duplicados
-        let current_time = data.timestamp;
-        if current_time - self.last_event_time < self.config.cooldown_ms {
-            return None; // Evento demasiado pronto, ignorar
-        }
-
-        // Convertir datos del sensor a unidades físicas
-        let acc_x = data.acc_x as f32 / self.config.acc_scale;
-        let acc_y = data.acc_y as f32 / self.config.acc_scale;
-        let acc_z = data.acc_z as f32 / self.config.acc_scale;
-        
-        let gyro_x = data.gyro_x as f32 / self.config.gyro_scale;
-        let gyro_y = data.gyro_y as f32 / self.config.gyro_scale;
-        let gyro_z = data.gyro_z as f32 / self.config.gyro_scale;
-
-        // Calcular magnitudes
+        // Aceleración lineal (sin gravedad, marco mundial)
+        let [acc_x, acc_y, acc_z] = data.linear_acc;
         let acc_magnitude = (acc_x * acc_x + acc_y * acc_y + acc_z * acc_z).sqrt();
-        let gyro_magnitude = (gyro_x * gyro_x + gyro_y * gyro_y + gyro_z * gyro_z).sqrt();
 
         // CRÍTICO: Sin logging aquí - Esta función se ejecuta a 200Hz
         // Logging eliminado para máximo rendimiento
 
-        // Detección específica por tipo de extremidad
-        let (event_type, confidence) = match limb_type {
-            LimbType::LeftHand | LimbType::RightHand => {
-                // Detección de bofetadas (manos)
-                if acc_magnitude >= self.config.slap_min_acc && gyro_magnitude >= self.config.slap_min_gyro {
-                    let confidence = ((acc_magnitude - self.config.slap_min_acc) / 2.0 + 
-                                    (gyro_magnitude - self.config.slap_min_gyro) / 50.0).min(1.0);
-                    ("slap", confidence)
-                } else {
-                    return None;
-                }
-            },
-            LimbType::LeftFoot | LimbType::RightFoot => {
-                // Detección de patadas hacia abajo (pies)
-                if acc_magnitude >= self.config.kick_min_acc && 
-                   acc_z < self.config.kick_min_acc_z && 
-                   gyro_magnitude <= self.config.kick_max_gyro {
-                    let confidence = ((acc_magnitude - self.config.kick_min_acc) / 3.0 + 
-                                    (self.config.kick_min_acc_z - acc_z) / 1.0).min(1.0);
-                    ("kickdown", confidence)
-                } else {
-                    return None;
-                }
-            }
+        // Disparo por extremidad; el evento se emite cuando el golpe termina
+        let trigger_acc = match limb_type {
+            LimbType::LeftHand | LimbType::RightHand => self.config.hand_trigger_acc,
+            LimbType::LeftFoot | LimbType::RightFoot => self.config.foot_trigger_acc,
         };
-
-        // Calcular velocidad realista basada en la extremidad y intensidad
-        let base_velocity = match limb_type {
-            LimbType::LeftHand | LimbType::RightHand => self.config.hand_base_velocity,
-            LimbType::LeftFoot | LimbType::RightFoot => self.config.foot_base_velocity,
+        let span = self.tracker.update(data.timestamp, acc_magnitude, trigger_acc, &self.config.refractory)?;
+
+        // Clasificar con la firma del golpe completo; movimientos dudosos quedan como "unknown"
+        let (classification, features) = strike_classifier::classify_strike(limb_type, window, &span)?;
+        let event_type = classification.class;
+        let confidence = classification.confidence;
+        let acc_magnitude = features.peak_acc.max(span.peak_acc);
+
+        // Velocidad medida por integración; si no es fiable, estimación por extremidad e intensidad
+        let (velocity, velocity_method) = match data.measured_velocity {
+            Some(measured) => (measured, VelocityMethod::IntegratedZupt),
+            None => {
+                let base_velocity = match limb_type {
+                    LimbType::LeftHand | LimbType::RightHand => self.config.hand_base_velocity,
+                    LimbType::LeftFoot | LimbType::RightFoot => self.config.foot_base_velocity,
+                };
+                
+                let intensity_factor = (acc_magnitude / 2.0).min(2.0); // Factor de intensidad entre 1.0 y 2.0
+                (base_velocity * intensity_factor, VelocityMethod::Heuristic)
+            }
         };
-        
-        let intensity_factor = (acc_magnitude / 2.0).min(2.0); // Factor de intensidad entre 1.0 y 2.0
-        let velocity = base_velocity * intensity_factor;
 
         // Calcular aceleración en m/s² (conversión de g a m/s²)
         let acceleration = acc_magnitude * 9.81;
 
-        // Calcular fuerza usando masa antropométrica
-        let limb_mass_percentage = match limb_type {
-            LimbType::LeftHand | LimbType::RightHand => self.config.hand_mass_percentage,
-            LimbType::LeftFoot | LimbType::RightFoot => self.config.foot_mass_percentage,
-        };
-        
-        let limb_mass = competitor.weight * limb_mass_percentage;
-        let force = limb_mass * acceleration * self.config.joint_stiffness_factor;
+        // Calcular fuerza con el modelo biomecánico activo
+        let (force, force_model) = force_model::estimate_force(&ForceInput {
+            roster_id: competitor.roster_id.clone(),
+            body_weight: roster::current_weight(&competitor.roster_id).unwrap_or(competitor.weight),
+            limb_type,
+            acceleration,
+            velocity,
+        });
 
-        info!(event_type = %event_type, velocity = velocity, acceleration = acceleration, force = force,
+        info!(event_type = %event_type, velocity = velocity, velocity_method = ?velocity_method, acceleration = acceleration,
+              force = force, force_model = %force_model.name, duration_ms = span.duration_ms(),
               "🥊 Evento de combate detectado");
 
-        // Actualizar timestamp del último evento para cooldown
-        self.last_event_time = current_time;
+        // El evento se fecha en el inicio del golpe
+        let elapsed_us = data.timestamp.saturating_sub(span.started_at) * 1000;
 
         Some(SimpleCombatEvent {
-            event_type: event_type.to_string(),
+            event_type,
             limb_name: limb_type.name().to_string(),
             fighter_id: format!("fighter_{}", competitor.id),
             competitor_name: competitor.name.clone(),
             velocity: Some(velocity),
+            velocity_method,
             acceleration: Some(acceleration),
             force: Some(force),
-            timestamp: data.timestamp,
+            force_model,
+            timestamp: span.started_at,
+            device_timestamp: data.device_timestamp.map(|device_us| device_us.saturating_sub(elapsed_us)),
+            received_at: data.received_at,
+            duration_ms: span.duration_ms(),
             confidence,
         })
     }
@@ -290,9 +389,18 @@ static BLE_ADAPTER: OnceLock<Arc<Mutex<Option<Adapter>>>> = OnceLock::new();
 static CONNECTED_DEVICES: OnceLock<Arc<Mutex<HashMap<String, String>>>> = OnceLock::new();
 static DEVICE_TASKS: OnceLock<Arc<Mutex<HashMap<String, JoinHandle<()>>>>> = OnceLock::new();
 static DEVICE_REFERENCES: OnceLock<Arc<Mutex<HashMap<String, Device>>>> = OnceLock::new();
+// Dispositivos asignados a un competidor en combate (device_id -> asignación)
+static MATCH_ASSIGNMENTS: OnceLock<Arc<Mutex<HashMap<String, MatchAssignment>>>> = OnceLock::new();
+
+// Peleador y competidor de la plantilla que lleva una banda en combate
+#[derive(Debug, Clone)]
+struct MatchAssignment {
+    fighter_id: String,
+    roster_id: String,
+}
 
 // Función para obtener el adaptador singleton
-async fn get_ble_adapter() -> Result<Adapter, String> {
+pub(crate) async fn get_ble_adapter() -> Result<Adapter, String> {
     let adapter_lock = BLE_ADAPTER.get_or_init(|| Arc::new(Mutex::new(None)));
     
     // Verificar si ya tenemos un adaptador válido
@@ -325,6 +433,11 @@ fn get_connected_devices_state() -> Arc<Mutex<HashMap<String, String>>> {
     }).clone()
 }
 
+/// Indica si un dispositivo figura como conectado
+pub(crate) fn is_device_connected(device_id: &str) -> bool {
+    get_connected_devices_state().lock().unwrap().contains_key(device_id)
+}
+
 // Función para obtener el estado de tareas de dispositivos
 fn get_device_tasks_state() -> Arc<Mutex<HashMap<String, JoinHandle<()>>>> {
     DEVICE_TASKS.get_or_init(|| {
@@ -332,6 +445,25 @@ fn get_device_tasks_state() -> Arc<Mutex<HashMap<String, JoinHandle<()>>>> {
     }).clone()
 }
 
+// Función para obtener las asignaciones de dispositivos a combate
+fn get_match_assignments_state() -> Arc<Mutex<HashMap<String, MatchAssignment>>> {
+    MATCH_ASSIGNMENTS.get_or_init(|| {
+        Arc::new(Mutex::new(HashMap::new()))
+    }).clone()
+}
+
+/// Indica si el dispositivo está asignado a un competidor en un combate activo
+pub(crate) fn is_device_assigned_to_match(device_id: &str) -> bool {
+    get_match_assignments_state().lock().unwrap().contains_key(device_id)
+}
+
+/// Competidores de la plantilla que llevan bandas asignadas (fighter_id -> roster_id)
+pub(crate) fn assigned_competitors() -> BTreeMap<String, String> {
+    get_match_assignments_state().lock().unwrap().values()
+        .map(|assignment| (assignment.fighter_id.clone(), assignment.roster_id.clone()))
+        .collect()
+}
+
 // Función para obtener las referencias de dispositivos BLE
 fn get_device_references_state() -> Arc<Mutex<HashMap<String, Device>>> {
     DEVICE_REFERENCES.get_or_init(|| {
@@ -339,22 +471,126 @@ fn get_device_references_state() -> Arc<Mutex<HashMap<String, Device>>> {
     }).clone()
 }
 
-// Función para parsear datos BLE
-fn parse_imu_data(data: &[u8], timestamp: u64) -> Option<ImuData> {
-    if data.len() < 14 {
-        return None;
+/// Referencias BLE de los dispositivos conectados (para leer RSSI)
+pub(crate) fn connected_device_handles() -> Vec<(String, Device)> {
+    get_device_references_state().lock().unwrap()
+        .iter()
+        .map(|(device_id, device)| (device_id.clone(), device.clone()))
+        .collect()
+}
+
+// Función para convertir una muestra decodificada en datos IMU
+// Las magnitudes físicas se escalan aquí; la calibración se aplica después
+fn to_imu_data(sample: &DecodedSample, config: &SimpleDetectionConfig, received_at: u64) -> ImuData {
+    ImuData {
+        limb_id: sample.limb_id,
+        battery_level: sample.battery_level,
+        acc_x: sample.acc[0],
+        acc_y: sample.acc[1],
+        acc_z: sample.acc[2],
+        gyro_x: sample.gyro[0],
+        gyro_y: sample.gyro[1],
+        gyro_z: sample.gyro[2],
+        acc: sample.acc.map(|axis| axis as f32 / config.acc_scale),
+        gyro: sample.gyro.map(|axis| axis as f32 / config.gyro_scale),
+        unfiltered_acc: [0.0; 3],
+        unfiltered_gyro: [0.0; 3],
+        linear_acc: [0.0; 3],
+        measured_velocity: None,
+        timestamp: received_at,
+        device_timestamp: None,
+        received_at,
     }
+}
 
-    Some(ImuData {
-        limb_id: data[0],
-        battery_level: data[1],
-        acc_x: i16::from_le_bytes([data[2], data[3]]),
-        acc_y: i16::from_le_bytes([data[4], data[5]]),
-        acc_z: i16::from_le_bytes([data[6], data[7]]),
-        gyro_x: i16::from_le_bytes([data[8], data[9]]),
-        gyro_y: i16::from_le_bytes([data[10], data[11]]),
-        gyro_z: i16::from_le_bytes([data[12], data[13]]),
-        timestamp,
+// Estado propio de cada conexión usado al procesar notificaciones
+struct NotificationContext {
+    device_id: String,
+    clock_sync: ClockSyncEstimator,        // Reloj banda -> host
+    packet_stats: Arc<Mutex<PacketStats>>, // Pérdidas, duplicados y CRC
+    calibration: Option<DeviceCalibration>, // Copia local para no bloquear a 200Hz
+    calibration_revision: u64,
+    orientation: OrientationFilter,         // Actitud estimada para restar la gravedad
+    velocity: VelocityEstimator,            // Integración con reinicio en reposo
+    strike_window: StrikeWindow,            // Muestras recientes para clasificar golpes
+    filters: SignalFilters,                 // Mediana, pasa-bajos y pasa-altos por eje
+    filter_revision: u64,
+    health: Arc<Mutex<DeviceHealth>>,       // Frecuencia, huecos, saturación y ejes congelados
+}
+
+impl NotificationContext {
+    fn new(device_id: &str, default_filters: &FilterChainConfig) -> Self {
+        let filter_config = signal_filters::device_filter_config(device_id)
+            .unwrap_or_else(|| default_filters.clone());
+        
+        Self {
+            device_id: device_id.to_string(),
+            clock_sync: ClockSyncEstimator::new(),
+            packet_stats: packet_decoder::begin_connection(device_id),
+            calibration: sensor_calibration::get_calibration(device_id),
+            calibration_revision: sensor_calibration::calibration_revision(),
+            orientation: OrientationFilter::new(),
+            velocity: VelocityEstimator::new(),
+            strike_window: StrikeWindow::new(),
+            filters: SignalFilters::new(&filter_config),
+            filter_revision: signal_filters::filter_config_revision(),
+            health: sensor_health::monitor_device(device_id),
+        }
+    }
+    
+    /// Reconstruye los filtros si cambió la configuración del dispositivo
+    fn refresh_filters(&mut self, default_filters: &FilterChainConfig) {
+        let revision = signal_filters::filter_config_revision();
+        if revision == self.filter_revision {
+            return;
+        }
+        
+        let config = signal_filters::device_filter_config(&self.device_id)
+            .unwrap_or_else(|| default_filters.clone());
+        if &config != self.filters.config() {
+            self.filters = SignalFilters::new(&config);
+        }
+        self.filter_revision = revision;
+    }
+
+    /// Refresca la calibración local si cambió desde la última muestra
+    fn refresh_calibration(&mut self) {
+        let revision = sensor_calibration::calibration_revision();
+        if revision != self.calibration_revision {
+            self.calibration = sensor_calibration::get_calibration(&self.device_id);
+            self.calibration_revision = revision;
+        }
+    }
+}
+
+/// Construye un BleDevice a partir de un anuncio BLE
+/// Retorna None si el anuncio no corresponde a un dispositivo BH
+pub(crate) fn build_ble_device(discovered_device: &AdvertisingDevice) -> Option<BleDevice> {
+    let adv_data = &discovered_device.adv_data;
+    
+    // Verificar UUID de servicio (o prefijo de nombre si el respaldo está habilitado)
+    if !gatt_profile::is_bh_advertisement(&adv_data.services, adv_data.local_name.as_deref()) {
+        return None;
+    }
+    
+    let local_name = adv_data.local_name.clone()
+        .unwrap_or_else(|| "Dispositivo BH".to_string());
+    
+    let device_id = discovered_device.device.id().to_string();
+    
+    // Determinar tipo de extremidad (perfil guardado o nombre) y su nombre traducido
+    let limb = limb_assignment::resolve_limb(&device_id, &local_name, None).ok();
+    let limb_type = limb.map(|limb| format!("{:?}", limb));
+    let limb_name = limb.map(|limb| limb.name().to_string());
+    
+    Some(BleDevice {
+        id: device_id.clone(),
+        name: local_name,
+        address: device_id,
+        limb_type,
+        limb_name,
+        rssi: discovered_device.rssi,
+        is_connectable: adv_data.is_connectable,
     })
 }
 
@@ -371,7 +607,8 @@ pub async fn scan_available_devices() -> Result<Vec<BleDevice>, String> {
     adapter.wait_available().await
         .map_err(|e| format!("Error esperando adaptador: {}", e))?;
     
-    let mut scan = adapter.scan(&[]).await
+    let service_filter = gatt_profile::scan_service_filter();
+    let mut scan = adapter.scan(&service_filter).await
         .map_err(|e| format!("Error iniciando escaneo: {}", e))?;
     
     let mut devices = Vec::with_capacity(8); // Pre-allocar para hasta 8 dispositivos (2 peleadores x 4 extremidades)
@@ -390,52 +627,17 @@ pub async fn scan_available_devices() -> Result<Vec<BleDevice>, String> {
             discovered = scan.next() => {
                 match discovered {
                     Some(discovered_device) => {
-                        let adv_data = &discovered_device.adv_data;
-                        
-                        // Early filtering - verificar nombre primero (más eficiente)
-                        let local_name = match &adv_data.local_name {
-                            Some(name) if name.contains("BH-") => name,
-                            _ => continue, // Skip si no es nuestro dispositivo
+                        // Early filtering - solo dispositivos BH
+                        let Some(ble_device) = build_ble_device(&discovered_device) else {
+                            continue; // Skip si no es nuestro dispositivo
                         };
                         
-                        let device_id = discovered_device.device.id().to_string();
-                        
                         // Evitar duplicados (early exit)
-                        if !seen_devices.insert(device_id.clone()) {
+                        if !seen_devices.insert(ble_device.id.clone()) {
                             continue; // Ya visto, skip
                         }
                         
-                        // Determinar tipo de extremidad y su nombre traducido
-                        let limb_type = match local_name.as_str() {
-                            name if name.contains("ManoIzquierda") => Some("LeftHand".to_string()),
-                            name if name.contains("ManoDerecha") => Some("RightHand".to_string()),
-                            name if name.contains("PieIzquierdo") => Some("LeftFoot".to_string()),
-                            name if name.contains("PieDerecho") => Some("RightFoot".to_string()),
-                            _ => None,
-                        };
-                        
-                        // Obtener el nombre traducido si existe un tipo de extremidad
-                        let limb_name = limb_type.as_ref().and_then(|lt| {
-                            match lt.as_str() {
-                                "LeftHand" => Some(LimbType::LeftHand.name().to_string()),
-                                "RightHand" => Some(LimbType::RightHand.name().to_string()),
-                                "LeftFoot" => Some(LimbType::LeftFoot.name().to_string()),
-                                "RightFoot" => Some(LimbType::RightFoot.name().to_string()),
-                                _ => None,
-                            }
-                        });
-                        
-                        let ble_device = BleDevice {
-                            id: device_id.clone(),
-                            name: local_name.clone(),
-                            address: device_id,
-                            limb_type,
-                            limb_name,
-                            rssi: discovered_device.rssi,
-                            is_connectable: adv_data.is_connectable,
-                        };
-                        
-                        info!(device_name = %local_name, device_id = %ble_device.id, 
+                        info!(device_name = %ble_device.name, device_id = %ble_device.id, 
                               devices_found = devices.len() + 1,
                               "📱 Dispositivo BLE encontrado");
                         devices.push(ble_device);
@@ -463,8 +665,8 @@ pub async fn connect_to_specific_device<R: tauri::Runtime>(
     // 1. Buscar y encontrar el dispositivo BLE
     let (target_device, device_name) = find_ble_device_by_id(&device_id).await?;
     
-    // 2. Determinar tipo de extremidad usando patrón mejorado
-    let limb_type = determine_limb_type_by_pattern(&device_name);
+    // 2. Determinar tipo de extremidad (perfil guardado o nombre anunciado)
+    let limb_type = limb_assignment::resolve_limb(&device_id, &device_name, None)?;
     
     // 3. Registrar dispositivo como conectado (sin competidor)
     register_device_without_competitor(&device_id, &device_name);
@@ -517,10 +719,8 @@ pub async fn disconnect_from_device(device_id: String) -> Result<(), String> {
         info!(device_id = %device_id, "🛑 Tarea del dispositivo cancelada");
     }
     
-    // Remover de la lista de conectados
-    let connected_devices = get_connected_devices_state();
-    let mut devices = connected_devices.lock().unwrap();
-    devices.remove(&device_id);
+    drop(tasks);
+    forget_device_state(&device_id);
     
     info!(device_id = %device_id, "✅ Dispositivo BLE desconectado completamente");
     Ok(())
@@ -574,6 +774,13 @@ pub async fn disconnect_all_devices() -> Result<(), String> {
     // Limpiar lista de dispositivos conectados
     let mut devices = connected_devices.lock().unwrap();
     devices.clear();
+    device_info::clear_device_information();
+    for device_id in &device_ids {
+        sensor_health::forget_device(device_id);
+    }
+    imu_stream::stop_all_streams();
+    get_match_assignments_state().lock().unwrap().clear();
+    limb_assignment::release_all();
     
     info!("✅ Todos los dispositivos BLE desconectados completamente");
     Ok(())
@@ -587,19 +794,35 @@ pub async fn get_connected_devices_list() -> Result<Vec<String>, String> {
     Ok(devices.keys().cloned().collect())
 }
 
+// Función para obtener dispositivos conectados con batería y versión de firmware
+pub async fn get_connected_devices_details() -> Result<Vec<ConnectedDeviceDetails>, String> {
+    let connected_devices = get_connected_devices_state();
+    let devices = connected_devices.lock().unwrap();
+    Ok(devices.iter()
+        .map(|(id, label)| ConnectedDeviceDetails {
+            id: id.clone(),
+            label: label.clone(),
+            information: device_info::get_device_information(id),
+        })
+        .collect())
+}
+
 // Manejo simplificado de periférico - Función coordinadora principal
 async fn handle_simple_peripheral<R: tauri::Runtime>(
     device: Device,
+    device_id: &str,
     limb_type: LimbType,
     detector: Arc<Mutex<SimpleEventDetector>>,
     app_handle: Arc<AppHandle<R>>,
 ) -> Result<(), String> {
     // 1. Establecer conexión BLE
     let _adapter = establish_ble_connection(&device).await?;
-    // 2. Descubrir servicios y características
+    // 2. Leer batería y Device Information Service
+    register_device_information(&device, device_id, &app_handle).await;
+    // 3. Descubrir servicios y características
     let notification_char = discover_notification_characteristic(&device).await?;
     
-    // 3. Suscribirse a notificaciones
+    // 4. Suscribirse a notificaciones
     info!(limb_type = ?limb_type, "📡 Suscribiéndose a notificaciones BLE");
     
     let notification_stream = notification_char.notify().await
@@ -611,8 +834,8 @@ async fn handle_simple_peripheral<R: tauri::Runtime>(
     
     info!(limb_type = ?limb_type, "🔔 Notificaciones BLE configuradas");
     
-    // 4. Procesar notificaciones en loop
-    process_notification_stream(notification_stream, limb_type, detector, app_handle).await;
+    // 5. Procesar notificaciones en loop
+    process_notification_stream(notification_stream, device_id, limb_type, detector, app_handle).await;
     
     info!(limb_type = ?limb_type, "🔌 Conexión terminada");
     Ok(())
@@ -638,26 +861,86 @@ async fn establish_ble_connection(device: &Device) -> Result<Adapter, String> {
     Ok(adapter)
 }
 
-/// Descubre y retorna la característica de notificación
+/// Lee la información estándar de la banda y la publica en el registro
+async fn register_device_information<R: tauri::Runtime>(
+    device: &Device,
+    device_id: &str,
+    app_handle: &AppHandle<R>,
+) {
+    let information = device_info::read_device_information(device).await;
+    device_info::store_device_information(device_id, information.clone());
+    
+    let payload = serde_json::json!({
+        "device_id": device_id,
+        "information": information
+    });
+    if let Err(e) = app_handle.emit("device-info-updated", &payload) {
+        error!(device_id = %device_id, error = %e, "Error emitiendo información del dispositivo");
+    }
+}
+
+/// Descubre y retorna la característica de notificación del sensor IMU
+/// Busca primero los UUIDs del perfil GATT; el descubrimiento genérico solo si está habilitado
 #[instrument(skip(device))]
 async fn discover_notification_characteristic(device: &Device) -> Result<Characteristic, String> {
-    // Obtener servicios directamente del dispositivo
+    let profile = gatt_profile::current_profile();
+    
+    // 1. Buscar servicio y característica esperados
+    let services = device.discover_services_with_uuid(profile.service_uuid).await
+        .map_err(|e| format!("Error obteniendo servicios: {}", e))?;
+    
+    for service in &services {
+        let characteristics = service.discover_characteristics_with_uuid(profile.imu_characteristic_uuid).await
+            .map_err(|e| format!("Error obteniendo características: {}", e))?;
+        
+        for characteristic in characteristics {
+            if let Ok(props) = characteristic.properties().await {
+                if props.notify {
+                    info!(uuid = %characteristic.uuid(), "Característica IMU encontrada");
+                    return Ok(characteristic);
+                }
+            }
+        }
+    }
+    
+    if !profile.allow_fallback_discovery {
+        let error_msg = format!(
+            "No se encontró la característica IMU {} en el servicio {}",
+            profile.imu_characteristic_uuid, profile.service_uuid
+        );
+        error!("{}", error_msg);
+        return Err(error_msg);
+    }
+    
+    // 2. Respaldo: primera característica notify fuera de los servicios estándar
+    warn!("⚠️ Característica IMU no encontrada, usando descubrimiento de respaldo");
+    discover_fallback_notification_characteristic(device).await
+}
+
+/// Descubrimiento de respaldo para bandas con UUIDs desconocidos
+/// Ignora servicios estándar (Battery Service, Device Information, etc.)
+async fn discover_fallback_notification_characteristic(device: &Device) -> Result<Characteristic, String> {
     let services = device.services().await
         .map_err(|e| format!("Error obteniendo servicios: {}", e))?;
     
     debug!(services_count = services.len(), "Servicios BLE descubiertos");
     
-    // Buscar característica con notificaciones
     for service in &services {
+        if gatt_profile::is_standard_bluetooth_uuid(&service.uuid()) {
+            continue;
+        }
+        
         let characteristics = service.characteristics().await
             .map_err(|e| format!("Error obteniendo características: {}", e))?;
         
         for characteristic in characteristics {
-            let properties = characteristic.properties().await;
+            if gatt_profile::is_standard_bluetooth_uuid(&characteristic.uuid()) {
+                continue;
+            }
             
-            if let Ok(props) = properties {
+            if let Ok(props) = characteristic.properties().await {
                 if props.notify {
-                    info!(uuid = %characteristic.uuid(), "Característica de notificación encontrada");
+                    info!(uuid = %characteristic.uuid(), "Característica de notificación encontrada (respaldo)");
                     return Ok(characteristic);
                 }
             }
@@ -673,17 +956,22 @@ async fn discover_notification_characteristic(device: &Device) -> Result<Charact
 #[instrument(skip(notification_stream, detector, app_handle))]
 async fn process_notification_stream<R: tauri::Runtime>(
     mut notification_stream: impl Stream<Item = Result<Vec<u8>, bluest::Error>> + Unpin,
+    device_id: &str,
     limb_type: LimbType,
     detector: Arc<Mutex<SimpleEventDetector>>,
     app_handle: Arc<AppHandle<R>>,
 ) {
     debug!(limb_type = ?limb_type, "Iniciando procesamiento de notificaciones");
     
+    // Reloj y estadísticas propios de esta conexión
+    let default_filters = detector.lock().unwrap().config().filters.clone();
+    let mut context = NotificationContext::new(device_id, &default_filters);
+    
     while let Some(data_result) = notification_stream.next().await {
         match data_result {
             Ok(data_bytes) => {
                 // CRÍTICO: Sin logging aquí para máximo rendimiento (200Hz)
-                process_notification_data(data_bytes, limb_type, &detector, &app_handle);
+                process_notification_data(data_bytes, limb_type, &detector, &mut context, &app_handle);
             }
             Err(e) => {
                 error!(limb_type = ?limb_type, error = %e, "Error en notificación BLE");
@@ -691,6 +979,11 @@ async fn process_notification_stream<R: tauri::Runtime>(
             }
         }
     }
+    
+    let packet_stats = context.packet_stats.lock().unwrap().clone();
+    debug!(limb_type = ?limb_type, offset_ms = ?context.clock_sync.offset_ms(), drift_ppm = ?context.clock_sync.drift_ppm(),
+           lost = packet_stats.lost, checksum_failures = packet_stats.checksum_failures,
+           "Sincronización de reloj y estadísticas al cerrar la conexión");
 }
 
 /// Procesa los datos de notificación BLE recibidos
@@ -699,42 +992,100 @@ fn process_notification_data<R: tauri::Runtime>(
     data_bytes: Vec<u8>,
     limb_type: LimbType,
     detector: &Arc<Mutex<SimpleEventDetector>>,
+    context: &mut NotificationContext,
     app_handle: &Arc<tauri::AppHandle<R>>,
 ) {
-    const EXPECTED_IMU_SIZE: usize = 14;
-    
-    // Validación rápida sin logging
-    if data_bytes.len() != EXPECTED_IMU_SIZE {
-        return; // Silencioso para máximo rendimiento
-    }
-    
-    // Generar timestamp
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as u64;
+    // Hora de llegada
+    let received_at_us = host_time_us();
     
-    // Parsear datos IMU
-    let imu_data = match parse_imu_data(&data_bytes, timestamp) {
-        Some(data) => data,
-        None => return, // Silencioso para máximo rendimiento
+    // Decodificar según versión; errores y duplicados solo cuentan en estadísticas
+    let packet = match packet_decoder::decode_packet(&data_bytes) {
+        Ok(packet) => packet,
+        Err(e) => {
+            context.packet_stats.lock().unwrap().record_error(&e);
+            return; // Silencioso para máximo rendimiento
+        }
     };
+    if !context.packet_stats.lock().unwrap().record_packet(&packet) {
+        return;
+    }
     
-    // Detectar eventos de combate
-    let event = match detector.lock().unwrap().detect_event(&imu_data) {
-        Some(event) => event,
-        None => return, // No hay evento, continuar
-    };
+    context.refresh_calibration();
+    let detection_config = detector.lock().unwrap().config().clone();
+    context.refresh_filters(&detection_config.filters);
     
+    // Los paquetes por lote traen varias muestras: detectar en orden
+    for sample in &packet.samples {
+        let mut imu_data = to_imu_data(sample, &detection_config, received_at_us / 1000);
+        // La extremidad asignada al conectar manda sobre la que indica la banda
+        imu_data.limb_id = limb_type.id();
+        
+        // Alimentar una calibración en curso con valores sin calibrar y aplicar la guardada
+        sensor_calibration::feed_sample(app_handle, &context.device_id, imu_data.acc, imu_data.gyro);
+        if let Some(calibration) = &context.calibration {
+            (imu_data.acc, imu_data.gyro) = calibration.apply(imu_data.acc, imu_data.gyro);
+        }
+        
+        // Filtrar por eje (vibración, golpeteo de la correa) antes de la detección
+        imu_data.unfiltered_acc = imu_data.acc;
+        imu_data.unfiltered_gyro = imu_data.gyro;
+        (imu_data.acc, imu_data.gyro) = context.filters.filter_motion(imu_data.acc, imu_data.gyro);
+        
+        // Mapear la hora de la banda a hora host si el paquete la incluye
+        if let Some(device_raw_us) = sample.device_clock_us {
+            let (device_us, host_ms) = context.clock_sync.synchronize(device_raw_us, received_at_us);
+            imu_data.device_timestamp = Some(device_us);
+            imu_data.timestamp = host_ms;
+        }
+        context.health.lock().unwrap().record_sample(imu_data.timestamp, [
+            imu_data.acc_x, imu_data.acc_y, imu_data.acc_z,
+            imu_data.gyro_x, imu_data.gyro_y, imu_data.gyro_z,
+        ]);
+        
+        // Estimar orientación y obtener la aceleración lineal en marco mundial
+        let sample_time_us = imu_data.device_timestamp.unwrap_or(imu_data.timestamp * 1000);
+        let linear_acc = context.orientation.update(imu_data.acc, imu_data.gyro, sample_time_us);
+        imu_data.linear_acc = context.filters.filter_linear_acc(linear_acc);
+        imu_data.measured_velocity = context.velocity.update(imu_data.linear_acc, imu_data.gyro, sample_time_us);
+        
+        let motion = MotionSample {
+            timestamp: imu_data.timestamp,
+            linear_acc: imu_data.linear_acc,
+            sensor_linear_acc: context.orientation.sensor_linear_acc(imu_data.acc),
+            gyro: imu_data.gyro,
+        };
+        context.strike_window.push(motion);
+        imu_recording::record_sample(&context.device_id, &imu_data, &motion);
+        imu_stream::publish_sample(&context.device_id, &imu_data);
+        
+        // Detectar eventos de combate
+        let event = match detector.lock().unwrap().detect_event(&imu_data, &context.strike_window) {
+            Some(event) => event,
+            None => continue, // No hay evento, continuar
+        };
+        
+        dispatch_combat_event(&event, limb_type, app_handle);
+    }
+}
+
+/// Publica un evento detectado: récords, frontend y WebSocket
+fn dispatch_combat_event<R: tauri::Runtime>(
+    event: &SimpleCombatEvent,
+    limb_type: LimbType,
+    app_handle: &Arc<tauri::AppHandle<R>>,
+) {
     // Solo logging para eventos detectados (menos frecuente)
     info!(limb_type = ?limb_type, event_type = %event.event_type, 
           "⚔️ Evento de combate detectado");
     
-    // Verificar y actualizar estadísticas máximas
-    check_and_update_max_stats(&event, app_handle);
+    // En sesión los récords se derivan de su registro (y pueden dar bonus);
+    // fuera de ella no hay registro y se acumulan directamente
+    if !scoring::score_event(app_handle, event) {
+        check_and_update_max_stats(event, app_handle);
+    }
     
     // Emitir evento al frontend
-    if let Err(e) = app_handle.emit("simple-combat-event", &event) {
+    if let Err(e) = app_handle.emit("simple-combat-event", event) {
         // Solo errores críticos se loggean
         error!(limb_type = ?limb_type, error = %e, "Error emitiendo evento");
     }
@@ -755,22 +1106,34 @@ pub async fn connect_to_device_with_competitor<R: tauri::Runtime>(
     app_handle: Arc<AppHandle<R>>,
     device_id: String,
     competitor_id: u8,
-    competitor_name: String,
-    competitor_weight: f32,
+    roster_id: String,
+    limb_override: Option<LimbType>,
 ) -> Result<(), String> {
+    // 1. Crear información del competidor a partir de la plantilla
+    let competitor_info = create_competitor_info(competitor_id, &roster_id)?;
+    let competitor_name = competitor_info.name.clone();
     info!(device_id = %device_id, competitor_name = %competitor_name, "🔗 Conectando dispositivo para competidor");
     
-    // 1. Crear información del competidor
-    let competitor_info = create_competitor_info(competitor_id, competitor_name.clone(), competitor_weight);
-    
     // 2. Buscar y encontrar el dispositivo BLE
     let (target_device, device_name) = find_ble_device_by_id(&device_id).await?;
     
-    // 3. Determinar tipo de extremidad
-    let limb_type = determine_limb_type_by_pattern(&device_name);
-    
-    // 4. Registrar dispositivo como conectado
+    // 3. Determinar tipo de extremidad y reservarla (una banda por extremidad y peleador)
+    let fighter_id = format!("fighter_{}", competitor_id);
+    let limb_type = limb_assignment::resolve_limb(&device_id, &device_name, limb_override)?;
+    limb_assignment::claim(&device_id, &fighter_id, limb_type)?;
+    
+    // 4. Asignar la banda al combate salvo que esté actualizando su firmware
+    // (se asigna antes de comprobar para que una OTA que arranque a la vez vea la asignación)
+    get_match_assignments_state().lock().unwrap()
+        .insert(device_id.clone(), MatchAssignment { fighter_id: fighter_id.clone(), roster_id: roster_id.clone() });
+    if firmware_ota::is_update_running(&device_id) {
+        get_match_assignments_state().lock().unwrap().remove(&device_id);
+        limb_assignment::release(&device_id);
+        return Err(format!("El dispositivo {} tiene una actualización de firmware en curso", device_id));
+    }
     register_connected_device(&device_id, &device_name, &competitor_name);
+    // La sesión en curso recuerda qué competidor de la plantilla es cada peleador
+    scoring::link_competitor(&app_handle, &fighter_id, &roster_id);
     
     // 5. Configurar detector con información del competidor
     let detector = setup_competitor_detector(competitor_info, &competitor_name, limb_type);
@@ -790,13 +1153,19 @@ pub async fn connect_to_device_with_competitor<R: tauri::Runtime>(
     Ok(())
 }
 
-/// Crea la información del competidor
-fn create_competitor_info(id: u8, name: String, weight: f32) -> CompetitorInfo {
-    CompetitorInfo {
+/// Crea la información del competidor con su último pesaje
+fn create_competitor_info(id: u8, roster_id: &str) -> Result<CompetitorInfo, String> {
+    let entry = roster::get_competitor(roster_id)
+        .ok_or_else(|| format!("No existe el competidor {} en la plantilla", roster_id))?;
+    let weight = entry.current_weight
+        .ok_or_else(|| format!("{} no tiene ningún pesaje registrado", entry.competitor.name))?;
+
+    Ok(CompetitorInfo {
         id,
-        name,
+        roster_id: roster_id.to_string(),
+        name: entry.competitor.name,
         weight,
-    }
+    })
 }
 
 /// Determina el tipo de extremidad basado en el nombre del dispositivo
@@ -831,12 +1200,19 @@ fn setup_competitor_detector(
 
 /// Busca y encuentra un dispositivo BLE por su ID
 async fn find_ble_device_by_id(device_id: &str) -> Result<(Device, String), String> {
+    // Reutilizar el resultado del escaneo continuo si está activo
+    if let Some((device, device_name)) = crate::device_discovery::find_cached_device(device_id) {
+        debug!(device_id = %device_id, "📦 Dispositivo BLE tomado del escaneo continuo");
+        return Ok((device, device_name));
+    }
+    
     // Obtener adaptador singleton
     let adapter = get_ble_adapter().await?;
     
     // Iniciar escaneo
     debug!(device_id = %device_id, "🔍 Buscando dispositivo BLE");
-    let mut scan = adapter.scan(&[]).await
+    let service_filter = gatt_profile::scan_service_filter();
+    let mut scan = adapter.scan(&service_filter).await
         .map_err(|e| format!("Error iniciando escaneo: {}", e))?;
     
     // Configurar timeout
@@ -872,6 +1248,15 @@ async fn find_ble_device_by_id(device_id: &str) -> Result<(Device, String), Stri
     }
 }
 
+/// Obtiene la referencia BLE de un dispositivo, conectado o visible en escaneo
+pub(crate) async fn find_ble_device(device_id: &str) -> Result<Device, String> {
+    let connected = get_device_references_state().lock().unwrap().get(device_id).cloned();
+    match connected {
+        Some(device) => Ok(device),
+        None => find_ble_device_by_id(device_id).await.map(|(device, _)| device),
+    }
+}
+
 /// Lanza una tarea para manejar el dispositivo BLE
 fn spawn_device_handler<R: tauri::Runtime>(
     target_device: Device,
@@ -881,30 +1266,28 @@ fn spawn_device_handler<R: tauri::Runtime>(
     device_id: String,
 ) -> JoinHandle<()> {
     tokio::spawn(async move {
-        if let Err(e) = handle_simple_peripheral(target_device, limb_type, detector, app_handle.clone()).await {
-            error!(device_id = %device_id, error = %e, "Error manejando dispositivo BLE");
-            
-            // Limpiar estado si hay error
-            let connected_devices = get_connected_devices_state();
-            let mut devices = connected_devices.lock().unwrap();
-            devices.remove(&device_id);
-            drop(devices);
-            
-            // Remover referencia del dispositivo BLE
-            let device_references = get_device_references_state();
-            let mut references = device_references.lock().unwrap();
-            references.remove(&device_id);
+        match handle_simple_peripheral(target_device, &device_id, limb_type, detector, app_handle.clone()).await {
+            Ok(()) => warn!(device_id = %device_id, "📴 La banda dejó de enviar notificaciones"),
+            Err(e) => error!(device_id = %device_id, error = %e, "Error manejando dispositivo BLE"),
         }
+
+        // Tanto si la banda se cae como si falla la conexión, se limpia su estado;
+        // una desconexión manual cancela la tarea antes y limpia por su cuenta
+        get_device_tasks_state().lock().unwrap().remove(&device_id);
+        get_device_references_state().lock().unwrap().remove(&device_id);
+        forget_device_state(&device_id);
     })
 }
 
-/// Determina el tipo de extremidad usando patrón mejorado con ble_name_pattern
-fn determine_limb_type_by_pattern(device_name: &str) -> LimbType {
-    [LimbType::LeftHand, LimbType::RightHand, LimbType::LeftFoot, LimbType::RightFoot]
-        .iter()
-        .find(|limb| device_name.contains(limb.ble_name_pattern()))
-        .copied()
-        .unwrap_or(LimbType::LeftHand) // Default
+/// Olvida el estado de una banda que ya no está conectada
+fn forget_device_state(device_id: &str) {
+    get_connected_devices_state().lock().unwrap().remove(device_id);
+    device_info::remove_device_information(device_id);
+    sensor_health::forget_device(device_id);
+    imu_stream::forget_device(device_id);
+    get_match_assignments_state().lock().unwrap().remove(device_id);
+    // Liberar la extremidad para poder reconectar la banda (o otra) en ella
+    limb_assignment::release(device_id);
 }
 
 /// Registra el dispositivo como conectado sin información de competidor
@@ -920,92 +1303,70 @@ fn setup_basic_detector(limb_type: LimbType) -> Arc<Mutex<SimpleEventDetector>>
     Arc::new(Mutex::new(SimpleEventDetector::new()))
 }
 
-/// Función para detectar y actualizar nuevos máximos
+/// Función para detectar y actualizar nuevos máximos de eventos fuera de una sesión
 fn check_and_update_max_stats<R: tauri::Runtime>(
     event: &SimpleCombatEvent,
     app_handle: &AppHandle<R>,
 ) {
-    let store = MAX_STATS_STORE.clone();
-    let mut stats_map = match store.lock() {
-        Ok(map) => map,
+    let new_records = match MAX_STATS_STORE.lock() {
+        Ok(mut stats_map) => stats_map.entry(event.fighter_id.clone())
+            .or_insert_with(|| CompetitorMaxStats::new(event))
+            .register(event),
         Err(e) => {
             error!(error = %e, "Error accediendo al store de estadísticas");
             return;
         }
     };
+    announce_new_records(app_handle, event, &new_records);
+}
 
-    // Obtener o crear estadísticas del peleador
-    let stats = stats_map.entry(event.fighter_id.clone()).or_insert(CompetitorMaxStats {
-        fighter_id: event.fighter_id.clone(),
-        competitor_name: event.competitor_name.clone(),
-        max_force: 0.0,
-        max_velocity: 0.0,
-        max_acceleration: 0.0,
-    });
-
-    let mut new_records = Vec::new();
-
-    // Verificar nuevo máximo de fuerza
-    if let Some(force) = event.force {
-        if force > stats.max_force {
-            stats.max_force = force;
-            new_records.push("force");
-            info!(fighter_id = %event.fighter_id, new_max_force = force, 
-                  "🏆 NUEVO RÉCORD DE FUERZA");
-        }
-    }
-
-    // Verificar nuevo máximo de velocidad
-    if let Some(velocity) = event.velocity {
-        if velocity > stats.max_velocity {
-            stats.max_velocity = velocity;
-            new_records.push("velocity");
-            info!(fighter_id = %event.fighter_id, new_max_velocity = velocity, 
-                  "🏆 NUEVO RÉCORD DE VELOCIDAD");
-        }
+/// Notifica los récords que batió un evento con las estadísticas ya actualizadas
+pub(crate) fn announce_new_records<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    event: &SimpleCombatEvent,
+    new_records: &[&'static str],
+) {
+    if new_records.is_empty() {
+        return;
     }
+    let Some(stats) = MAX_STATS_STORE.lock().unwrap().get(&event.fighter_id).cloned() else {
+        return;
+    };
 
-    // Verificar nuevo máximo de aceleración
-    if let Some(acceleration) = event.acceleration {
-        if acceleration > stats.max_acceleration {
-            stats.max_acceleration = acceleration;
-            new_records.push("acceleration");
-            info!(fighter_id = %event.fighter_id, new_max_acceleration = acceleration, 
-                  "🏆 NUEVO RÉCORD DE ACELERACIÓN");
+    for record in new_records {
+        match *record {
+            "force" => info!(fighter_id = %event.fighter_id, new_max_force = stats.max_force, "🏆 NUEVO RÉCORD DE FUERZA"),
+            "velocity" => info!(fighter_id = %event.fighter_id, new_max_velocity = stats.max_velocity, "🏆 NUEVO RÉCORD DE VELOCIDAD"),
+            _ => info!(fighter_id = %event.fighter_id, new_max_acceleration = stats.max_acceleration, "🏆 NUEVO RÉCORD DE ACELERACIÓN"),
         }
     }
 
-    // Si hay nuevos récords, enviar notificaciones
-    if !new_records.is_empty() {
-        let stats_clone = stats.clone();
-        
-        // 1. Enviar evento al frontend
-        let record_event = serde_json::json!({
-            "type": "new_max_record",
-            "fighter_id": event.fighter_id,
-            "records": new_records,
-            "stats": stats_clone,
-            "triggering_event": event
-        });
+    // 1. Enviar evento al frontend
+    let record_event = serde_json::json!({
+        "type": "new_max_record",
+        "fighter_id": event.fighter_id,
+        "records": new_records,
+        "stats": stats,
+        "triggering_event": event
+    });
 
-        if let Err(e) = app_handle.emit("new-max-record", &record_event) {
-            error!(error = %e, "Error emitiendo evento de nuevo récord");
-        }
+    if let Err(e) = app_handle.emit("new-max-record", &record_event) {
+        error!(error = %e, "Error emitiendo evento de nuevo récord");
+    }
 
-        // 2. Enviar por WebSocket
-        let ws_message = serde_json::json!({
-            "type": "max_stats_update",
-            "fighter_id": event.fighter_id,
-            "data": stats_clone,
-            "new_records": new_records,
-            "timestamp": event.timestamp,
-            "view_type": "stats"
-        });
-        ws_broadcast(&ws_message);
+    // 2. Enviar por WebSocket
+    let ws_message = serde_json::json!({
+        "type": "max_stats_update",
+        "fighter_id": event.fighter_id,
+        "data": stats,
+        "new_records": new_records,
+        "timestamp": event.timestamp,
+        "view_type": "stats"
+    });
+    ws_broadcast(&ws_message);
 
-        info!(fighter_id = %event.fighter_id, records = ?new_records, 
-              "📡 Nuevos récords enviados por WebSocket y evento");
-    }
+    info!(fighter_id = %event.fighter_id, records = ?new_records, 
+          "📡 Nuevos récords enviados por WebSocket y evento");
 }
 
 /// Comando para obtener estadísticas máximas actuales
@@ -0,0 +1,67 @@
+// Persistencia simple en archivos JSON dentro del directorio de datos de la app
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing::{error, warn};
+
+/// Ruta de un archivo dentro del directorio de datos de la aplicación
+pub fn data_file_path<R: tauri::Runtime>(app_handle: &AppHandle<R>, file_name: &str) -> Result<PathBuf, String> {
+    let data_dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Error obteniendo directorio de datos: {}", e))?;
+
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|e| format!("Error creando directorio de datos: {}", e))?;
+
+    Ok(data_dir.join(file_name))
+}
+
+/// Subdirectorio dentro del directorio de datos (se crea si no existe)
+pub fn data_subdir<R: tauri::Runtime>(app_handle: &AppHandle<R>, name: &str) -> Result<PathBuf, String> {
+    let dir = data_file_path(app_handle, name)?;
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Error creando directorio {}: {}", name, e))?;
+
+    Ok(dir)
+}
+
+/// Carga un archivo JSON; None si no existe o no se puede interpretar
+pub fn load_json<T: DeserializeOwned, R: tauri::Runtime>(app_handle: &AppHandle<R>, file_name: &str) -> Option<T> {
+    let path = data_file_path(app_handle, file_name).ok()?;
+    load_json_at(&path)
+}
+
+/// Carga un archivo JSON desde una ruta concreta
+pub fn load_json_at<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = std::fs::read_to_string(path).ok()?;
+
+    match serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "⚠️ Archivo de datos inválido, se ignora");
+            None
+        }
+    }
+}
+
+/// Guarda un valor como JSON (escritura atómica vía archivo temporal)
+pub fn save_json<T: Serialize, R: tauri::Runtime>(app_handle: &AppHandle<R>, file_name: &str, value: &T) -> Result<(), String> {
+    let path = data_file_path(app_handle, file_name)?;
+    save_json_at(&path, value)
+}
+
+/// Guarda un valor como JSON en una ruta concreta (escritura atómica vía archivo temporal)
+pub fn save_json_at<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let temp_path = path.with_extension("json.tmp");
+
+    let contents = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Error serializando {}: {}", path.display(), e))?;
+
+    std::fs::write(&temp_path, contents)
+        .and_then(|_| std::fs::rename(&temp_path, path))
+        .map_err(|e| {
+            error!(path = %path.display(), error = %e, "❌ Error guardando archivo de datos");
+            format!("Error guardando {}: {}", path.display(), e)
+        })
+}
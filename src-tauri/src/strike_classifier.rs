@@ -0,0 +1,291 @@
+// Clasificación de golpes a partir de la firma acc/gyro de la ventana del golpe
+// Las clases y sus umbrales se definen en configuración; lo que no encaja se reporta como "unknown"
+
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::sync::RwLock;
+use tauri::AppHandle;
+use tracing::info;
+
+use crate::simple_ble::LimbType;
+use crate::storage;
+use crate::strike_model;
+use crate::strike_tracker::StrikeSpan;
+
+const STRIKE_CLASSES_FILE: &str = "strike_classes.json";
+
+pub const UNKNOWN_STRIKE: &str = "unknown";
+
+// Capacidad máxima de la ventana (1 s a 200Hz)
+const MAX_WINDOW_SAMPLES: usize = 200;
+
+/// Grupo de extremidades al que aplica una clase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimbGroup {
+    Hand,
+    Foot,
+}
+
+impl LimbGroup {
+    pub fn of(limb_type: LimbType) -> Self {
+        match limb_type {
+            LimbType::LeftHand | LimbType::RightHand => LimbGroup::Hand,
+            LimbType::LeftFoot | LimbType::RightFoot => LimbGroup::Foot,
+        }
+    }
+}
+
+/// Eje del sensor (marco de la banda) con mayor aceleración lineal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorAxis {
+    X,
+    Y,
+    Z,
+}
+
+/// Regla de una clase de golpe; los límites en None no se evalúan
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StrikeClassRule {
+    pub name: String,
+    pub limb_group: LimbGroup,
+    pub min_peak_acc: f32,                  // g (aceleración lineal)
+    pub min_peak_gyro: Option<f32>,         // °/s
+    pub max_peak_gyro: Option<f32>,         // °/s
+    pub max_vertical_acc: Option<f32>,      // g en Z mundial en el pico (negativo = hacia abajo)
+    pub min_vertical_ratio: Option<f32>,    // |Z| / magnitud en el pico
+    pub max_vertical_ratio: Option<f32>,
+    pub dominant_axis: Option<SensorAxis>,  // Eje de la banda que domina el empuje
+}
+
+/// Configuración del clasificador
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StrikeClassifierConfig {
+    pub pre_onset_ms: u64,   // Margen analizado antes del inicio del golpe
+    pub min_confidence: f32, // Por debajo se reporta como "unknown"
+    pub classes: Vec<StrikeClassRule>,
+}
+
+impl Default for StrikeClassifierConfig {
+    fn default() -> Self {
+        let rule = |name: &str, limb_group: LimbGroup, min_peak_acc: f32| StrikeClassRule {
+            name: name.to_string(),
+            limb_group,
+            min_peak_acc,
+            min_peak_gyro: None,
+            max_peak_gyro: None,
+            max_vertical_acc: None,
+            min_vertical_ratio: None,
+            max_vertical_ratio: None,
+            dominant_axis: None,
+        };
+
+        Self {
+            pre_onset_ms: 100,
+            min_confidence: 0.55,
+            classes: vec![
+                // Manos: puñetazo recto (poco giro) frente a bofetada en arco
+                StrikeClassRule { max_peak_gyro: Some(3.0), max_vertical_ratio: Some(0.7), ..rule("punch", LimbGroup::Hand, 0.8) },
+                StrikeClassRule { min_peak_gyro: Some(3.0), ..rule("slap", LimbGroup::Hand, 0.8) },
+                // Pies: hacia abajo, frontal, lateral y circular
+                StrikeClassRule { max_peak_gyro: Some(10.0), max_vertical_acc: Some(-0.3), min_vertical_ratio: Some(0.6),
+                                  ..rule("kickdown", LimbGroup::Foot, 1.0) },
+                StrikeClassRule { max_peak_gyro: Some(10.0), max_vertical_ratio: Some(0.6), dominant_axis: Some(SensorAxis::X),
+                                  ..rule("front_kick", LimbGroup::Foot, 1.0) },
+                StrikeClassRule { max_peak_gyro: Some(10.0), max_vertical_ratio: Some(0.6), dominant_axis: Some(SensorAxis::Y),
+                                  ..rule("side_kick", LimbGroup::Foot, 1.0) },
+                StrikeClassRule { min_peak_gyro: Some(10.0), max_vertical_ratio: Some(0.6), ..rule("round_kick", LimbGroup::Foot, 1.0) },
+            ],
+        }
+    }
+}
+
+/// Muestra de movimiento usada para clasificar (ventana en vivo y grabaciones)
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct MotionSample {
+    pub timestamp: u64,              // ms
+    pub linear_acc: [f32; 3],        // g, marco mundial
+    pub sensor_linear_acc: [f32; 3], // g, marco de la banda
+    pub gyro: [f32; 3],              // °/s
+}
+
+/// Historial reciente de muestras de una conexión
+#[derive(Debug, Default)]
+pub struct StrikeWindow {
+    samples: VecDeque<MotionSample>,
+}
+
+impl StrikeWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, sample: MotionSample) {
+        if self.samples.len() == MAX_WINDOW_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Muestras desde `start` (ms) en orden cronológico
+    pub fn since(&self, start: u64) -> Vec<MotionSample> {
+        self.samples.iter().filter(|s| s.timestamp >= start).copied().collect()
+    }
+
+    /// Extrae la firma del golpe de las muestras desde `start` (ms)
+    pub fn features(&self, start: u64) -> Option<StrikeFeatures> {
+        let mut peak: Option<(&MotionSample, f32)> = None;
+        let mut peak_gyro = 0.0f32;
+        for sample in self.samples.iter().rev().take_while(|s| s.timestamp >= start) {
+            let magnitude = norm(sample.linear_acc);
+            if peak.is_none_or(|(_, peak_magnitude)| magnitude > peak_magnitude) {
+                peak = Some((sample, magnitude));
+            }
+            peak_gyro = peak_gyro.max(norm(sample.gyro));
+        }
+
+        let (peak_sample, peak_acc) = peak?;
+        let vertical_acc = peak_sample.linear_acc[2];
+        let sensor = peak_sample.sensor_linear_acc.map(f32::abs);
+        let dominant_axis = if sensor[0] >= sensor[1] && sensor[0] >= sensor[2] {
+            SensorAxis::X
+        } else if sensor[1] >= sensor[2] {
+            SensorAxis::Y
+        } else {
+            SensorAxis::Z
+        };
+
+        Some(StrikeFeatures {
+            peak_acc,
+            peak_gyro,
+            vertical_acc,
+            vertical_ratio: if peak_acc > 0.0 { vertical_acc.abs() / peak_acc } else { 0.0 },
+            dominant_axis,
+        })
+    }
+}
+
+/// Firma de un golpe usada por las reglas
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct StrikeFeatures {
+    pub peak_acc: f32,       // g
+    pub peak_gyro: f32,      // °/s
+    pub vertical_acc: f32,   // g en Z mundial en el pico
+    pub vertical_ratio: f32,
+    pub dominant_axis: SensorAxis,
+}
+
+/// Resultado de la clasificación
+#[derive(Debug, Clone)]
+pub struct StrikeClassification {
+    pub class: String,
+    pub confidence: f32,
+}
+
+impl StrikeClassifierConfig {
+    /// Clasifica un golpe; devuelve "unknown" si ninguna clase supera la confianza mínima
+    pub fn classify(&self, limb_type: LimbType, features: &StrikeFeatures) -> StrikeClassification {
+        let limb_group = LimbGroup::of(limb_type);
+
+        let best = self.classes.iter()
+            .filter(|rule| rule.limb_group == limb_group)
+            .filter_map(|rule| rule_confidence(rule, features).map(|confidence| (rule, confidence)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match best {
+            Some((rule, confidence)) if confidence >= self.min_confidence => StrikeClassification {
+                class: rule.name.clone(),
+                confidence,
+            },
+            other => StrikeClassification {
+                class: UNKNOWN_STRIKE.to_string(),
+                confidence: other.map(|(_, confidence)| confidence).unwrap_or(0.0),
+            },
+        }
+    }
+
+    /// Nombres de clases configuradas (más "unknown")
+    pub fn class_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.classes.iter().map(|rule| rule.name.clone()).collect();
+        names.push(UNKNOWN_STRIKE.to_string());
+        names
+    }
+}
+
+// Confianza de una regla: None si algún límite no se cumple; si se cumplen,
+// promedio de los márgenes normalizados (0.5 en el límite, 1.0 con margen amplio)
+fn rule_confidence(rule: &StrikeClassRule, features: &StrikeFeatures) -> Option<f32> {
+    if rule.dominant_axis.is_some_and(|axis| axis != features.dominant_axis) {
+        return None;
+    }
+
+    let mut margins = vec![margin(features.peak_acc - rule.min_peak_acc, rule.min_peak_acc)?];
+    if let Some(min) = rule.min_peak_gyro {
+        margins.push(margin(features.peak_gyro - min, min)?);
+    }
+    if let Some(max) = rule.max_peak_gyro {
+        margins.push(margin(max - features.peak_gyro, max)?);
+    }
+    if let Some(max) = rule.max_vertical_acc {
+        margins.push(margin(max - features.vertical_acc, max)?);
+    }
+    if let Some(min) = rule.min_vertical_ratio {
+        margins.push(margin(features.vertical_ratio - min, 1.0)?);
+    }
+    if let Some(max) = rule.max_vertical_ratio {
+        margins.push(margin(max - features.vertical_ratio, 1.0)?);
+    }
+
+    Some(margins.iter().sum::<f32>() / margins.len() as f32)
+}
+
+fn margin(distance: f32, scale: f32) -> Option<f32> {
+    if distance < 0.0 {
+        return None;
+    }
+    Some(0.5 + 0.5 * (distance / scale.abs().max(0.5)).min(1.0))
+}
+
+fn norm(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+static CLASSIFIER_CONFIG: Lazy<RwLock<StrikeClassifierConfig>> =
+    Lazy::new(|| RwLock::new(StrikeClassifierConfig::default()));
+
+pub fn current_config() -> StrikeClassifierConfig {
+    CLASSIFIER_CONFIG.read().unwrap().clone()
+}
+
+/// Clasifica un golpe terminado con el modelo entrenado activo o, si no cubre la extremidad,
+/// con las reglas configuradas
+pub fn classify_strike(limb_type: LimbType, window: &StrikeWindow, span: &StrikeSpan) -> Option<(StrikeClassification, StrikeFeatures)> {
+    let config = CLASSIFIER_CONFIG.read().unwrap();
+    let features = window.features(span.started_at.saturating_sub(config.pre_onset_ms))?;
+    let classification = strike_model::classify_with_model(limb_type, window, span, config.min_confidence)
+        .unwrap_or_else(|| config.classify(limb_type, &features));
+    Some((classification, features))
+}
+
+/// Carga la configuración guardada al iniciar la aplicación
+pub fn load_config<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    if let Some(config) = storage::load_json::<StrikeClassifierConfig, R>(app_handle, STRIKE_CLASSES_FILE) {
+        info!(classes = config.classes.len(), "🥋 Clases de golpes cargadas");
+        *CLASSIFIER_CONFIG.write().unwrap() = config;
+    }
+}
+
+/// Reemplaza la configuración activa y la persiste
+pub fn set_config<R: tauri::Runtime>(app_handle: &AppHandle<R>, config: StrikeClassifierConfig) -> Result<(), String> {
+    if config.classes.iter().any(|rule| rule.name == UNKNOWN_STRIKE) {
+        return Err(format!("'{}' es una clase reservada", UNKNOWN_STRIKE));
+    }
+    if !(0.0..=1.0).contains(&config.min_confidence) {
+        return Err("La confianza mínima debe estar entre 0 y 1".to_string());
+    }
+
+    storage::save_json(app_handle, STRIKE_CLASSES_FILE, &config)?;
+    *CLASSIFIER_CONFIG.write().unwrap() = config;
+    Ok(())
+}
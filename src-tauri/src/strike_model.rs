@@ -0,0 +1,452 @@
+// Extracción de características y entrenamiento offline del clasificador de golpes
+// Regresión logística multinomial por grupo de extremidades, exportada a JSON
+
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::RwLock;
+use tauri::AppHandle;
+use tracing::{error, info};
+
+use crate::imu_recording::Recording;
+use crate::simple_ble::{LimbType, SimpleDetectionConfig};
+use crate::storage;
+use crate::strike_classifier::{LimbGroup, MotionSample, StrikeClassification, StrikeWindow, UNKNOWN_STRIKE};
+use crate::strike_tracker::{StrikeSpan, StrikeTracker};
+
+const ACTIVE_MODEL_FILE: &str = "strike_model.json";
+
+// Orden fijo del vector de características
+pub const FEATURE_NAMES: [&str; 15] = [
+    "peak_acc",
+    "mean_acc",
+    "peak_gyro",
+    "mean_gyro",
+    "duration_ms",
+    "vertical_acc",
+    "vertical_ratio",
+    "sensor_x_ratio",
+    "sensor_y_ratio",
+    "sensor_z_ratio",
+    "peak_jerk",
+    "rotation_deg",
+    "gyro_x_ratio",
+    "gyro_y_ratio",
+    "gyro_z_ratio",
+];
+
+// Emparejamiento de golpes (por su inicio) con etiquetas del operador (la etiqueta suele llegar después)
+const LABEL_BEFORE_TRIGGER_MS: u64 = 250;
+const LABEL_AFTER_TRIGGER_MS: u64 = 2_000;
+
+// Descenso de gradiente
+const EPOCHS: usize = 500;
+const LEARNING_RATE: f32 = 0.5;
+const L2_PENALTY: f32 = 0.001;
+const MIN_TRAINING_STRIKES: usize = 10;
+
+/// Calcula las características de un golpe a partir de su ventana de muestras
+pub fn extract_features(samples: &[MotionSample]) -> Option<[f32; FEATURE_NAMES.len()]> {
+    let magnitudes: Vec<f32> = samples.iter().map(|s| norm(s.linear_acc)).collect();
+    let (peak_index, &peak_acc) = magnitudes.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+    let peak = &samples[peak_index];
+
+    let gyro_magnitudes: Vec<f32> = samples.iter().map(|s| norm(s.gyro)).collect();
+    let peak_gyro = gyro_magnitudes.iter().copied().fold(0.0, f32::max);
+    let count = samples.len() as f32;
+
+    // Duración por encima de la mitad del pico
+    let above_half: Vec<u64> = samples.iter().zip(&magnitudes)
+        .filter(|&(_, &magnitude)| magnitude >= peak_acc / 2.0)
+        .map(|(sample, _)| sample.timestamp)
+        .collect();
+    // La hora estimada puede retroceder al reajustar la sincronización de reloj
+    let duration_ms = match (above_half.iter().min(), above_half.iter().max()) {
+        (Some(first), Some(last)) => (last - first) as f32,
+        _ => 0.0,
+    };
+
+    let sensor = peak.sensor_linear_acc.map(f32::abs);
+    let sensor_sum = (sensor[0] + sensor[1] + sensor[2]).max(f32::EPSILON);
+
+    // Jerk máximo y rotación acumulada
+    let mut peak_jerk = 0.0f32;
+    let mut rotation_deg = 0.0f32;
+    let mut gyro_axes = [0.0f32; 3];
+    for (index, pair) in samples.windows(2).enumerate() {
+        let dt = (pair[1].timestamp.saturating_sub(pair[0].timestamp) as f32 / 1000.0).max(0.001);
+        peak_jerk = peak_jerk.max((magnitudes[index + 1] - magnitudes[index]).abs() / dt);
+        rotation_deg += gyro_magnitudes[index + 1] * dt;
+        for (total, value) in gyro_axes.iter_mut().zip(pair[1].gyro) {
+            *total += value.abs() * dt;
+        }
+    }
+    let gyro_sum = (gyro_axes[0] + gyro_axes[1] + gyro_axes[2]).max(f32::EPSILON);
+
+    Some([
+        peak_acc,
+        magnitudes.iter().sum::<f32>() / count,
+        peak_gyro,
+        gyro_magnitudes.iter().sum::<f32>() / count,
+        duration_ms,
+        peak.linear_acc[2],
+        if peak_acc > 0.0 { peak.linear_acc[2].abs() / peak_acc } else { 0.0 },
+        sensor[0] / sensor_sum,
+        sensor[1] / sensor_sum,
+        sensor[2] / sensor_sum,
+        peak_jerk,
+        rotation_deg,
+        gyro_axes[0] / gyro_sum,
+        gyro_axes[1] / gyro_sum,
+        gyro_axes[2] / gyro_sum,
+    ])
+}
+
+/// Modelo de un grupo de extremidades (regresión logística multinomial)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LimbModel {
+    pub limb_group: LimbGroup,
+    pub classes: Vec<String>,
+    pub means: Vec<f32>,        // Estandarización por característica
+    pub stds: Vec<f32>,
+    pub weights: Vec<Vec<f32>>, // Por clase: [sesgo, w_1 .. w_n]
+}
+
+impl LimbModel {
+    /// Probabilidad por clase
+    fn predict(&self, features: &[f32]) -> Vec<f32> {
+        let standardized: Vec<f32> = features.iter().zip(self.means.iter().zip(&self.stds))
+            .map(|(value, (mean, std))| (value - mean) / std)
+            .collect();
+        softmax(&self.weights, &standardized)
+    }
+}
+
+/// Modelo entrenado exportable
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TrainedStrikeModel {
+    pub model_type: String, // "softmax_regression"
+    pub feature_names: Vec<String>,
+    pub pre_onset_ms: u64,
+    pub trained_at: u64,
+    pub limb_models: Vec<LimbModel>,
+}
+
+impl TrainedStrikeModel {
+    /// Clasifica una ventana; None si el modelo no cubre esa extremidad
+    pub fn classify(&self, limb_type: LimbType, samples: &[MotionSample], min_confidence: f32) -> Option<StrikeClassification> {
+        let limb_model = self.limb_models.iter().find(|model| model.limb_group == LimbGroup::of(limb_type))?;
+        let features = extract_features(samples)?;
+        let probabilities = limb_model.predict(&features);
+
+        let (index, &confidence) = probabilities.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+        let class = if confidence >= min_confidence { limb_model.classes[index].clone() } else { UNKNOWN_STRIKE.to_string() };
+        Some(StrikeClassification { class, confidence })
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.feature_names.iter().map(String::as_str).ne(FEATURE_NAMES) {
+            return Err("El modelo usa otro conjunto de características".to_string());
+        }
+        for model in &self.limb_models {
+            let features = FEATURE_NAMES.len();
+            if model.means.len() != features || model.stds.len() != features
+                || model.weights.len() != model.classes.len()
+                || model.weights.iter().any(|w| w.len() != features + 1) {
+                return Err(format!("Dimensiones inválidas en el modelo de {:?}", model.limb_group));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Golpe etiquetado listo para entrenar
+struct LabeledStrike {
+    limb_group: LimbGroup,
+    features: [f32; FEATURE_NAMES.len()],
+    label: String,
+}
+
+/// Resumen del entrenamiento
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TrainingReport {
+    pub strikes: usize,                         // Disparos encontrados al reproducir las grabaciones
+    pub labels_matched: usize,
+    pub labels_unmatched: usize,                // Etiquetas sin disparo cercano
+    pub class_counts: BTreeMap<String, usize>,
+    pub accuracy: BTreeMap<String, f32>,        // Exactitud de entrenamiento por grupo
+}
+
+/// Reproduce las grabaciones con el mismo disparo que el detector y empareja etiquetas
+fn build_dataset(recordings: &[Recording], detection: &SimpleDetectionConfig, pre_onset_ms: u64) -> (Vec<LabeledStrike>, usize) {
+    let mut strikes = Vec::new();
+    let mut unmatched_labels = 0;
+
+    for recording in recordings {
+        let mut window = StrikeWindow::new();
+        let mut tracker = StrikeTracker::new();
+        let mut triggers = Vec::new();
+
+        for sample in &recording.samples {
+            window.push(sample.motion);
+            let Some(limb_type) = LimbType::from_id(sample.limb_id) else {
+                continue;
+            };
+
+            let trigger_acc = match LimbGroup::of(limb_type) {
+                LimbGroup::Hand => detection.hand_trigger_acc,
+                LimbGroup::Foot => detection.foot_trigger_acc,
+            };
+            let acc_magnitude = norm(sample.motion.linear_acc);
+            let Some(span) = tracker.update(sample.motion.timestamp, acc_magnitude, trigger_acc, &detection.refractory) else {
+                continue;
+            };
+
+            if let Some(features) = extract_features(&window.since(span.started_at.saturating_sub(pre_onset_ms))) {
+                triggers.push((span.started_at, LimbGroup::of(limb_type), features));
+            }
+        }
+
+        // Cada etiqueta se asigna al disparo más cercano que la preceda dentro del margen
+        let mut used = vec![false; recording.labels.len()];
+        for (timestamp, limb_group, features) in triggers {
+            let label = recording.labels.iter().enumerate()
+                .filter(|(index, (label_time, _))| {
+                    !used[*index]
+                        && *label_time + LABEL_BEFORE_TRIGGER_MS >= timestamp
+                        && *label_time <= timestamp + LABEL_AFTER_TRIGGER_MS
+                })
+                .min_by_key(|(_, (label_time, _))| label_time.abs_diff(timestamp));
+
+            let label = match label {
+                Some((index, (_, strike_type))) => {
+                    used[index] = true;
+                    strike_type.clone()
+                }
+                None => UNKNOWN_STRIKE.to_string(), // Movimiento sin etiquetar
+            };
+            strikes.push(LabeledStrike { limb_group, features, label });
+        }
+        unmatched_labels += used.iter().filter(|used| !**used).count();
+    }
+
+    (strikes, unmatched_labels)
+}
+
+/// Entrena un modelo con las grabaciones indicadas
+pub fn train_model(recording_paths: &[&Path], pre_onset_ms: u64) -> Result<(TrainedStrikeModel, TrainingReport), String> {
+    let recordings = recording_paths.iter()
+        .map(|path| crate::imu_recording::load_recording(path))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let (strikes, labels_unmatched) = build_dataset(&recordings, &SimpleDetectionConfig::default(), pre_onset_ms);
+    if strikes.len() < MIN_TRAINING_STRIKES {
+        return Err(format!("Se necesitan al menos {} golpes para entrenar ({} encontrados)",
+                           MIN_TRAINING_STRIKES, strikes.len()));
+    }
+
+    let mut report = TrainingReport {
+        strikes: strikes.len(),
+        labels_matched: strikes.iter().filter(|s| s.label != UNKNOWN_STRIKE).count(),
+        labels_unmatched,
+        class_counts: BTreeMap::new(),
+        accuracy: BTreeMap::new(),
+    };
+    for strike in &strikes {
+        *report.class_counts.entry(strike.label.clone()).or_default() += 1;
+    }
+
+    let mut limb_models = Vec::new();
+    for limb_group in [LimbGroup::Hand, LimbGroup::Foot] {
+        let group: Vec<&LabeledStrike> = strikes.iter().filter(|s| s.limb_group == limb_group).collect();
+        let Some((model, accuracy)) = train_limb_model(limb_group, &group) else {
+            continue;
+        };
+        report.accuracy.insert(format!("{:?}", limb_group).to_lowercase(), accuracy);
+        limb_models.push(model);
+    }
+
+    if limb_models.is_empty() {
+        return Err("Las grabaciones necesitan al menos dos clases distintas por extremidad".to_string());
+    }
+
+    let model = TrainedStrikeModel {
+        model_type: "softmax_regression".to_string(),
+        feature_names: FEATURE_NAMES.iter().map(|name| name.to_string()).collect(),
+        pre_onset_ms,
+        trained_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        limb_models,
+    };
+    Ok((model, report))
+}
+
+// Descenso de gradiente por lotes sobre la entropía cruzada con regularización L2
+fn train_limb_model(limb_group: LimbGroup, strikes: &[&LabeledStrike]) -> Option<(LimbModel, f32)> {
+    let mut classes: Vec<String> = strikes.iter().map(|s| s.label.clone()).collect();
+    classes.sort();
+    classes.dedup();
+    if classes.len() < 2 {
+        return None;
+    }
+
+    let feature_count = FEATURE_NAMES.len();
+    let n = strikes.len() as f32;
+    let means: Vec<f32> = (0..feature_count)
+        .map(|i| strikes.iter().map(|s| s.features[i]).sum::<f32>() / n)
+        .collect();
+    let stds: Vec<f32> = (0..feature_count)
+        .map(|i| {
+            let variance = strikes.iter().map(|s| (s.features[i] - means[i]).powi(2)).sum::<f32>() / n;
+            variance.sqrt().max(1e-6)
+        })
+        .collect();
+
+    let class_index: HashMap<&str, usize> = classes.iter().enumerate().map(|(i, c)| (c.as_str(), i)).collect();
+    let data: Vec<(Vec<f32>, usize)> = strikes.iter()
+        .map(|s| {
+            let standardized = (0..feature_count).map(|i| (s.features[i] - means[i]) / stds[i]).collect();
+            (standardized, class_index[s.label.as_str()])
+        })
+        .collect();
+
+    let mut weights = vec![vec![0.0f32; feature_count + 1]; classes.len()];
+    for _ in 0..EPOCHS {
+        let mut gradient = vec![vec![0.0f32; feature_count + 1]; classes.len()];
+        for (features, target) in &data {
+            let probabilities = softmax(&weights, features);
+            for (class, probability) in probabilities.iter().enumerate() {
+                let error = probability - if class == *target { 1.0 } else { 0.0 };
+                gradient[class][0] += error;
+                for (i, value) in features.iter().enumerate() {
+                    gradient[class][i + 1] += error * value;
+                }
+            }
+        }
+
+        for (class_weights, class_gradient) in weights.iter_mut().zip(&gradient) {
+            for (i, (weight, grad)) in class_weights.iter_mut().zip(class_gradient).enumerate() {
+                let penalty = if i == 0 { 0.0 } else { L2_PENALTY * *weight };
+                *weight -= LEARNING_RATE * (grad / n + penalty);
+            }
+        }
+    }
+
+    let correct = data.iter()
+        .filter(|(features, target)| {
+            let probabilities = softmax(&weights, features);
+            probabilities.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).map(|(i, _)| i) == Some(*target)
+        })
+        .count();
+
+    Some((LimbModel { limb_group, classes, means, stds, weights }, correct as f32 / n))
+}
+
+fn softmax(weights: &[Vec<f32>], features: &[f32]) -> Vec<f32> {
+    let scores: Vec<f32> = weights.iter()
+        .map(|w| w[0] + w[1..].iter().zip(features).map(|(a, b)| a * b).sum::<f32>())
+        .collect();
+    let max = scores.iter().copied().fold(f32::MIN, f32::max);
+    let exps: Vec<f32> = scores.iter().map(|score| (score - max).exp()).collect();
+    let total: f32 = exps.iter().sum();
+    exps.iter().map(|e| e / total).collect()
+}
+
+fn norm(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+// Modelo entrenado activo (None = reglas de strike_classifier)
+static ACTIVE_MODEL: Lazy<RwLock<Option<TrainedStrikeModel>>> = Lazy::new(|| RwLock::new(None));
+
+/// Clasifica con el modelo entrenado activo, si lo hay
+pub fn classify_with_model(
+    limb_type: LimbType,
+    window: &StrikeWindow,
+    span: &StrikeSpan,
+    min_confidence: f32,
+) -> Option<StrikeClassification> {
+    let model = ACTIVE_MODEL.read().unwrap();
+    let model = model.as_ref()?;
+    model.classify(limb_type, &window.since(span.started_at.saturating_sub(model.pre_onset_ms)), min_confidence)
+}
+
+/// Exporta un modelo a un archivo JSON
+pub fn export_model(model: &TrainedStrikeModel, path: &Path) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(model)
+        .map_err(|e| format!("Error serializando modelo: {}", e))?;
+    std::fs::write(path, contents)
+        .map_err(|e| format!("Error exportando modelo a {}: {}", path.display(), e))
+}
+
+/// Lee un modelo exportado
+pub fn read_model(path: &Path) -> Result<TrainedStrikeModel, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Error leyendo modelo {}: {}", path.display(), e))?;
+    let model: TrainedStrikeModel = serde_json::from_str(&contents)
+        .map_err(|e| format!("Modelo inválido: {}", e))?;
+    model.validate()?;
+    Ok(model)
+}
+
+/// Activa un modelo para el detector y lo guarda para próximos inicios
+pub fn activate_model<R: tauri::Runtime>(app_handle: &AppHandle<R>, model: TrainedStrikeModel) -> Result<(), String> {
+    storage::save_json(app_handle, ACTIVE_MODEL_FILE, &model)?;
+    info!(limb_models = model.limb_models.len(), "🧠 Modelo de golpes activado");
+    *ACTIVE_MODEL.write().unwrap() = Some(model);
+    Ok(())
+}
+
+/// Vuelve a las reglas configuradas
+pub fn clear_model<R: tauri::Runtime>(app_handle: &AppHandle<R>) -> Result<(), String> {
+    *ACTIVE_MODEL.write().unwrap() = None;
+    let path = storage::data_file_path(app_handle, ACTIVE_MODEL_FILE)?;
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("Error eliminando modelo: {}", e))?;
+    }
+    Ok(())
+}
+
+pub fn active_model() -> Option<TrainedStrikeModel> {
+    ACTIVE_MODEL.read().unwrap().clone()
+}
+
+/// Carga el modelo activo guardado al iniciar la aplicación
+pub fn load_active_model<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(model) = storage::load_json::<TrainedStrikeModel, R>(app_handle, ACTIVE_MODEL_FILE) else {
+        return;
+    };
+
+    match model.validate() {
+        Ok(()) => {
+            info!(limb_models = model.limb_models.len(), "🧠 Modelo de golpes cargado");
+            *ACTIVE_MODEL.write().unwrap() = Some(model);
+        }
+        Err(e) => error!(error = %e, "❌ Modelo de golpes guardado inválido"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64, acc: f32) -> MotionSample {
+        MotionSample {
+            timestamp,
+            linear_acc: [acc, 0.0, 0.0],
+            sensor_linear_acc: [acc, 0.0, 0.0],
+            gyro: [0.0, 0.0, 100.0],
+        }
+    }
+
+    #[test]
+    fn duration_survives_clock_stepping_backwards() {
+        // El reloj retrocede 3 ms a mitad del golpe
+        let samples = [sample(100, 0.1), sample(105, 4.0), sample(110, 8.0), sample(107, 5.0), sample(112, 0.2)];
+        let features = extract_features(&samples).unwrap();
+        let duration = FEATURE_NAMES.iter().position(|&name| name == "duration_ms").unwrap();
+        assert_eq!(features[duration], 5.0);
+    }
+}
@@ -0,0 +1,119 @@
+// Máquina de estados por dispositivo que sigue cada golpe (reposo, en golpe, recuperación)
+// Sustituye al cooldown fijo: se rearma cuando la señal vuelve a la línea base
+
+// Salto hacia atrás del reloj que se interpreta como reinicio (ms)
+const CLOCK_RESET_THRESHOLD_MS: u64 = 1_000;
+
+/// Parámetros de la lógica refractaria
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RefractoryConfig {
+    pub release_ratio: f32,     // El golpe termina cuando la aceleración baja de disparo x ratio
+    pub rearm_hold_ms: u64,     // Tiempo bajo la línea base antes de aceptar otro golpe
+    pub min_strike_gap_ms: u64, // Separación mínima entre inicios de golpes
+    pub max_strike_ms: u64,     // Un golpe más largo se cierra igualmente
+}
+
+impl Default for RefractoryConfig {
+    fn default() -> Self {
+        Self {
+            release_ratio: 0.5,
+            rearm_hold_ms: 30,
+            min_strike_gap_ms: 120,
+            max_strike_ms: 400,
+        }
+    }
+}
+
+/// Golpe completo detectado por la máquina de estados
+#[derive(Debug, Clone, Copy)]
+pub struct StrikeSpan {
+    pub started_at: u64, // ms
+    pub ended_at: u64,
+    pub peak_acc: f32,   // g
+}
+
+impl StrikeSpan {
+    pub fn duration_ms(&self) -> u64 {
+        self.ended_at.saturating_sub(self.started_at)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StrikeState {
+    Idle,
+    InStrike { started_at: u64, peak_acc: f32 },
+    Recovering { below_since: Option<u64> },
+}
+
+#[derive(Debug, Clone)]
+pub struct StrikeTracker {
+    state: StrikeState,
+    last_timestamp: Option<u64>,
+    last_strike_start: Option<u64>,
+}
+
+impl Default for StrikeTracker {
+    fn default() -> Self {
+        Self {
+            state: StrikeState::Idle,
+            last_timestamp: None,
+            last_strike_start: None,
+        }
+    }
+}
+
+impl StrikeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Procesa una muestra; devuelve el golpe cuando termina
+    /// Toda la aritmética de tiempo es saturada: un reloj que retrocede no puede provocar pánico
+    pub fn update(&mut self, timestamp: u64, acc_magnitude: f32, trigger_acc: f32, config: &RefractoryConfig) -> Option<StrikeSpan> {
+        // Reloj reiniciado: descartar el estado anterior
+        if self.last_timestamp.is_some_and(|last| last.saturating_sub(timestamp) > CLOCK_RESET_THRESHOLD_MS) {
+            *self = Self::default();
+        }
+        self.last_timestamp = Some(timestamp);
+
+        let release_acc = trigger_acc * config.release_ratio;
+
+        match self.state {
+            StrikeState::Idle => {
+                let gap_ok = self.last_strike_start
+                    .is_none_or(|start| timestamp.saturating_sub(start) >= config.min_strike_gap_ms);
+                if acc_magnitude >= trigger_acc && gap_ok {
+                    self.state = StrikeState::InStrike { started_at: timestamp, peak_acc: acc_magnitude };
+                    self.last_strike_start = Some(timestamp);
+                }
+                None
+            }
+            StrikeState::InStrike { started_at, peak_acc } => {
+                let peak_acc = peak_acc.max(acc_magnitude);
+
+                let released = acc_magnitude < release_acc;
+                if released || timestamp.saturating_sub(started_at) >= config.max_strike_ms {
+                    self.state = StrikeState::Recovering { below_since: released.then_some(timestamp) };
+                    return Some(StrikeSpan { started_at, ended_at: timestamp, peak_acc });
+                }
+
+                self.state = StrikeState::InStrike { started_at, peak_acc };
+                None
+            }
+            StrikeState::Recovering { below_since } => {
+                if acc_magnitude >= release_acc {
+                    self.state = StrikeState::Recovering { below_since: None };
+                    return None;
+                }
+
+                let below_since = below_since.unwrap_or(timestamp);
+                self.state = if timestamp.saturating_sub(below_since) >= config.rearm_hold_ms {
+                    StrikeState::Idle
+                } else {
+                    StrikeState::Recovering { below_since: Some(below_since) }
+                };
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,749 @@
+// Torneos: divisiones con sus competidores y cuadros de eliminación directa o todos contra todos
+// Cada pelea del cuadro se disputa como una sesión de combate (scoring.rs); al terminar, el ganador avanza
+// El cuadro se publica por WebSocket para la vista del proyector
+
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use tauri::{AppHandle, Emitter};
+use tracing::{error, info, warn};
+
+use crate::broadcast_ws::ws_broadcast;
+use crate::roster;
+use crate::scoring::{self, ScoreBoard};
+use crate::storage;
+
+const TOURNAMENTS_FILE: &str = "tournaments.json";
+// Peleadores de la sesión: la esquina roja conecta como competidor 1 y la azul como competidor 2
+const RED_FIGHTER: &str = "fighter_1";
+const BLUE_FIGHTER: &str = "fighter_2";
+// Puntos de clasificación en todos contra todos
+const WIN_POINTS: u32 = 3;
+const DRAW_POINTS: u32 = 1;
+
+/// Formato del cuadro
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BracketFormat {
+    SingleElimination,
+    RoundRobin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Corner {
+    Red,
+    Blue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStatus {
+    Pending,    // Faltan competidores de peleas anteriores
+    Ready,
+    InProgress,
+    Finished,
+    Bye,        // Pase directo por falta de rival
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivisionStatus {
+    Setup,      // Sin cuadro generado
+    InProgress,
+    Finished,
+}
+
+/// Pelea del cuadro (los competidores son ids de la plantilla)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BracketMatch {
+    pub id: String,
+    pub round: u32,
+    pub red: Option<String>,
+    pub blue: Option<String>,
+    pub status: MatchStatus,
+    pub winner: Option<String>,
+    pub session_id: Option<String>,
+    pub next_match: Option<String>, // Eliminación directa: pelea a la que pasa el ganador
+    pub next_corner: Option<Corner>,
+}
+
+/// Clasificación de todos contra todos
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Standing {
+    pub roster_id: String,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub points: u32,
+}
+
+/// División del torneo
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Division {
+    pub id: String,
+    pub name: String,
+    pub format: BracketFormat,
+    pub rule_set: Option<String>,
+    pub weight_class: Option<String>,
+    pub entrants: Vec<String>,                   // En orden de cabeza de serie
+    pub entrant_names: BTreeMap<String, String>, // roster_id -> nombre (para el proyector)
+    pub status: DivisionStatus,
+    pub matches: Vec<BracketMatch>,
+    #[serde(default)]
+    pub standings: Vec<Standing>, // Solo todos contra todos
+    pub champion: Option<String>,
+}
+
+/// Torneo
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Tournament {
+    pub id: String,
+    pub name: String,
+    pub created_at: u64,
+    pub divisions: Vec<Division>,
+}
+
+static TOURNAMENTS: Lazy<RwLock<BTreeMap<String, Tournament>>> = Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+impl Division {
+    fn find_match(&mut self, match_id: &str) -> Result<&mut BracketMatch, String> {
+        self.matches.iter_mut().find(|bracket_match| bracket_match.id == match_id)
+            .ok_or_else(|| format!("No existe la pelea {} en {}", match_id, self.name))
+    }
+
+    // Clasificación de todos contra todos
+    fn compute_standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self.entrants.iter()
+            .map(|roster_id| Standing { roster_id: roster_id.clone(), wins: 0, draws: 0, losses: 0, points: 0 })
+            .collect();
+        let finished = self.matches.iter().filter(|bracket_match| bracket_match.status == MatchStatus::Finished);
+        for bracket_match in finished {
+            for standing in standings.iter_mut() {
+                let id = Some(&standing.roster_id);
+                if bracket_match.red.as_ref() != id && bracket_match.blue.as_ref() != id {
+                    continue;
+                }
+                match &bracket_match.winner {
+                    None => standing.draws += 1,
+                    Some(winner) if winner == &standing.roster_id => standing.wins += 1,
+                    Some(_) => standing.losses += 1,
+                }
+            }
+        }
+        for standing in standings.iter_mut() {
+            standing.points = standing.wins * WIN_POINTS + standing.draws * DRAW_POINTS;
+        }
+        standings.sort_by(|a, b| b.points.cmp(&a.points).then(b.wins.cmp(&a.wins)));
+        standings
+    }
+
+    // Lleva los ganadores a su siguiente pelea y actualiza el estado de la división
+    fn advance(&mut self) {
+        loop {
+            let pending = self.matches.iter()
+                .filter(|bracket_match| matches!(bracket_match.status, MatchStatus::Finished | MatchStatus::Bye))
+                .find_map(|bracket_match| {
+                    let winner = bracket_match.winner.clone()?;
+                    let next = self.matches.iter().find(|next| Some(&next.id) == bracket_match.next_match.as_ref())?;
+                    let corner = bracket_match.next_corner?;
+                    let slot = match corner {
+                        Corner::Red => &next.red,
+                        Corner::Blue => &next.blue,
+                    };
+                    slot.is_none().then(|| (next.id.clone(), corner, winner))
+                });
+            let Some((next_id, corner, winner)) = pending else {
+                break;
+            };
+            let Some(next) = self.matches.iter_mut().find(|next| next.id == next_id) else {
+                break;
+            };
+            match corner {
+                Corner::Red => next.red = Some(winner),
+                Corner::Blue => next.blue = Some(winner),
+            }
+            if next.red.is_some() && next.blue.is_some() && next.status == MatchStatus::Pending {
+                next.status = MatchStatus::Ready;
+            }
+        }
+
+        let all_done = self.matches.iter()
+            .all(|bracket_match| matches!(bracket_match.status, MatchStatus::Finished | MatchStatus::Bye));
+        if self.format == BracketFormat::RoundRobin {
+            self.standings = self.compute_standings();
+        }
+        self.champion = match self.format {
+            BracketFormat::SingleElimination => self.matches.iter()
+                .find(|bracket_match| bracket_match.next_match.is_none())
+                .and_then(|final_match| final_match.winner.clone()),
+            // Empate a puntos en cabeza: sin campeón hasta que se decida
+            BracketFormat::RoundRobin if all_done => match self.standings.as_slice() {
+                [first, second, ..] if first.points == second.points => None,
+                [first, ..] => Some(first.roster_id.clone()),
+                [] => None,
+            },
+            BracketFormat::RoundRobin => None,
+        };
+
+        let status = if all_done { DivisionStatus::Finished } else { DivisionStatus::InProgress };
+        if status == DivisionStatus::Finished && self.status != status {
+            info!(division = %self.name, champion = ?self.champion, "🏆 División terminada");
+        }
+        self.status = status;
+    }
+}
+
+// Orden de cabezas de serie en la primera ronda (1 contra el último, 2 contra el penúltimo, ...)
+fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let slots = order.len() * 2;
+        order = order.iter().flat_map(|&seed| [seed, slots - 1 - seed]).collect();
+    }
+    order
+}
+
+fn single_elimination(entrants: &[String]) -> Vec<BracketMatch> {
+    let size = entrants.len().next_power_of_two();
+    let rounds = size.trailing_zeros();
+    let order = seed_order(size);
+
+    let mut matches = Vec::new();
+    for round in 1..=rounds {
+        let count = size >> round;
+        for index in 0..count {
+            let (red, blue) = if round == 1 {
+                (entrants.get(order[2 * index]).cloned(), entrants.get(order[2 * index + 1]).cloned())
+            } else {
+                (None, None)
+            };
+            // Primera ronda sin rival: pasa directo
+            let (status, winner) = match (&red, &blue, round) {
+                (Some(_), Some(_), _) => (MatchStatus::Ready, None),
+                (Some(entrant), None, 1) | (None, Some(entrant), 1) => (MatchStatus::Bye, Some(entrant.clone())),
+                _ => (MatchStatus::Pending, None),
+            };
+            let is_final = round == rounds;
+            matches.push(BracketMatch {
+                id: format!("R{}-M{}", round, index + 1),
+                round,
+                red,
+                blue,
+                status,
+                winner,
+                session_id: None,
+                next_match: (!is_final).then(|| format!("R{}-M{}", round + 1, index / 2 + 1)),
+                next_corner: (!is_final).then_some(if index % 2 == 0 { Corner::Red } else { Corner::Blue }),
+            });
+        }
+    }
+    matches
+}
+
+// Calendario por el método del círculo: cada competidor pelea una vez por ronda (descansa uno si son impares)
+fn round_robin(entrants: &[String]) -> Vec<BracketMatch> {
+    let mut circle: Vec<Option<&String>> = entrants.iter().map(Some).collect();
+    if circle.len() % 2 == 1 {
+        circle.push(None);
+    }
+    let slots = circle.len();
+
+    let mut matches = Vec::new();
+    for round in 1..slots as u32 {
+        for index in 0..slots / 2 {
+            if let (Some(red), Some(blue)) = (circle[index], circle[slots - 1 - index]) {
+                matches.push(BracketMatch {
+                    id: format!("R{}-M{}", round, matches.iter().filter(|m: &&BracketMatch| m.round == round).count() + 1),
+                    round,
+                    red: Some(red.clone()),
+                    blue: Some(blue.clone()),
+                    status: MatchStatus::Ready,
+                    winner: None,
+                    session_id: None,
+                    next_match: None,
+                    next_corner: None,
+                });
+            }
+        }
+        // El primero queda fijo y el resto rota
+        circle[1..].rotate_right(1);
+    }
+    matches
+}
+
+fn save<R: tauri::Runtime>(app_handle: &AppHandle<R>, tournaments: &BTreeMap<String, Tournament>) -> Result<(), String> {
+    storage::save_json(app_handle, TOURNAMENTS_FILE, tournaments)
+}
+
+// Publica el torneo al frontend y por WebSocket (vista del proyector)
+fn publish<R: tauri::Runtime>(app_handle: &AppHandle<R>, tournament: &Tournament) {
+    if let Err(e) = app_handle.emit("bracket-update", tournament) {
+        error!(error = %e, "Error emitiendo cuadro del torneo");
+    }
+    ws_broadcast(&serde_json::json!({
+        "type": "bracket_update",
+        "data": tournament,
+        "timestamp": now_ms(),
+    }));
+}
+
+// Modifica un torneo, lo guarda y lo publica
+fn update_tournament<R, T, F>(app_handle: &AppHandle<R>, tournament_id: &str, update: F) -> Result<T, String>
+where
+    R: tauri::Runtime,
+    F: FnOnce(&mut Tournament) -> Result<T, String>,
+{
+    let mut tournaments = TOURNAMENTS.write().unwrap();
+    let tournament = tournaments.get_mut(tournament_id)
+        .ok_or_else(|| format!("No existe el torneo {}", tournament_id))?;
+    let value = update(tournament)?;
+    let snapshot = tournament.clone();
+    save(app_handle, &tournaments)?;
+    drop(tournaments);
+
+    publish(app_handle, &snapshot);
+    Ok(value)
+}
+
+fn find_division<'a>(tournament: &'a mut Tournament, division_id: &str) -> Result<&'a mut Division, String> {
+    tournament.divisions.iter_mut().find(|division| division.id == division_id)
+        .ok_or_else(|| format!("No existe la división {}", division_id))
+}
+
+/// Carga los torneos guardados al iniciar la aplicación
+pub fn load_tournaments<R: tauri::Runtime>(app_handle: &AppHandle<R>) {
+    let Some(saved) = storage::load_json::<BTreeMap<String, Tournament>, R>(app_handle, TOURNAMENTS_FILE) else {
+        return;
+    };
+    info!(tournaments = saved.len(), "🏟️ Torneos cargados");
+    *TOURNAMENTS.write().unwrap() = saved;
+}
+
+/// Torneos guardados
+pub fn list_tournaments() -> Vec<Tournament> {
+    TOURNAMENTS.read().unwrap().values().cloned().collect()
+}
+
+/// Torneo por id
+pub fn get_tournament(tournament_id: &str) -> Option<Tournament> {
+    TOURNAMENTS.read().unwrap().get(tournament_id).cloned()
+}
+
+/// Crea un torneo vacío
+pub fn create_tournament<R: tauri::Runtime>(app_handle: &AppHandle<R>, name: &str) -> Result<Tournament, String> {
+    if name.trim().is_empty() {
+        return Err("El torneo necesita un nombre".to_string());
+    }
+    let created_at = now_ms();
+    let tournament = Tournament {
+        id: format!("tournament_{}", created_at),
+        name: name.trim().to_string(),
+        created_at,
+        divisions: Vec::new(),
+    };
+
+    let mut tournaments = TOURNAMENTS.write().unwrap();
+    if tournaments.contains_key(&tournament.id) {
+        return Err("Ya existe un torneo con ese identificador, inténtalo de nuevo".to_string());
+    }
+    tournaments.insert(tournament.id.clone(), tournament.clone());
+    save(app_handle, &tournaments)?;
+    info!(tournament_id = %tournament.id, name = %tournament.name, "🏟️ Torneo creado");
+    Ok(tournament)
+}
+
+/// Elimina un torneo
+pub fn delete_tournament<R: tauri::Runtime>(app_handle: &AppHandle<R>, tournament_id: &str) -> Result<(), String> {
+    let mut tournaments = TOURNAMENTS.write().unwrap();
+    tournaments.remove(tournament_id)
+        .ok_or_else(|| format!("No existe el torneo {}", tournament_id))?;
+    save(app_handle, &tournaments)?;
+    info!(tournament_id = %tournament_id, "🗑️ Torneo eliminado");
+    Ok(())
+}
+
+/// Añade una división con sus competidores (en orden de cabeza de serie)
+pub fn add_division<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    tournament_id: &str,
+    name: &str,
+    format: BracketFormat,
+    rule_set: Option<String>,
+    weight_class: Option<String>,
+    entrants: Vec<String>,
+) -> Result<Tournament, String> {
+    if name.trim().is_empty() {
+        return Err("La división necesita un nombre".to_string());
+    }
+    if entrants.len() < 2 {
+        return Err("La división necesita al menos dos competidores".to_string());
+    }
+    let mut entrant_names = BTreeMap::new();
+    for roster_id in &entrants {
+        let entry = roster::get_competitor(roster_id)
+            .ok_or_else(|| format!("No existe el competidor {} en la plantilla", roster_id))?;
+        if let Some(weight_class) = &weight_class {
+            if entry.weight_class.as_ref() != Some(weight_class) {
+                return Err(format!("{} no está en la categoría {} ({:?})", entry.competitor.name, weight_class, entry.weight_class));
+            }
+        }
+        if entrant_names.insert(roster_id.clone(), entry.competitor.name).is_some() {
+            return Err(format!("{} está repetido en la división", roster_id));
+        }
+    }
+    if let Some(rule_set) = &rule_set {
+        if !scoring::rule_sets().iter().any(|rules| &rules.name == rule_set) {
+            return Err(format!("No existe el conjunto de reglas {}", rule_set));
+        }
+    }
+
+    update_tournament(app_handle, tournament_id, |tournament| {
+        let division = Division {
+            id: format!("division_{}", tournament.divisions.len() + 1),
+            name: name.trim().to_string(),
+            format,
+            rule_set,
+            weight_class,
+            entrants,
+            entrant_names,
+            status: DivisionStatus::Setup,
+            matches: Vec::new(),
+            standings: Vec::new(),
+            champion: None,
+        };
+        info!(tournament_id = %tournament.id, division = %division.name, format = ?format, entrants = division.entrants.len(), "📋 División añadida");
+        tournament.divisions.push(division);
+        Ok(tournament.clone())
+    })
+}
+
+/// Genera (o regenera, si aún no ha empezado ninguna pelea) el cuadro de una división
+pub fn generate_bracket<R: tauri::Runtime>(app_handle: &AppHandle<R>, tournament_id: &str, division_id: &str) -> Result<Tournament, String> {
+    update_tournament(app_handle, tournament_id, |tournament| {
+        let division = find_division(tournament, division_id)?;
+        let started = division.matches.iter()
+            .any(|bracket_match| matches!(bracket_match.status, MatchStatus::InProgress | MatchStatus::Finished));
+        if started {
+            return Err(format!("{} ya tiene peleas disputadas", division.name));
+        }
+
+        division.matches = match division.format {
+            BracketFormat::SingleElimination => single_elimination(&division.entrants),
+            BracketFormat::RoundRobin => round_robin(&division.entrants),
+        };
+        division.advance();
+        info!(division = %division.name, matches = division.matches.len(), "🗂️ Cuadro generado");
+        Ok(tournament.clone())
+    })
+}
+
+/// Empieza la sesión de combate de una pelea del cuadro
+/// La esquina roja es fighter_1 y la azul fighter_2 al conectar las bandas
+pub fn start_match<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    tournament_id: &str,
+    division_id: &str,
+    match_id: &str,
+) -> Result<ScoreBoard, String> {
+    // Sin bloquear los torneos mientras arranca la sesión (publica el marcador)
+    let (rule_set, fighters, roster_ids) = {
+        let mut tournaments = TOURNAMENTS.write().unwrap();
+        let tournament = tournaments.get_mut(tournament_id)
+            .ok_or_else(|| format!("No existe el torneo {}", tournament_id))?;
+        let division = find_division(tournament, division_id)?;
+        let names = division.entrant_names.clone();
+        let rule_set = division.rule_set.clone();
+        let bracket_match = division.find_match(match_id)?;
+        if bracket_match.status != MatchStatus::Ready {
+            return Err(format!("La pelea {} no está lista ({:?})", match_id, bracket_match.status));
+        }
+        let (Some(red), Some(blue)) = (&bracket_match.red, &bracket_match.blue) else {
+            return Err(format!("La pelea {} no tiene los dos competidores", match_id));
+        };
+        let name = |roster_id: &String| names.get(roster_id).cloned().unwrap_or_else(|| roster_id.clone());
+        let fighters = BTreeMap::from([
+            (RED_FIGHTER.to_string(), name(red)),
+            (BLUE_FIGHTER.to_string(), name(blue)),
+        ]);
+        let roster_ids = BTreeMap::from([
+            (RED_FIGHTER.to_string(), red.clone()),
+            (BLUE_FIGHTER.to_string(), blue.clone()),
+        ]);
+        (rule_set, fighters, roster_ids)
+    };
+
+    let scoreboard = scoring::start_session(app_handle, rule_set.as_deref(), fighters, roster_ids, None)?;
+
+    update_tournament(app_handle, tournament_id, |tournament| {
+        let bracket_match = find_division(tournament, division_id)?.find_match(match_id)?;
+        bracket_match.status = MatchStatus::InProgress;
+        bracket_match.session_id = Some(scoreboard.session_id.clone());
+        info!(match_id = %match_id, session_id = %scoreboard.session_id, "🥊 Pelea del cuadro iniciada");
+        Ok(())
+    })?;
+    Ok(scoreboard)
+}
+
+// Cierra una pelea con su ganador (None = empate) y avanza el cuadro
+// Todo se valida antes de tocar el cuadro: un error no deja la división a medias
+fn settle(division: &mut Division, match_id: &str, winner: Option<String>) -> Result<(), String> {
+    let bracket_match = division.find_match(match_id)?.clone();
+    if let Some(winner) = &winner {
+        if bracket_match.red.as_ref() != Some(winner) && bracket_match.blue.as_ref() != Some(winner) {
+            return Err(format!("{} no pelea en {}", winner, match_id));
+        }
+    }
+    if winner.is_none() && division.format == BracketFormat::SingleElimination {
+        return Err(format!("La pelea {} necesita un ganador para avanzar el cuadro", match_id));
+    }
+    if bracket_match.winner == winner && bracket_match.status == MatchStatus::Finished {
+        return Ok(());
+    }
+
+    // Cambiar un ganador ya avanzado solo si la siguiente pelea no ha empezado
+    let replaced = bracket_match.winner.clone().filter(|previous| Some(previous) != winner.as_ref());
+    let vacated = match (replaced, bracket_match.next_match.zip(bracket_match.next_corner)) {
+        (Some(previous), Some((next_id, corner))) => {
+            let next = division.find_match(&next_id)?;
+            if matches!(next.status, MatchStatus::InProgress | MatchStatus::Finished) {
+                return Err(format!("La pelea {} ya empezó con {}", next_id, previous));
+            }
+            Some((next_id, corner))
+        }
+        _ => None,
+    };
+
+    let bracket_match = division.find_match(match_id)?;
+    bracket_match.winner = winner.clone();
+    bracket_match.status = MatchStatus::Finished;
+    if let Some((next_id, corner)) = vacated {
+        let next = division.find_match(&next_id)?;
+        match corner {
+            Corner::Red => next.red = None,
+            Corner::Blue => next.blue = None,
+        }
+        next.status = MatchStatus::Pending;
+    }
+
+    info!(division = %division.name, match_id = %match_id, winner = ?winner, "✅ Pelea del cuadro resuelta");
+    division.advance();
+    Ok(())
+}
+
+/// Fija el ganador de una pelea (decisión del árbitro, empates en eliminación directa, descalificaciones)
+pub fn set_match_winner<R: tauri::Runtime>(
+    app_handle: &AppHandle<R>,
+    tournament_id: &str,
+    division_id: &str,
+    match_id: &str,
+    winner: Option<String>,
+) -> Result<Tournament, String> {
+    update_tournament(app_handle, tournament_id, |tournament| {
+        settle(find_division(tournament, division_id)?, match_id, winner)?;
+        Ok(tournament.clone())
+    })
+}
+
+// Pelea del cuadro que se disputó en una sesión: (torneo, división, pelea)
+fn session_match(session_id: &str) -> Option<(String, String, BracketMatch)> {
+    TOURNAMENTS.read().unwrap().values().find_map(|tournament| {
+        tournament.divisions.iter().find_map(|division| {
+            division.matches.iter()
+                .find(|bracket_match| bracket_match.session_id.as_deref() == Some(session_id))
+                .map(|bracket_match| (tournament.id.clone(), division.id.clone(), bracket_match.clone()))
+        })
+    })
+}
+
+/// Competidores de la plantilla de una sesión del cuadro (fighter_id -> roster_id)
+pub fn session_competitors(session_id: &str) -> BTreeMap<String, String> {
+    let Some((_, _, bracket_match)) = session_match(session_id) else {
+        return BTreeMap::new();
+    };
+    [(RED_FIGHTER, bracket_match.red), (BLUE_FIGHTER, bracket_match.blue)].into_iter()
+        .filter_map(|(fighter_id, roster_id)| Some((fighter_id.to_string(), roster_id?)))
+        .collect()
+}
+
+/// Lleva al cuadro el resultado de una sesión terminada (lo llama el motor de puntuación)
+pub fn record_result<R: tauri::Runtime>(app_handle: &AppHandle<R>, scoreboard: &ScoreBoard) {
+    let Some((tournament_id, division_id, bracket_match)) = session_match(&scoreboard.session_id) else {
+        return;
+    };
+
+    let winner = match scoreboard.winner.as_deref() {
+        Some(RED_FIGHTER) => bracket_match.red.clone(),
+        Some(BLUE_FIGHTER) => bracket_match.blue.clone(),
+        _ => None,
+    };
+    let result = update_tournament(app_handle, &tournament_id, |tournament| {
+        let division = find_division(tournament, &division_id)?;
+        if winner.is_none() && division.format == BracketFormat::SingleElimination {
+            warn!(match_id = %bracket_match.id, "⚖️ Pelea sin ganador: decide con set_match_winner");
+            return Ok(());
+        }
+        settle(division, &bracket_match.id, winner)
+    });
+    if let Err(e) = result {
+        error!(match_id = %bracket_match.id, session_id = %scoreboard.session_id, error = %e, "❌ No se pudo llevar el resultado al cuadro");
+    }
+}
+
+/// Vuelve a publicar un torneo (al abrir la vista del proyector)
+pub fn broadcast_tournament<R: tauri::Runtime>(app_handle: &AppHandle<R>, tournament_id: &str) -> Result<(), String> {
+    let tournament = get_tournament(tournament_id)
+        .ok_or_else(|| format!("No existe el torneo {}", tournament_id))?;
+    publish(app_handle, &tournament);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrants(count: usize) -> Vec<String> {
+        (1..=count).map(|seed| format!("seed_{}", seed)).collect()
+    }
+
+    fn generated(format: BracketFormat, count: usize) -> Division {
+        let entrants = entrants(count);
+        let mut division = Division {
+            id: "division_1".to_string(),
+            name: "Prueba".to_string(),
+            format,
+            rule_set: None,
+            weight_class: None,
+            matches: match format {
+                BracketFormat::SingleElimination => single_elimination(&entrants),
+                BracketFormat::RoundRobin => round_robin(&entrants),
+            },
+            entrants,
+            entrant_names: BTreeMap::new(),
+            status: DivisionStatus::Setup,
+            standings: Vec::new(),
+            champion: None,
+        };
+        division.advance();
+        division
+    }
+
+    fn get<'a>(division: &'a Division, match_id: &str) -> &'a BracketMatch {
+        division.matches.iter().find(|bracket_match| bracket_match.id == match_id).unwrap()
+    }
+
+    #[test]
+    fn seed_order_pairs_top_seeds_against_bottom_seeds() {
+        assert_eq!(seed_order(1), vec![0]);
+        assert_eq!(seed_order(2), vec![0, 1]);
+        assert_eq!(seed_order(4), vec![0, 3, 1, 2]);
+        assert_eq!(seed_order(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+        for pair in seed_order(16).chunks(2) {
+            assert_eq!(pair[0] + pair[1], 15);
+        }
+    }
+
+    #[test]
+    fn byes_go_to_the_top_seeds() {
+        for count in [3, 5, 6] {
+            let matches = single_elimination(&entrants(count));
+            let first_round: Vec<&BracketMatch> = matches.iter().filter(|bracket_match| bracket_match.round == 1).collect();
+            let byes: Vec<&String> = first_round.iter()
+                .filter(|bracket_match| bracket_match.status == MatchStatus::Bye)
+                .filter_map(|bracket_match| bracket_match.winner.as_ref())
+                .collect();
+
+            assert_eq!(byes.len(), count.next_power_of_two() - count, "{} competidores", count);
+            assert!(byes.iter().all(|winner| entrants(byes.len()).contains(winner)), "{} competidores: {:?}", count, byes);
+            assert!(first_round.iter().all(|bracket_match| bracket_match.red.is_some() || bracket_match.blue.is_some()));
+            let seeded = first_round.iter().flat_map(|bracket_match| [&bracket_match.red, &bracket_match.blue]).flatten().count();
+            assert_eq!(seeded, count);
+        }
+    }
+
+    #[test]
+    fn byes_advance_when_the_bracket_is_generated() {
+        let division = generated(BracketFormat::SingleElimination, 5);
+        // 1 pasa directo y espera al ganador de 4-5; 2 y 3 pasan directo y se cruzan ya
+        let semifinal = get(&division, "R2-M1");
+        assert_eq!(semifinal.red.as_deref(), Some("seed_1"));
+        assert_eq!(semifinal.status, MatchStatus::Pending);
+        let semifinal = get(&division, "R2-M2");
+        assert_eq!((semifinal.red.as_deref(), semifinal.blue.as_deref()), (Some("seed_2"), Some("seed_3")));
+        assert_eq!(semifinal.status, MatchStatus::Ready);
+    }
+
+    #[test]
+    fn round_robin_with_odd_entrants_pairs_everyone_once() {
+        for count in [3, 5, 7] {
+            let matches = round_robin(&entrants(count));
+            assert_eq!(matches.len(), count * (count - 1) / 2);
+
+            let mut pairs: Vec<(String, String)> = matches.iter()
+                .map(|bracket_match| {
+                    let (red, blue) = (bracket_match.red.clone().unwrap(), bracket_match.blue.clone().unwrap());
+                    if red < blue { (red, blue) } else { (blue, red) }
+                })
+                .collect();
+            pairs.sort();
+            pairs.dedup();
+            assert_eq!(pairs.len(), matches.len(), "{} competidores: pelea repetida", count);
+
+            for round in 1..=count as u32 {
+                let fighters: Vec<&String> = matches.iter()
+                    .filter(|bracket_match| bracket_match.round == round)
+                    .flat_map(|bracket_match| [bracket_match.red.as_ref().unwrap(), bracket_match.blue.as_ref().unwrap()])
+                    .collect();
+                // Uno descansa en cada ronda
+                assert_eq!(fighters.len(), count - 1, "{} competidores, ronda {}", count, round);
+            }
+        }
+    }
+
+    #[test]
+    fn changing_a_winner_replaces_them_in_the_next_match() {
+        let mut division = generated(BracketFormat::SingleElimination, 4);
+        settle(&mut division, "R1-M1", Some("seed_1".to_string())).unwrap();
+        settle(&mut division, "R1-M2", Some("seed_2".to_string())).unwrap();
+        assert_eq!(get(&division, "R2-M1").status, MatchStatus::Ready);
+
+        settle(&mut division, "R1-M1", Some("seed_4".to_string())).unwrap();
+        let final_match = get(&division, "R2-M1");
+        assert_eq!(final_match.red.as_deref(), Some("seed_4"));
+        assert_eq!(final_match.blue.as_deref(), Some("seed_2"));
+        assert_eq!(final_match.status, MatchStatus::Ready);
+    }
+
+    #[test]
+    fn winner_cannot_change_once_the_next_match_started() {
+        let mut division = generated(BracketFormat::SingleElimination, 4);
+        settle(&mut division, "R1-M1", Some("seed_1".to_string())).unwrap();
+        settle(&mut division, "R1-M2", Some("seed_2".to_string())).unwrap();
+        division.find_match("R2-M1").unwrap().status = MatchStatus::InProgress;
+
+        assert!(settle(&mut division, "R1-M1", Some("seed_4".to_string())).is_err());
+        // El cuadro queda como estaba
+        assert_eq!(get(&division, "R1-M1").winner.as_deref(), Some("seed_1"));
+        assert_eq!(get(&division, "R2-M1").red.as_deref(), Some("seed_1"));
+        assert_eq!(get(&division, "R2-M1").status, MatchStatus::InProgress);
+    }
+
+    #[test]
+    fn settle_rejects_outsiders_and_elimination_draws() {
+        let mut division = generated(BracketFormat::SingleElimination, 4);
+        assert!(settle(&mut division, "R1-M1", Some("seed_2".to_string())).is_err());
+        assert!(settle(&mut division, "R1-M1", None).is_err());
+        assert_eq!(get(&division, "R1-M1").status, MatchStatus::Ready);
+
+        let mut division = generated(BracketFormat::RoundRobin, 3);
+        settle(&mut division, "R1-M1", None).unwrap();
+        assert_eq!(division.standings.iter().filter(|standing| standing.draws == 1).count(), 2);
+    }
+}
@@ -0,0 +1,93 @@
+// Estimación de velocidad integrando la aceleración lineal
+// La deriva se corrige reiniciando la velocidad en cada reposo detectado (ZUPT)
+
+use std::collections::VecDeque;
+
+const GRAVITY_MS2: f32 = 9.81;
+
+// Condiciones de reposo: aceleración lineal y giro casi nulos durante un tiempo mínimo
+const ZUPT_MAX_LINEAR_ACC_G: f32 = 0.12;
+const ZUPT_MAX_GYRO_DPS: f32 = 25.0;
+const ZUPT_MIN_DURATION_US: u64 = 80_000;
+
+// Más allá de este tiempo sin reposo la integración acumula demasiada deriva
+const MAX_INTEGRATION_US: u64 = 1_500_000;
+// Ventana del golpe en la que se busca la velocidad máxima
+const STRIKE_WINDOW_US: u64 = 300_000;
+
+const DEFAULT_DT_US: u64 = 5_000;
+const MAX_DT_US: u64 = 50_000;
+
+/// Método con el que se obtuvo la velocidad reportada en un evento
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VelocityMethod {
+    IntegratedZupt, // Integración desde el último reposo
+    Heuristic,      // Velocidad base por extremidad escalada por intensidad
+    Manual,         // Evento añadido por el árbitro, sin medición
+}
+
+/// Integrador por conexión
+#[derive(Debug, Default)]
+pub struct VelocityEstimator {
+    velocity: [f32; 3],           // m/s en marco mundial
+    last_sample_us: Option<u64>,
+    rest_started_us: Option<u64>, // Inicio del reposo actual (si lo hay)
+    last_rest_us: Option<u64>,    // Último instante confirmado en reposo
+    recent_speeds: VecDeque<(u64, f32)>, // (hora µs, rapidez) dentro de la ventana del golpe
+}
+
+impl VelocityEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Integra una muestra (aceleración lineal en g, giro en °/s) y devuelve la
+    /// velocidad máxima medida en la ventana reciente, si la integración es fiable
+    pub fn update(&mut self, linear_acc: [f32; 3], gyro: [f32; 3], sample_time_us: u64) -> Option<f32> {
+        let dt_us = match self.last_sample_us {
+            Some(last) if sample_time_us > last => (sample_time_us - last).min(MAX_DT_US),
+            _ => DEFAULT_DT_US,
+        };
+        self.last_sample_us = Some(sample_time_us);
+
+        // Detección de reposo (actualización de velocidad cero)
+        if norm(linear_acc) <= ZUPT_MAX_LINEAR_ACC_G && norm(gyro) <= ZUPT_MAX_GYRO_DPS {
+            let rest_started = *self.rest_started_us.get_or_insert(sample_time_us);
+            if sample_time_us.saturating_sub(rest_started) >= ZUPT_MIN_DURATION_US {
+                self.velocity = [0.0; 3];
+                self.last_rest_us = Some(sample_time_us);
+            }
+        } else {
+            self.rest_started_us = None;
+        }
+
+        let dt = dt_us as f32 / 1_000_000.0;
+        for (axis, acc) in self.velocity.iter_mut().zip(linear_acc) {
+            *axis += acc * GRAVITY_MS2 * dt;
+        }
+
+        self.recent_speeds.push_back((sample_time_us, norm(self.velocity)));
+        while let Some(&(time, _)) = self.recent_speeds.front() {
+            if sample_time_us.saturating_sub(time) <= STRIKE_WINDOW_US {
+                break;
+            }
+            self.recent_speeds.pop_front();
+        }
+
+        // Sin reposo previo reciente la velocidad no es confiable
+        let last_rest = self.last_rest_us?;
+        if sample_time_us.saturating_sub(last_rest) > MAX_INTEGRATION_US {
+            return None;
+        }
+
+        self.recent_speeds.iter()
+            .filter(|&&(time, _)| time >= last_rest)
+            .map(|&(_, speed)| speed)
+            .reduce(f32::max)
+    }
+}
+
+fn norm(v: [f32; 3]) -> f32 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
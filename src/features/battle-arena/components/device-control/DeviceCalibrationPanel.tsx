@@ -0,0 +1,219 @@
+import React, { useEffect } from 'react';
+import { Card, CardBody, Button, Chip } from '@heroui/react';
+import { Compass } from 'lucide-react';
+
+import { devErrorLog } from '@utils/devLog';
+import { useBLEStore } from '@stores/useBLEStore';
+import { useCalibrationStore } from '@stores/useCalibrationStore';
+import { CalibrationStatus, DeviceCalibration } from '../../types';
+
+// Fases en las que la calibración sigue en curso
+const ACTIVE_PHASES = [
+  'collecting_still',
+  'awaiting_orientation',
+  'collecting_orientation',
+];
+
+const PHASE_LABELS: Record<string, string> = {
+  collecting_still: 'Mantener la banda quieta',
+  awaiting_orientation: 'Girar la banda a la siguiente orientación',
+  collecting_orientation: 'Capturando orientación',
+  completed: 'Calibración completada',
+  failed: 'Calibración fallida',
+  cancelled: 'Calibración cancelada',
+};
+
+const formatVector = (vector: number[]) =>
+  vector.map(value => value.toFixed(2)).join(' / ');
+
+interface RowProps {
+  deviceId: string;
+  deviceName: string;
+  calibration?: DeviceCalibration;
+  status?: CalibrationStatus;
+}
+
+const CalibrationRow: React.FC<RowProps> = ({
+  deviceId,
+  deviceName,
+  calibration,
+  status,
+}) => {
+  const startCalibration = useCalibrationStore(state => state.startCalibration);
+  const captureOrientation = useCalibrationStore(
+    state => state.captureOrientation,
+  );
+  const cancelCalibration = useCalibrationStore(
+    state => state.cancelCalibration,
+  );
+  const clearCalibration = useCalibrationStore(state => state.clearCalibration);
+
+  const isActive = status !== undefined && ACTIVE_PHASES.includes(status.phase);
+
+  // El store ya registra el error; aquí solo se evita el rechazo sin capturar
+  const run = (action: () => Promise<void>) => () => {
+    action().catch(error => devErrorLog('❌ Acción de calibración:', error));
+  };
+
+  return (
+    <div className="rounded-lg border border-zinc-700/50 bg-zinc-900/40 p-4">
+      <div className="flex items-center justify-between gap-4">
+        <div>
+          <p className="font-semibold text-zinc-100">{deviceName}</p>
+          {calibration ? (
+            <p className="text-xs text-zinc-400">
+              Calibrada el{' '}
+              {new Date(calibration.calibrated_at).toLocaleString()}
+              {calibration.six_orientation ? ' (seis orientaciones)' : ''}
+            </p>
+          ) : (
+            <p className="text-xs text-yellow-400">Sin calibrar</p>
+          )}
+        </div>
+        <Chip
+          size="sm"
+          variant="flat"
+          color={calibration ? 'success' : 'warning'}
+        >
+          {calibration ? 'Calibrada' : 'Pendiente'}
+        </Chip>
+      </div>
+
+      {calibration && (
+        <div className="mt-3 grid grid-cols-1 gap-1 text-xs text-zinc-400 md:grid-cols-3">
+          <span>Sesgo giro (°/s): {formatVector(calibration.gyro_bias)}</span>
+          <span>Gravedad (g): {formatVector(calibration.gravity)}</span>
+          <span>Escala acel.: {formatVector(calibration.acc_scale)}</span>
+        </div>
+      )}
+
+      {status && (
+        <div className="mt-3 text-sm text-zinc-300">
+          <p>
+            {PHASE_LABELS[status.phase] ?? status.phase}
+            {status.six_orientation &&
+              isActive &&
+              ` · orientaciones ${status.orientations_captured}/6`}
+            {isActive && ` · ${status.samples_collected} muestras`}
+          </p>
+          {status.message && (
+            <p
+              className={
+                status.phase === 'failed'
+                  ? 'text-xs text-red-400'
+                  : 'text-xs text-zinc-400'
+              }
+            >
+              {status.message}
+            </p>
+          )}
+        </div>
+      )}
+
+      <div className="mt-3 flex flex-wrap gap-2">
+        {isActive ? (
+          <>
+            {status?.phase === 'awaiting_orientation' && (
+              <Button
+                size="sm"
+                color="primary"
+                onPress={run(() => captureOrientation(deviceId))}
+              >
+                Capturar orientación
+              </Button>
+            )}
+            <Button
+              size="sm"
+              color="warning"
+              variant="bordered"
+              onPress={run(() => cancelCalibration(deviceId))}
+            >
+              Cancelar
+            </Button>
+          </>
+        ) : (
+          <>
+            <Button
+              size="sm"
+              color="primary"
+              variant="bordered"
+              onPress={run(() => startCalibration(deviceId, false))}
+            >
+              {calibration ? 'Recalibrar' : 'Calibrar'}
+            </Button>
+            <Button
+              size="sm"
+              color="primary"
+              variant="bordered"
+              onPress={run(() => startCalibration(deviceId, true))}
+            >
+              Seis orientaciones
+            </Button>
+            {calibration && (
+              <Button
+                size="sm"
+                color="danger"
+                variant="light"
+                onPress={run(() => clearCalibration(deviceId))}
+              >
+                Borrar
+              </Button>
+            )}
+          </>
+        )}
+      </div>
+    </div>
+  );
+};
+
+// Calibración de las bandas conectadas: resultados guardados y captura en curso
+const DeviceCalibrationPanel: React.FC = () => {
+  const connectedDevices = useBLEStore(state => state.connectedDevices);
+  const availableDevices = useBLEStore(state => state.availableDevices);
+  const calibrations = useCalibrationStore(state => state.calibrations);
+  const statuses = useCalibrationStore(state => state.statuses);
+  const loadCalibrations = useCalibrationStore(state => state.loadCalibrations);
+
+  useEffect(() => {
+    loadCalibrations().catch(() => {
+      // El error ya se registra en el store
+    });
+  }, [loadCalibrations]);
+
+  if (connectedDevices.length === 0) return null;
+
+  return (
+    <Card className="border-zinc-700/50 bg-zinc-800/50">
+      <CardBody className="space-y-4 p-6">
+        <div className="flex items-center gap-2">
+          <Compass size={20} className="text-zinc-300" />
+          <div>
+            <h3 className="text-lg font-bold text-zinc-100">
+              Calibración de bandas
+            </h3>
+            <p className="text-sm text-zinc-400">
+              Con la banda quieta se miden el sesgo del giroscopio y la
+              gravedad; las seis orientaciones ajustan la escala del
+              acelerómetro
+            </p>
+          </div>
+        </div>
+
+        {connectedDevices.map(deviceId => (
+          <CalibrationRow
+            key={deviceId}
+            deviceId={deviceId}
+            deviceName={
+              availableDevices.find(device => device.id === deviceId)?.name ??
+              deviceId
+            }
+            calibration={calibrations[deviceId]}
+            status={statuses[deviceId]}
+          />
+        ))}
+      </CardBody>
+    </Card>
+  );
+};
+
+export default DeviceCalibrationPanel;
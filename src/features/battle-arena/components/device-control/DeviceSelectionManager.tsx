@@ -6,6 +6,7 @@ import { useBLEStore } from '@stores/useBLEStore';
 import { BleDevice } from '@features/battle-arena/types';
 
 import DeviceList from './DeviceList';
+import DeviceCalibrationPanel from './DeviceCalibrationPanel';
 import useBattleStore from '../../stores/useBattleStore';
 import IncompleteConfigWarning from './IncompleteConfigWarning';
 
@@ -15,6 +16,7 @@ const DeviceSelectionManager: React.FC = () => {
   const competitor2 = useBattleStore(state => state.competitor2);
 
   // Store BLE - SOLO para operaciones de hardware
+  const syncRosterCompetitor = useBLEStore(state => state.syncRosterCompetitor);
   const connectToDeviceWithCompetitor = useBLEStore(
     state => state.connectToDeviceWithCompetitor,
   );
@@ -109,14 +111,16 @@ const DeviceSelectionManager: React.FC = () => {
     setIsConnectingCompetitor1(true);
 
     try {
+      if (!competitor1) {
+        throw new Error('Competidor 1 no seleccionado');
+      }
+      // El backend solo conecta competidores de su plantilla con un pesaje
+      const rosterId = await syncRosterCompetitor(competitor1);
       for (const device of competitor1Devices) {
-        const weight = Number(competitor1?.weight || 70);
-
         await connectToDeviceWithCompetitor(
           device.id,
           1, // competitor_id
-          competitor1?.name || 'Competidor 1',
-          weight,
+          rosterId, // id en la plantilla
         );
         devInfoLog(
           `🔴 Conectado dispositivo para ${competitor1?.name}: ${device.name}`,
@@ -140,14 +144,16 @@ const DeviceSelectionManager: React.FC = () => {
     setIsConnectingCompetitor2(true);
 
     try {
+      if (!competitor2) {
+        throw new Error('Competidor 2 no seleccionado');
+      }
+      // El backend solo conecta competidores de su plantilla con un pesaje
+      const rosterId = await syncRosterCompetitor(competitor2);
       for (const device of competitor2Devices) {
-        const weight = Number(competitor2?.weight || 70);
-
         await connectToDeviceWithCompetitor(
           device.id,
           2, // competitor_id
-          competitor2?.name || 'Competidor 2',
-          weight,
+          rosterId, // id en la plantilla
         );
         devInfoLog(
           `🔵 Conectado dispositivo para ${competitor2?.name}: ${device.name}`,
@@ -369,6 +375,9 @@ const DeviceSelectionManager: React.FC = () => {
         )}
       </div>
 
+      {/* Calibración de las bandas conectadas */}
+      <DeviceCalibrationPanel />
+
       <IncompleteConfigWarning
         show={
           competitor1Devices.length === 0 ||
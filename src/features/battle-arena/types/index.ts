@@ -38,8 +38,37 @@ export interface CombatEvent {
 export interface DeviceConnection {
   deviceId: string;
   competitorId: number;
-  competitorName: string;
-  competitorWeight: number;
+  rosterId: string; // Competidor de la plantilla (el peso sale del último pesaje)
+}
+
+// Calibración guardada de una banda (sensor_calibration.rs)
+export interface DeviceCalibration {
+  device_id: string;
+  gyro_bias: [number, number, number]; // °/s
+  acc_bias: [number, number, number]; // g
+  acc_scale: [number, number, number];
+  gravity: [number, number, number]; // g en reposo
+  mount_rotation: number[][];
+  six_orientation: boolean;
+  calibrated_at: number;
+}
+
+export type CalibrationPhase =
+  | 'collecting_still'
+  | 'awaiting_orientation'
+  | 'collecting_orientation'
+  | 'completed'
+  | 'failed'
+  | 'cancelled';
+
+// Progreso de una calibración (evento "calibration-progress")
+export interface CalibrationStatus {
+  device_id: string;
+  phase: CalibrationPhase;
+  six_orientation: boolean;
+  orientations_captured: number;
+  samples_collected: number;
+  message: string | null;
 }
 
 // Definición de la interfaz para la configuración de la batalla
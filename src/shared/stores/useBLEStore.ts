@@ -7,6 +7,7 @@ import {
   CombatEvent,
   DeviceConnection,
 } from '@features/battle-arena/types';
+import { Fighter } from '@features/fighters/models/Fighter';
 
 interface State {
   availableDevices: BleDevice[];
@@ -21,6 +22,9 @@ interface Actions {
   startBLESystem: () => Promise<void>;
   scanDevices: () => Promise<BleDevice[]>;
 
+  // Plantilla del backend (las conexiones la referencian por id)
+  syncRosterCompetitor: (fighter: Fighter) => Promise<string>;
+
   // Conexiones físicas
   connectToDevice: (
     deviceId: string,
@@ -29,8 +33,7 @@ interface Actions {
   connectToDeviceWithCompetitor: (
     deviceId: string,
     competitorId: number,
-    competitorName: string,
-    competitorWeight: number,
+    rosterId: string,
   ) => Promise<void>;
   connectMultipleDevices: (
     connections: DeviceConnection[],
@@ -188,27 +191,43 @@ export const useBLEStore = create<State & Actions>((set, get) => ({
     }
   },
 
-  connectToDeviceWithCompetitor: async (
-    deviceId,
-    competitorId,
-    competitorName,
-    competitorWeight,
-  ) => {
+  syncRosterCompetitor: async fighter => {
     try {
-      await invoke('connect_to_device_with_competitor', {
+      // Crea o actualiza el competidor enlazado al peleador (su peso es el pesaje)
+      const entry = await invoke<{ id: string }>('save_competitor', {
+        profile: {
+          id: null,
+          external_id: fighter.id,
+          name: fighter.name,
+          weight_kg: fighter.weight > 0 ? fighter.weight : null,
+        },
+      });
+      devInfoLog(
+        `👤 ${fighter.name} sincronizado en la plantilla: ${entry.id}`,
+      );
+      return entry.id;
+    } catch (error) {
+      devErrorLog(
+        `❌ Error sincronizando ${fighter.name} con la plantilla:`,
+        error,
+      );
+      throw error;
+    }
+  },
+
+  connectToDeviceWithCompetitor: async (deviceId, competitorId, rosterId) => {
+    try {
+      const result = await invoke<string>('connect_to_device_with_competitor', {
         deviceId,
         competitorId,
-        competitorName,
-        competitorWeight,
+        rosterId,
       });
       const connected = await invoke<string[]>('get_connected_devices');
       set({ connectedDevices: connected });
-      devSuccessLog(
-        `🔗 Dispositivo ${deviceId} conectado para ${competitorName} (${competitorWeight}kg)`,
-      );
+      devSuccessLog(`🔗 ${result}`);
     } catch (error) {
       devErrorLog(
-        `❌ Error conectando dispositivo ${deviceId} para ${competitorName}:`,
+        `❌ Error conectando dispositivo ${deviceId} para ${rosterId}:`,
         error,
       );
       throw error;
@@ -0,0 +1,135 @@
+import { create } from 'zustand';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { devErrorLog, devInfoLog, devSuccessLog } from '@utils/devLog';
+import {
+  CalibrationStatus,
+  DeviceCalibration,
+} from '@features/battle-arena/types';
+
+interface State {
+  calibrations: Record<string, DeviceCalibration>; // Por device_id
+  statuses: Record<string, CalibrationStatus>; // Último progreso por device_id
+}
+
+interface Actions {
+  loadCalibrations: () => Promise<void>;
+  startCalibration: (
+    deviceId: string,
+    sixOrientation: boolean,
+  ) => Promise<void>;
+  captureOrientation: (deviceId: string) => Promise<void>;
+  cancelCalibration: (deviceId: string) => Promise<void>;
+  clearCalibration: (deviceId: string) => Promise<void>;
+  setStatus: (status: CalibrationStatus) => void;
+}
+
+// Variable global para el listener (evita múltiples listeners)
+let progressListener: (() => void) | null = null;
+
+const setupProgressListenerIfNeeded = async () => {
+  if (progressListener) return;
+
+  try {
+    progressListener = await listen<CalibrationStatus>(
+      'calibration-progress',
+      event => {
+        const status = event.payload;
+        devInfoLog('📐 Progreso de calibración:', status);
+        useCalibrationStore.getState().setStatus(status);
+        // Al terminar, el resultado ya está guardado en el backend
+        if (status.phase === 'completed') {
+          useCalibrationStore.getState().loadCalibrations();
+        }
+      },
+    );
+  } catch (error) {
+    devErrorLog('❌ Error configurando listener de calibración:', error);
+  }
+};
+
+export const useCalibrationStore = create<State & Actions>(set => ({
+  calibrations: {},
+  statuses: {},
+
+  loadCalibrations: async () => {
+    await setupProgressListenerIfNeeded();
+
+    try {
+      const calibrations = await invoke<DeviceCalibration[]>(
+        'get_device_calibrations',
+      );
+      set({
+        calibrations: Object.fromEntries(
+          calibrations.map(calibration => [
+            calibration.device_id,
+            calibration,
+          ]),
+        ),
+      });
+    } catch (error) {
+      devErrorLog('❌ Error obteniendo calibraciones:', error);
+      throw error;
+    }
+  },
+
+  startCalibration: async (deviceId, sixOrientation) => {
+    await setupProgressListenerIfNeeded();
+
+    try {
+      const status = await invoke<CalibrationStatus>(
+        'start_device_calibration',
+        { deviceId, sixOrientation },
+      );
+      set(state => ({ statuses: { ...state.statuses, [deviceId]: status } }));
+      devSuccessLog(`📐 Calibración iniciada para ${deviceId}`);
+    } catch (error) {
+      devErrorLog(`❌ Error iniciando calibración de ${deviceId}:`, error);
+      throw error;
+    }
+  },
+
+  captureOrientation: async deviceId => {
+    try {
+      const status = await invoke<CalibrationStatus>(
+        'capture_calibration_orientation',
+        { deviceId },
+      );
+      set(state => ({ statuses: { ...state.statuses, [deviceId]: status } }));
+    } catch (error) {
+      devErrorLog(`❌ Error capturando orientación de ${deviceId}:`, error);
+      throw error;
+    }
+  },
+
+  cancelCalibration: async deviceId => {
+    try {
+      await invoke<string>('cancel_device_calibration', { deviceId });
+    } catch (error) {
+      devErrorLog(`❌ Error cancelando calibración de ${deviceId}:`, error);
+      throw error;
+    }
+  },
+
+  clearCalibration: async deviceId => {
+    try {
+      await invoke<string>('clear_device_calibration', { deviceId });
+      set(state => {
+        const calibrations = { ...state.calibrations };
+        const statuses = { ...state.statuses };
+        delete calibrations[deviceId];
+        delete statuses[deviceId];
+        return { calibrations, statuses };
+      });
+      devSuccessLog(`🔄 Calibración de ${deviceId} eliminada`);
+    } catch (error) {
+      devErrorLog(`❌ Error eliminando calibración de ${deviceId}:`, error);
+      throw error;
+    }
+  },
+
+  setStatus: status =>
+    set(state => ({
+      statuses: { ...state.statuses, [status.device_id]: status },
+    })),
+}));